@@ -0,0 +1,43 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::node::NodeState;
+
+/// Key of the annotation list in the store.
+pub const STORE_KEY: &str = "annotations";
+
+/// Longest note accepted, the lists have to stay one line per note.
+pub const MAX_NOTE_LENGTH: usize = 60;
+
+/// Short note pinned to a block height, or only to a moment when the height is unknown.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub height: Option<u64>,
+    pub time: i64,
+    pub note: String,
+}
+
+impl Annotation {
+    pub fn new(node: &NodeState, note: &str) -> Self {
+        Self {
+            height: Some(node.height).filter(|height| *height > 0),
+            time: Utc::now().timestamp(),
+            note: note.trim().to_string(),
+        }
+    }
+}
+
+/// Index of the first sample taken after each note, for marking the notes on a chart
+/// of samples taken at `times`. Notes older than the first sample are left out.
+pub fn sample_marks(annotations: &[Annotation], times: &[i64]) -> Vec<usize> {
+    let Some(first) = times.first() else {
+        return Vec::new();
+    };
+
+    annotations
+        .iter()
+        .filter(|annotation| annotation.time >= *first)
+        .map(|annotation| times.partition_point(|time| *time < annotation.time))
+        .filter(|index| *index < times.len())
+        .collect()
+}
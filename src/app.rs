@@ -1,18 +1,70 @@
+use chrono::Utc;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::style::Print;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::Path;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use std::{env, error};
 use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant};
 use tokio_util::sync::CancellationToken;
 use tokio_util::task::TaskTracker;
 
+use crate::annotations::{Annotation, MAX_NOTE_LENGTH, STORE_KEY as ANNOTATIONS_KEY};
+use crate::badges::Badge;
 use crate::config::AppConfig;
-use crate::event::Event;
+use crate::daily::{DailyStats, DayStats, STORE_KEY as DAILY_KEY};
+use crate::diagnostics::Diagnostics;
+use crate::disk::{DiskGrowth, GB, STORE_KEY as DISK_KEY};
+use crate::event::{Event, MarketEvent, NodeEvent, SystemEvent, UiEvent};
+use crate::explorer::{spawn_explorer_fetch, ExplorerTip, CHECK_INTERVAL as EXPLORER_INTERVAL};
+use crate::fees::providers::bitcoin_core::FeesBitcoinCore;
+use crate::fees::providers::esplora::FeesEsplora;
+use crate::fees::providers::mempool_space::FeesMempoolSpace;
 use crate::fees::providers::FeesBlockchainInfo;
-use crate::fees::{spawn_fees_checker, FeesState};
-use crate::node::{Node, NodeProvider, NodeState};
-use crate::price::providers::coinbase::PriceCoinbase;
-use crate::price::{spawn_price_checker, PriceCurrency, PriceState};
+use crate::fees::{spawn_fees_checker, FeeServiceProvider, FeesState, NamedProvider, HISTORY_LEN};
+use crate::host::{spawn_host_checker, HostState};
+use crate::node::widgets::VirtualListState;
+use crate::node::{
+    Node, NodeCommand, NodeProvider, NodeState, NodeStatus, BLOCK_TIMES_KEY, BLOCK_TIMES_WINDOW,
+};
+use crate::notify::Notifier;
+use crate::preflight::{spawn_test_node, Check};
+use crate::price::ath::{spawn_ath_fetch, ATH_SOURCES};
+use crate::price::providers as price_providers;
+use crate::price::{
+    spawn_price_checker, PriceAlert, PriceCommand, PriceCurrency, PriceState,
+    CHECK_INTERVAL as PRICE_INTERVAL,
+};
+use crate::quiet::{is_quiet, QuietWindow};
+use crate::record::spawn_replay;
+use crate::script::{Script, ScriptState};
+use crate::session::{LastSeen, SessionState, SinceLastSeen};
+use crate::snapshot::{Snapshot, SNAPSHOT_INTERVAL};
+use crate::store::Store;
+use crate::ui::banner::Banner;
+
+/// How long the startup summary stays when there is nothing to warn about.
+const SPLASH_DURATION: Duration = Duration::from_secs(3);
+
+/// How long the node gets to answer before the explorer stands in for it.
+const EXPLORER_GRACE: Duration = Duration::from_secs(30);
+
+/// Up to 9999 blocks per `m`, mining is quick on regtest but not instant.
+const MAX_MINE_DIGITS: usize = 4;
+
+/// Longest peer address that can be typed, enough for an onion address and port.
+const MAX_PEER_ADDRESS_LENGTH: usize = 72;
+
+/// The statistics of the day are written out this often, and on quitting.
+const DAILY_SAVE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// A new all time high is written out at most this often, and on quitting, not on every
+/// streamed price of a rally.
+const ATH_SAVE_INTERVAL: Duration = Duration::from_secs(60);
 
 /// Application result type.
 pub type AppResult<T> = std::result::Result<T, Box<dyn error::Error>>;
@@ -34,11 +86,179 @@ impl AppThread {
     }
 }
 
+/// Panels that can receive focus, in the order Tab cycles through them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Panel {
+    Node,
+    Peers,
+    Fees,
+    /// Backlog from the fee provider.
+    Mempool,
+    /// Fee histogram of the projected next block.
+    NextBlock,
+    Price,
+    Session,
+    /// Lines from the user script.
+    Custom,
+}
+
+impl FromStr for Panel {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> anyhow::Result<Panel> {
+        match input {
+            "node" => Ok(Panel::Node),
+            "peers" => Ok(Panel::Peers),
+            "fees" => Ok(Panel::Fees),
+            "mempool" => Ok(Panel::Mempool),
+            "next_block" => Ok(Panel::NextBlock),
+            "price" => Ok(Panel::Price),
+            "session" => Ok(Panel::Session),
+            "custom" => Ok(Panel::Custom),
+            _ => Err(anyhow::Error::msg("Unknown panel")),
+        }
+    }
+}
+
+/// Bottom panel currently shown when they take turns instead of sharing the row.
+pub struct Rotation {
+    pub current: Option<Panel>,
+    pub rotated_at: Instant,
+}
+
+/// Copies of the panels data taken when they were paused.
+#[derive(Default)]
+pub struct PausedState {
+    pub node: Option<NodeState>,
+    pub peers: Option<NodeState>,
+    pub fees: Option<FeesState>,
+    pub mempool: Option<FeesState>,
+    pub next_block: Option<FeesState>,
+    pub price: Option<PriceState>,
+    pub session: Option<SessionState>,
+    pub custom: Option<ScriptState>,
+}
+
+impl PausedState {
+    pub fn is_paused(&self, panel: Panel) -> bool {
+        match panel {
+            Panel::Node => self.node.is_some(),
+            Panel::Peers => self.peers.is_some(),
+            Panel::Fees => self.fees.is_some(),
+            Panel::Mempool => self.mempool.is_some(),
+            Panel::NextBlock => self.next_block.is_some(),
+            Panel::Price => self.price.is_some(),
+            Panel::Session => self.session.is_some(),
+            Panel::Custom => self.custom.is_some(),
+        }
+    }
+}
+
+/// Full screen views opened on top of the dashboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailView {
+    BlockHeatmap,
+    DifficultyEpochs,
+    SinceLastSeen,
+    /// Statistics of the day that just ended.
+    DaySummary,
+    Annotations,
+    Preflight,
+}
+
+/// Mutating action waiting for the user to confirm it.
+#[derive(Debug, Clone)]
+pub struct PendingAction {
+    pub description: String,
+    pub command: NodeCommand,
+}
+
+/// Which list the peers panel shows and where its cursor is.
+#[derive(Default)]
+pub struct PeersView {
+    pub banned: bool,
+    pub list: Cell<VirtualListState>,
+}
+
 pub struct AppState {
     pub counter: u8,
+    pub focus: Panel,
+    /// Price in the main currency.
     pub price: PriceState,
+    /// Price in each of the other `price.currencies`.
+    pub other_prices: HashMap<PriceCurrency, PriceState>,
+    /// Currency the price panel shows, switched with a key.
+    pub shown_currency: PriceCurrency,
+    /// Sats per unit of the currency instead of the price, also switched with a key.
+    pub price_in_sats: bool,
+    /// Levels watched on the main price, each one dropped once crossed.
+    pub price_alerts: Vec<PriceAlert>,
+    /// Last alert crossed, at what price and when, shown for a while.
+    pub price_alert: Option<(PriceAlert, f64, Instant)>,
     pub fees: FeesState,
+    pub host: HostState,
     pub node: Option<Arc<Mutex<NodeState>>>,
+    pub session: SessionState,
+    pub script: ScriptState,
+    pub paused: PausedState,
+    pub peers: PeersView,
+    pub detail: Option<DetailView>,
+    pub rotation: Rotation,
+    /// Previous session values, kept until the first live ones arrive to compare them.
+    pub last_seen: Option<LastSeen>,
+    pub since_last_seen: Option<SinceLastSeen>,
+    pub badges: Vec<Badge>,
+    pub quiet: Vec<QuietWindow>,
+    /// Labels of the badges whose condition held on the last tick, or that turned on
+    /// during a quiet window and were not pushed yet.
+    pub active_badges: Vec<String>,
+    /// Outcome of the last test push and when it arrived.
+    pub test_notification: Option<(bool, Instant)>,
+    /// Why the last key did nothing, e.g. an action outside control mode, and when.
+    pub notice: Option<(String, Instant)>,
+    pub annotations: Vec<Annotation>,
+    /// Note being typed, keys go to it until it is saved or cancelled.
+    pub note_input: Option<String>,
+    /// Number of blocks to mine being typed, on regtest or signet in control mode.
+    pub mine_input: Option<String>,
+    /// Address of a peer to connect to being typed, in control mode.
+    pub peer_input: Option<String>,
+    /// When the startup summary was opened, it stays while there are warnings.
+    pub splash: Option<Instant>,
+    pub diagnostics: Diagnostics,
+    pub disk: DiskGrowth,
+    /// Chain tip from `explorer.url`, only kept while the node is offline.
+    pub explorer: Option<ExplorerTip>,
+    pub explorer_requested_at: Option<Instant>,
+    /// Whether the disk limit was already within the warning window on the last tick.
+    pub disk_warned: bool,
+    /// Results of the last connection test, none while it runs.
+    pub preflight: Option<Vec<Check>>,
+    pub warnings: Vec<String>,
+    pub banner: Banner,
+    pub pending_action: Option<PendingAction>,
+    /// Values of the last session, shown until each source answers.
+    pub restored: Option<Snapshot>,
+    pub snapshot_at: Instant,
+    pub daily: DailyStats,
+    /// Date of the last tick, to tell when the day changes while running.
+    pub day: Option<String>,
+    pub day_summary: Option<DayStats>,
+    pub daily_saved_at: Instant,
+    /// Whether an all time high was raised since they were last saved.
+    pub ath_unsaved: bool,
+    pub ath_saved_at: Instant,
+    /// Height and time of the newest block time written to the store.
+    pub block_times_saved: Option<(u64, i64)>,
+}
+
+impl AppState {
+    /// Price in the currency the panel shows.
+    pub fn shown_price(&self) -> &PriceState {
+        self.other_prices
+            .get(&self.shown_currency)
+            .unwrap_or(&self.price)
+    }
 }
 
 pub struct App {
@@ -46,6 +266,13 @@ pub struct App {
     pub thread: AppThread,
     pub config: AppConfig,
     pub state: AppState,
+    pub store: Store,
+    /// Whether a push service is configured. Pushes are published as events, the
+    /// notifier follows them on the bus.
+    pub pushes: bool,
+    pub script: Option<Script>,
+    /// Requests to the price checkers, one per currency, once they run.
+    pub price_commands: Vec<mpsc::UnboundedSender<PriceCommand>>,
     pub running: bool,
 }
 
@@ -54,39 +281,572 @@ impl App {
         let (args, argv) = argmap::parse(env::args());
         let config = AppConfig::new(args, argv).unwrap();
         let cloned_thread = thread.clone();
+        let store = Store::open();
+        let rotation = Rotation {
+            current: App::rotation_panels(&config).first().copied(),
+            rotated_at: Instant::now(),
+        };
+        let last_seen = store.get("last_seen");
+        // Badges with a condition that does not parse are left out
+        let badges = config
+            .badges
+            .iter()
+            .filter_map(|badge| Badge::try_from(badge).ok())
+            .collect();
+        let quiet = config
+            .quiet
+            .iter()
+            .filter_map(|window| QuietWindow::try_from(window).ok())
+            .collect();
+        let banner = Banner::from_config(&config);
+        let pushes = Notifier::from_config(&config).is_some();
+        let annotations = store.get(ANNOTATIONS_KEY).unwrap_or_default();
+        let disk = DiskGrowth::new(store.get(DISK_KEY).unwrap_or_default());
+        let daily = DailyStats::new(store.get(DAILY_KEY).unwrap_or_default());
+        let warnings = config.warnings();
+        let splash = config.ui.splash.then(Instant::now);
+        let restored = match config.snapshot.enabled {
+            true => Snapshot::load(),
+            false => None,
+        };
+        let currencies = config.price_currencies();
+        let price_in_sats = config.price.unit == "sats";
+        let price_alerts = config.price_alerts();
+        let main_currency = currencies.first().copied().unwrap_or(PriceCurrency::USD);
+        let price = PriceState {
+            currency: main_currency,
+            ath: App::seed_ath(&config, &store),
+            ..PriceState::new()
+        };
+        let other_prices = currencies
+            .iter()
+            .skip(1)
+            .map(|currency| {
+                let state = PriceState {
+                    currency: *currency,
+                    ath: store.get(&App::ath_key(currency)),
+                    ..PriceState::new()
+                };
+                (*currency, state)
+            })
+            .collect();
+        let mut script_state = ScriptState::default();
+        let script = match config.script.path.is_empty() {
+            true => None,
+            false => Script::load(Path::new(&config.script.path))
+                .map_err(|e| script_state.error = Some(e.to_string()))
+                .ok(),
+        };
         Self {
             running: true,
             config,
             thread,
             node: Node::new(cloned_thread),
+            store,
+            pushes,
+            script,
+            price_commands: vec![],
             state: AppState {
                 counter: 0,
-                price: PriceState::new(),
+                focus: Panel::Node,
+                price,
+                other_prices,
+                shown_currency: main_currency,
+                price_in_sats,
+                price_alerts,
+                price_alert: None,
                 fees: FeesState::new(),
+                host: HostState::default(),
                 node: Some(NodeState::new()),
+                session: SessionState::new(),
+                script: script_state,
+                paused: PausedState::default(),
+                peers: PeersView::default(),
+                detail: None,
+                rotation,
+                last_seen,
+                since_last_seen: None,
+                badges,
+                quiet,
+                active_badges: vec![],
+                test_notification: None,
+                notice: None,
+                preflight: None,
+                annotations,
+                note_input: None,
+                mine_input: None,
+                peer_input: None,
+                splash,
+                diagnostics: Diagnostics::default(),
+                disk,
+                explorer: None,
+                explorer_requested_at: None,
+                disk_warned: false,
+                warnings,
+                banner,
+                pending_action: None,
+                restored,
+                snapshot_at: Instant::now(),
+                daily,
+                day: None,
+                day_summary: None,
+                daily_saved_at: Instant::now(),
+                ath_unsaved: false,
+                ath_saved_at: Instant::now(),
+                block_times_saved: None,
             },
         }
     }
 
+    /// Starts the provider, with the block times of the last week loaded from the store so
+    /// only the headers of newer blocks are fetched.
     pub fn init_node(&mut self, provider: Box<dyn NodeProvider + Send>) {
-        self.state.node = Some(provider.get_state());
+        let node = provider.get_state();
+        let cutoff = Utc::now().timestamp() - BLOCK_TIMES_WINDOW;
+        let block_times: BTreeMap<u64, i64> = self.store.get(BLOCK_TIMES_KEY).unwrap_or_default();
+
+        node.lock().unwrap().block_times = block_times
+            .into_iter()
+            .filter(|(_, time)| *time >= cutoff)
+            .collect();
+
+        self.state.node = Some(node);
         self.node.init(provider);
     }
 
+    /// One checker per currency. Unsupported currencies are reported on the splash screen.
     pub fn init_price(&mut self) {
-        spawn_price_checker::<PriceCoinbase>(
-            self.thread.clone(),
-            PriceCurrency::from_str(&self.config.price.currency).unwrap(),
-        );
+        for currency in self.config.price_currencies() {
+            let providers = price_providers::from_config(&self.config);
+            let (sender, receiver) = mpsc::unbounded_channel();
+            self.price_commands.push(sender);
+
+            spawn_price_checker(
+                self.thread.clone(),
+                currency,
+                providers,
+                receiver,
+                self.config.price.aggregate == "vwap",
+                self.config.price_stream_url(&currency),
+            );
+        }
+    }
+
+    /// Seeds the all time high of every currency from `price.ath_source`, once.
+    pub fn init_ath(&mut self) {
+        if ATH_SOURCES.contains(&self.config.price.ath_source.as_str()) {
+            spawn_ath_fetch(self.thread.clone(), self.config.price_currencies());
+        }
+    }
+
+    /// Switches the price to the next of `price.providers`, in every currency.
+    pub fn next_price_provider(&mut self) {
+        if self.config.price_providers().len() < 2 || self.config.price.aggregate == "vwap" {
+            return;
+        }
+
+        for commands in self.price_commands.iter() {
+            let _ = commands.send(PriceCommand::NextProvider);
+        }
+    }
+
+    /// Shows the price in the next of `price.currencies`.
+    pub fn next_currency(&mut self) {
+        let currencies = self.config.price_currencies();
+
+        if let Some(i) = currencies
+            .iter()
+            .position(|c| *c == self.state.shown_currency)
+        {
+            self.state.shown_currency = currencies[(i + 1) % currencies.len()];
+        }
     }
 
     pub fn init_fees(&mut self) {
-        spawn_fees_checker::<FeesBlockchainInfo>(self.thread.clone());
+        let config = &self.config;
+        let providers: Vec<NamedProvider> = config
+            .fee_providers()
+            .iter()
+            .map(|name| -> NamedProvider {
+                match name.as_str() {
+                    "bitcoin_core" => (name.clone(), Box::new(FeesBitcoinCore::new(config))),
+                    "esplora" => (name.clone(), Box::new(FeesEsplora::new(config))),
+                    "mempool_space" => (name.clone(), Box::new(FeesMempoolSpace::new(config))),
+                    _ => (
+                        "blockchain_info".to_string(),
+                        Box::new(FeesBlockchainInfo::new(config)),
+                    ),
+                }
+            })
+            .collect();
+
+        spawn_fees_checker(self.thread.clone(), config, providers);
+    }
+
+    pub fn init_host(&mut self) {
+        spawn_host_checker(self.thread.clone(), &self.config);
+    }
+
+    /// Plays `replay` back in place of every source. Nothing of it is live, so no pushes
+    /// go out, nothing is persisted or restored and the explorer is not asked for the tip.
+    pub fn init_replay(&mut self) {
+        self.pushes = false;
+        self.store = Store::default();
+        self.config.explorer.url.clear();
+        self.config.snapshot.enabled = false;
+        self.state.restored = None;
+
+        if let Some(node) = &self.state.node {
+            spawn_replay(
+                self.thread.clone(),
+                self.config.replay.clone(),
+                self.config.replay_speed,
+                node.clone(),
+            );
+        }
+    }
+
+    pub fn tick(&mut self) {
+        let blocks_seen = self.state.session.blocks_seen;
+        if let Some(node) = &self.state.node {
+            self.state.session.observe_node(&node.lock().unwrap());
+        }
+        self.observe_day(self.state.session.blocks_seen - blocks_seen);
+
+        self.close_splash();
+        self.rotate();
+        self.compare_last_seen();
+        self.notify_badges();
+        self.observe_disk();
+        self.fall_back_to_explorer();
+        self.run_script();
+        self.save_snapshot();
+        self.save_ath(false);
+        self.save_block_times();
+    }
+
+    /// Today's date, starting a new day in the statistics when it changed. A day ending
+    /// while running is summed up on screen with `ui.day_summary`. Replays are not days.
+    fn today(&mut self) -> Option<String> {
+        if !self.config.replay.is_empty() {
+            return None;
+        }
+
+        let date = self.config.date(Utc::now().timestamp())?;
+        let ended = self.state.daily.roll(&date);
+        let changed = self
+            .state
+            .day
+            .replace(date.clone())
+            .is_some_and(|day| day != date);
+
+        if let (true, Some(ended)) = (changed, ended) {
+            self.store.set(DAILY_KEY, &self.state.daily.days);
+            self.state.daily_saved_at = Instant::now();
+
+            if self.config.ui.day_summary {
+                self.state.day_summary = Some(ended);
+                if self.state.detail.is_none() {
+                    self.state.detail = Some(DetailView::DaySummary);
+                }
+            }
+        }
+
+        Some(date)
+    }
+
+    /// Adds the blocks seen since the last tick to today, and saves the statistics every
+    /// [`DAILY_SAVE_INTERVAL`].
+    fn observe_day(&mut self, blocks: u64) {
+        let Some(date) = self.today() else {
+            return;
+        };
+
+        if blocks > 0 {
+            self.state.daily.observe_blocks(&date, blocks);
+        }
+
+        if self.state.daily_saved_at.elapsed() >= DAILY_SAVE_INTERVAL {
+            self.store.set(DAILY_KEY, &self.state.daily.days);
+            self.state.daily_saved_at = Instant::now();
+        }
+    }
+
+    /// Writes out the block times whenever the newest one changed, a new block or a reorg.
+    fn save_block_times(&mut self) {
+        let Some(node) = &self.state.node else {
+            return;
+        };
+
+        let node = node.lock().unwrap();
+        let newest = node
+            .block_times
+            .last_key_value()
+            .map(|(height, time)| (*height, *time));
+
+        if newest.is_some() && newest != self.state.block_times_saved {
+            self.store.set(BLOCK_TIMES_KEY, &node.block_times);
+            self.state.block_times_saved = newest;
+        }
+    }
+
+    /// Writes out the all time highs raised since the last time, at most every
+    /// [`ATH_SAVE_INTERVAL`] unless `now`.
+    fn save_ath(&mut self, now: bool) {
+        if !self.state.ath_unsaved
+            || (!now && self.state.ath_saved_at.elapsed() < ATH_SAVE_INTERVAL)
+        {
+            return;
+        }
+
+        let prices = std::iter::once(&self.state.price).chain(self.state.other_prices.values());
+        for price in prices {
+            if let Some(ath) = price.ath {
+                self.store.set(&App::ath_key(&price.currency), ath);
+            }
+        }
+
+        self.state.ath_unsaved = false;
+        self.state.ath_saved_at = Instant::now();
+    }
+
+    /// Writes the values on screen out every [`SNAPSHOT_INTERVAL`], keeping the restored
+    /// ones of sources that did not answer yet.
+    fn save_snapshot(&mut self) {
+        if !self.config.snapshot.enabled || self.state.snapshot_at.elapsed() < SNAPSHOT_INTERVAL {
+            return;
+        }
+
+        self.state.snapshot_at = Instant::now();
+        let restored = self.state.restored.as_ref();
+
+        let node = self
+            .state
+            .node
+            .as_ref()
+            .map(|node| node.lock().unwrap().clone())
+            .filter(|node| !node.last_hash.is_empty())
+            .or_else(|| restored.and_then(|restored| restored.node.clone()));
+        let price = Some(&self.state.price)
+            .filter(|price| price.last_price_in_currency.is_some())
+            .cloned()
+            .or_else(|| restored.and_then(|restored| restored.price.clone()));
+        let fees = Some(&self.state.fees)
+            .filter(|fees| !fees.result.rates.is_empty())
+            .cloned()
+            .or_else(|| restored.and_then(|restored| restored.fees.clone()));
+
+        if node.is_some() || price.is_some() || fees.is_some() {
+            Snapshot::new(node, price, fees).save();
+        }
+    }
+
+    /// Asks the explorer for the tip every minute while the node is offline or starting,
+    /// and forgets it as soon as the node answers again.
+    fn fall_back_to_explorer(&mut self) {
+        if self.config.explorer.url.is_empty() {
+            return;
+        }
+
+        let offline = self.state.node.as_ref().is_some_and(|node| {
+            matches!(
+                node.lock().unwrap().status,
+                NodeStatus::Offline | NodeStatus::Starting
+            )
+        });
+
+        // The node starts offline, give it a chance to answer first
+        if !offline || self.state.session.started.elapsed() < EXPLORER_GRACE {
+            self.state.explorer = None;
+            self.state.explorer_requested_at = None;
+            return;
+        }
+
+        if self
+            .state
+            .explorer_requested_at
+            .is_none_or(|requested_at| requested_at.elapsed() >= EXPLORER_INTERVAL)
+        {
+            self.state.explorer_requested_at = Some(Instant::now());
+            spawn_explorer_fetch(self.thread.clone(), self.config.clone());
+        }
     }
 
-    pub fn tick(&mut self) {}
+    /// Keeps an hourly history of the chain size and pushes a warning when the
+    /// projected days until the limit first drop under `disk.warn_days`.
+    fn observe_disk(&mut self) {
+        let Some(size) = self
+            .state
+            .node
+            .as_ref()
+            .and_then(|node| node.lock().unwrap().size_on_disk)
+        else {
+            return;
+        };
+
+        if self.state.disk.observe(size, Utc::now().timestamp()) {
+            self.store.set(DISK_KEY, &self.state.disk.samples);
+        }
+
+        let days_left = self.state.disk.days_left(self.config.disk.limit_gb * GB);
+        let warn = days_left.is_some_and(|days| days < self.config.disk.warn_days as f64);
+
+        if warn && !self.state.disk_warned {
+            // Held back until the quiet window closes
+            if self.is_quiet("Disk") {
+                return;
+            }
+
+            self.state.session.alerts += 1;
+            self.push(
+                "Disk".to_string(),
+                format!(
+                    "About {:.0} days until the {} GB limit",
+                    days_left.unwrap_or_default(),
+                    self.config.disk.limit_gb
+                ),
+            );
+        }
+
+        self.state.disk_warned = warn;
+    }
+
+    fn run_script(&mut self) {
+        let Some(script) = &self.script else {
+            return;
+        };
+
+        match script.run(&self.config, &self.state) {
+            Ok(lines) => {
+                self.state.script = ScriptState { lines, error: None };
+            }
+            Err(e) => self.state.script.error = Some(e.to_string()),
+        }
+    }
+
+    /// Hands a push to the notifier, when there is one.
+    fn push(&self, title: String, message: String) {
+        if self.pushes {
+            let _ = self
+                .thread
+                .sender
+                .send(SystemEvent::Push(title, message).into());
+        }
+    }
+
+    fn send_test_notification(&mut self) {
+        match self.pushes {
+            true => {
+                let _ = self.thread.sender.send(SystemEvent::TestPush.into());
+            }
+            false => self.state.test_notification = Some((false, Instant::now())),
+        }
+    }
+
+    fn test_connection(&mut self) {
+        self.state.preflight = None;
+        self.state.detail = Some(DetailView::Preflight);
+        spawn_test_node(self.thread.clone(), self.config.clone());
+    }
+
+    /// Whether pushes titled `alert` are held back by a quiet window right now.
+    fn is_quiet(&self, alert: &str) -> bool {
+        self.config
+            .locale()
+            .to_datetime(Utc::now().timestamp())
+            .is_some_and(|now| is_quiet(&self.state.quiet, alert, &now))
+    }
+
+    /// Pushes a notification when a badge turns on, not for as long as it stays on. One
+    /// turning on during a quiet window is pushed when it closes, if still on by then.
+    fn notify_badges(&mut self) {
+        let Some(node) = &self.state.node else {
+            return;
+        };
+        let node = node.lock().unwrap();
+
+        let active: Vec<&Badge> = self
+            .state
+            .badges
+            .iter()
+            .filter(|badge| badge.is_active(&node, &self.state.fees, &self.state.price))
+            .filter(|badge| {
+                self.state.active_badges.contains(&badge.label) || !self.is_quiet(&badge.label)
+            })
+            .collect();
+
+        self.state.session.alerts += active
+            .iter()
+            .filter(|badge| !self.state.active_badges.contains(&badge.label))
+            .count() as u64;
+
+        for badge in &active {
+            if self.state.active_badges.contains(&badge.label) {
+                continue;
+            }
+
+            let value = badge
+                .metric
+                .value(&node, &self.state.fees, &self.state.price)
+                .unwrap_or_default();
+
+            self.push(
+                badge.label.clone(),
+                format!("{} (now {})", badge.when, value),
+            );
+        }
+
+        self.state.active_badges = active.iter().map(|badge| badge.label.clone()).collect();
+    }
+
+    /// The summary goes away on its own unless something needs attention.
+    fn close_splash(&mut self) {
+        if self.state.splash.is_some_and(|opened| {
+            self.state.warnings.is_empty() && opened.elapsed() >= SPLASH_DURATION
+        }) {
+            self.state.splash = None;
+        }
+    }
+
+    /// Opens the summary of what changed since the previous session once the node
+    /// answered, and the price too unless it takes too long.
+    fn compare_last_seen(&mut self) {
+        let (Some(last_seen), Some(node)) = (&self.state.last_seen, &self.state.node) else {
+            return;
+        };
+
+        let now = LastSeen::capture(&node.lock().unwrap(), &self.state.price);
+        let price_ready = !self.config.price.enabled
+            || now.price.is_some()
+            || self.state.session.started.elapsed().as_secs() > 30;
+
+        if now.height.is_none() || !price_ready {
+            return;
+        }
+
+        self.state.since_last_seen = Some(SinceLastSeen::compare(last_seen, &now));
+        self.state.last_seen = None;
+
+        if self.state.detail.is_none() {
+            self.state.detail = Some(DetailView::SinceLastSeen);
+        }
+    }
 
     pub fn quit(&mut self) {
+        if let Some(node) = &self.state.node {
+            let last_seen = LastSeen::capture(&node.lock().unwrap(), &self.state.price);
+
+            // Nothing worth comparing next time if the node never answered
+            if last_seen.height.is_some() {
+                self.store.set("last_seen", last_seen);
+            }
+        }
+
+        if self.config.replay.is_empty() {
+            self.store.set(DAILY_KEY, &self.state.daily.days);
+        }
+        self.save_ath(true);
+
         self.running = false;
     }
 
@@ -102,24 +862,716 @@ impl App {
         }
     }
 
-    pub fn handle_price_update(&mut self, state: PriceState) {
-        self.state.price = state;
+    /// Enabled bottom panels, in layout order.
+    fn bottom_panels(config: &AppConfig) -> Vec<Panel> {
+        let mut panels = vec![];
+
+        if config.fees.enabled {
+            panels.push(Panel::Fees);
+        }
+
+        if config.fees.enabled && config.mempool.enabled {
+            panels.push(Panel::Mempool);
+        }
+
+        if config.fees.enabled && config.mempool.next_block {
+            panels.push(Panel::NextBlock);
+        }
+
+        if config.price.enabled {
+            panels.push(Panel::Price);
+        }
+
+        if config.session.enabled {
+            panels.push(Panel::Session);
+        }
+
+        if !config.script.path.is_empty() {
+            panels.push(Panel::Custom);
+        }
+
+        panels
+    }
+
+    /// Enabled bottom panels listed in `rotation.panels`, in the configured order.
+    fn rotation_panels(config: &AppConfig) -> Vec<Panel> {
+        let enabled = App::bottom_panels(config);
+
+        config
+            .rotation
+            .panels
+            .iter()
+            .filter_map(|panel| Panel::from_str(panel).ok())
+            .filter(|panel| enabled.contains(panel))
+            .collect()
     }
 
-    pub fn handle_fee_update(&mut self, state: FeesState) {
+    /// Shows the next bottom panel once the interval is over, focus follows it.
+    pub fn rotate(&mut self) {
+        let rotation = &mut self.state.rotation;
+
+        let Some(current) = rotation.current else {
+            return;
+        };
+
+        if rotation.rotated_at.elapsed().as_secs() < self.config.rotation.interval {
+            return;
+        }
+
+        let panels = App::rotation_panels(&self.config);
+        let next = panels
+            .iter()
+            .position(|panel| *panel == current)
+            .map_or(panels[0], |i| panels[(i + 1) % panels.len()]);
+
+        rotation.current = Some(next);
+        rotation.rotated_at = Instant::now();
+
+        if self.state.focus == current {
+            self.state.focus = next;
+        }
+    }
+
+    /// Panels currently on screen, in layout order.
+    pub fn panels(&self) -> Vec<Panel> {
+        let mut panels = vec![Panel::Node];
+
+        if self.config.peers.enabled {
+            panels.push(Panel::Peers);
+        }
+
+        match self.state.rotation.current {
+            Some(current) => panels.push(current),
+            None => panels.extend(App::bottom_panels(&self.config)),
+        }
+
+        panels
+    }
+
+    pub fn focus_next(&mut self) {
+        let panels = self.panels();
+        let current = panels.iter().position(|p| *p == self.state.focus);
+        self.state.focus = panels[current.map_or(0, |i| (i + 1) % panels.len())];
+    }
+
+    pub fn focus_previous(&mut self) {
+        let panels = self.panels();
+        let current = panels.iter().position(|p| *p == self.state.focus);
+        self.state.focus = panels[current.map_or(0, |i| (i + panels.len() - 1) % panels.len())];
+    }
+
+    /// Freezes or resumes what the focused panel displays, data keeps being collected.
+    pub fn toggle_pause(&mut self) {
+        let state = &mut self.state;
+        let paused = &mut state.paused;
+
+        match state.focus {
+            Panel::Node => {
+                paused.node = match paused.node {
+                    Some(_) => None,
+                    None => state.node.as_ref().map(|node| node.lock().unwrap().clone()),
+                }
+            }
+            Panel::Peers => {
+                paused.peers = match paused.peers {
+                    Some(_) => None,
+                    None => state.node.as_ref().map(|node| node.lock().unwrap().clone()),
+                }
+            }
+            Panel::Fees => {
+                paused.fees = match paused.fees {
+                    Some(_) => None,
+                    None => Some(state.fees.clone()),
+                }
+            }
+            Panel::Mempool => {
+                paused.mempool = match paused.mempool {
+                    Some(_) => None,
+                    None => Some(state.fees.clone()),
+                }
+            }
+            Panel::NextBlock => {
+                paused.next_block = match paused.next_block {
+                    Some(_) => None,
+                    None => Some(state.fees.clone()),
+                }
+            }
+            Panel::Price => {
+                paused.price = match paused.price {
+                    Some(_) => None,
+                    None => Some(state.price.clone()),
+                }
+            }
+            Panel::Session => {
+                paused.session = match paused.session {
+                    Some(_) => None,
+                    None => Some(state.session.clone()),
+                }
+            }
+            Panel::Custom => {
+                paused.custom = match paused.custom {
+                    Some(_) => None,
+                    None => Some(state.script.clone()),
+                }
+            }
+        }
+    }
+
+    /// Peers panel contents as currently displayed, paused or live.
+    fn displayed_peers(&self) -> Option<NodeState> {
+        match &self.state.paused.peers {
+            Some(node) => Some(node.clone()),
+            None => self
+                .state
+                .node
+                .as_ref()
+                .map(|node| node.lock().unwrap().clone()),
+        }
+    }
+
+    pub fn handle_peers_key(&mut self, code: KeyCode) {
+        let Some(node) = self.displayed_peers() else {
+            return;
+        };
+
+        let view = &mut self.state.peers;
+        let len = if view.banned {
+            node.banned.len()
+        } else {
+            node.peers.len()
+        };
+        let mut list = view.list.get();
+
+        match code {
+            KeyCode::Down => list.next(len),
+            KeyCode::Up => list.previous(),
+            KeyCode::PageDown => (0..10).for_each(|_| list.next(len)),
+            KeyCode::PageUp => (0..10).for_each(|_| list.previous()),
+            KeyCode::Home => list.first(),
+            KeyCode::End => list.last(len),
+            KeyCode::Char('b') => {
+                view.banned = !view.banned;
+                list = VirtualListState::new();
+            }
+            KeyCode::Char('d') if !view.banned => {
+                if let Some(peer) = list.selected().and_then(|i| node.peers.get(i)) {
+                    self.request_action(PendingAction {
+                        description: format!("Disconnect peer {} ({})?", peer.id, peer.address),
+                        command: NodeCommand::DisconnectPeer(peer.id),
+                    });
+                }
+            }
+            KeyCode::Char('+') => match self.config.control_mode {
+                true => self.state.peer_input = Some(String::new()),
+                false => self.notice_control_mode(),
+            },
+            _ => {}
+        }
+
+        self.state.peers.list.set(list);
+    }
+
+    /// Asks for confirmation of a mutating action, only allowed in control mode.
+    pub fn request_action(&mut self, action: PendingAction) {
+        match self.config.control_mode {
+            true => self.state.pending_action = Some(action),
+            false => self.notice_control_mode(),
+        }
+    }
+
+    /// Tells why a mutating key did nothing, rather than ignoring it.
+    fn notice_control_mode(&mut self) {
+        self.state.notice = Some(("NEEDS control_mode = true".to_string(), Instant::now()));
+    }
+
+    fn handle_pending_action_key(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                if let Some(action) = self.state.pending_action.take() {
+                    self.node.send(action.command);
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                self.state.pending_action = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_note_key(&mut self, key_event: KeyEvent) {
+        let Some(input) = &mut self.state.note_input else {
+            return;
+        };
+
+        match key_event.code {
+            KeyCode::Char(c) if input.chars().count() < MAX_NOTE_LENGTH => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => {
+                let input = self.state.note_input.take().unwrap_or_default();
+
+                if let (false, Some(node)) = (input.trim().is_empty(), &self.state.node) {
+                    let annotation = Annotation::new(&node.lock().unwrap(), &input);
+                    self.state.annotations.push(annotation);
+                    self.store.set(ANNOTATIONS_KEY, &self.state.annotations);
+                }
+            }
+            KeyCode::Esc => {
+                self.state.note_input = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_peer_input_key(&mut self, key_event: KeyEvent) {
+        let Some(input) = &mut self.state.peer_input else {
+            return;
+        };
+
+        match key_event.code {
+            KeyCode::Char(c) if !c.is_whitespace() && input.len() < MAX_PEER_ADDRESS_LENGTH => {
+                input.push(c)
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => {
+                let address = self.state.peer_input.take().unwrap_or_default();
+
+                if !address.is_empty() {
+                    self.request_action(PendingAction {
+                        description: format!("Connect to {} once?", address),
+                        command: NodeCommand::AddNode(address),
+                    });
+                }
+            }
+            KeyCode::Esc => {
+                self.state.peer_input = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Prompts for a number of blocks to mine, only offered on test chains in control mode.
+    fn start_mining(&mut self) {
+        let can_mine = self
+            .state
+            .node
+            .as_ref()
+            .is_some_and(|node| node.lock().unwrap().can_mine());
+
+        match (self.config.control_mode, can_mine) {
+            (true, true) => self.state.mine_input = Some(String::new()),
+            (false, true) => self.notice_control_mode(),
+            (_, false) => {}
+        }
+    }
+
+    fn handle_mine_key(&mut self, key_event: KeyEvent) {
+        let Some(input) = &mut self.state.mine_input else {
+            return;
+        };
+
+        match key_event.code {
+            KeyCode::Char(c) if c.is_ascii_digit() && input.len() < MAX_MINE_DIGITS => {
+                input.push(c)
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => {
+                let input = self.state.mine_input.take().unwrap_or_default();
+
+                if let Ok(blocks @ 1..) = input.parse::<u64>() {
+                    self.request_action(PendingAction {
+                        description: format!(
+                            "Mine {} block{}?",
+                            blocks,
+                            if blocks == 1 { "" } else { "s" }
+                        ),
+                        command: NodeCommand::MineBlocks(blocks),
+                    });
+                }
+            }
+            KeyCode::Esc => {
+                self.state.mine_input = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Opens the given detail view, or closes it when it is already open.
+    pub fn toggle_detail(&mut self, view: DetailView) {
+        self.state.detail = match self.state.detail {
+            Some(current) if current == view => None,
+            _ => Some(view),
+        };
+    }
+
+    fn ath_key(currency: &PriceCurrency) -> String {
+        format!("price.ath.{}", currency)
+    }
+
+    /// Persisted all time high in the main currency, or the configured one when it is higher.
+    fn seed_ath(config: &AppConfig, store: &Store) -> Option<f64> {
+        let stored = config
+            .price_currencies()
+            .first()
+            .and_then(|currency| store.get::<f64>(&App::ath_key(currency)));
+        let configured = Some(config.price.ath).filter(|ath| *ath > 0.0);
+
+        stored.into_iter().chain(configured).reduce(f64::max)
+    }
+
+    pub fn handle_price_update(&mut self, mut state: PriceState) {
+        let main = state.currency == self.state.price.currency;
+        let previous = match main {
+            true => Some(&mut self.state.price),
+            false => self.state.other_prices.get_mut(&state.currency),
+        };
+        if let Some(previous) = previous {
+            state.ath = previous.ath;
+            state.ath_reached_at = previous.ath_reached_at;
+            state.history = std::mem::take(&mut previous.history);
+            state.history_times = std::mem::take(&mut previous.history_times);
+            state.sampled_at = previous.sampled_at;
+        }
+
+        // One price per poll interval, streamed ones arrive every second. Polls are let
+        // through a little early, the events may have waited in the queue.
+        let sample_every = PRICE_INTERVAL - Duration::from_secs(1);
+        if let Some(price) = state.last_price_in_currency.filter(|_| {
+            state
+                .sampled_at
+                .is_none_or(|at| at.elapsed() >= sample_every)
+        }) {
+            state.history.push_back(price);
+            state.history_times.push_back(Utc::now().timestamp());
+            state.sampled_at = Some(Instant::now());
+
+            if state.history.len() > self.config.price_history_kept() {
+                state.history.pop_front();
+                state.history_times.pop_front();
+            }
+        }
+
+        if let Some(price) = state.last_price_in_currency {
+            if state.ath.is_none_or(|ath| price > ath) {
+                // The first price ever seen is not worth celebrating
+                if state.ath.is_some() {
+                    state.ath_reached_at = Some(Instant::now());
+                }
+                state.ath = Some(price);
+                self.state.ath_unsaved = true;
+            }
+        }
+
+        // The session range stays in one currency
+        match main {
+            true => {
+                self.check_price_alerts(&state);
+                if let (Some(date), Some(price)) = (self.today(), state.last_price_in_currency) {
+                    let currency = state.currency.to_string();
+                    self.state.daily.observe_price(&date, &currency, price);
+                }
+                self.state.session.observe_price(&state);
+                self.state.price = state;
+            }
+            false => {
+                self.state.other_prices.insert(state.currency, state);
+            }
+        }
+    }
+
+    /// Drops the alerts the new price went past and reports each one as an event.
+    fn check_price_alerts(&mut self, state: &PriceState) {
+        let Some(price) = state.last_price_in_currency else {
+            return;
+        };
+        let previous = self.state.price.last_price_in_currency;
+
+        let (crossed, armed) = self
+            .state
+            .price_alerts
+            .iter()
+            .partition(|alert| alert.crossed(previous, price));
+        self.state.price_alerts = armed;
+
+        for alert in crossed {
+            let _ = self
+                .thread
+                .sender
+                .send(Event::Market(MarketEvent::PriceAlert(alert, price)));
+        }
+    }
+
+    /// Arms alerts `price.alert_move` percent above and below the current main price.
+    fn arm_price_alerts(&mut self) {
+        let Some(price) = self
+            .state
+            .price
+            .last_price_in_currency
+            .filter(|_| self.config.price.alert_move > 0.0)
+        else {
+            return;
+        };
+        let delta = price * self.config.price.alert_move / 100.0;
+
+        self.state.price_alerts = vec![
+            PriceAlert::Above(price + delta),
+            PriceAlert::Below(price - delta),
+        ];
+    }
+
+    fn handle_price_alert(&mut self, alert: PriceAlert, price: f64) {
+        self.state.price_alert = Some((alert, price, Instant::now()));
+        self.state.session.alerts += 1;
+
+        if self.config.price.alert_bell {
+            let _ = crossterm::execute!(io::stderr(), Print('\x07'));
+        }
+
+        if self.is_quiet("Price alert") {
+            return;
+        }
+
+        let main = &self.state.price;
+        let (direction, level) = match alert {
+            PriceAlert::Above(level) => ("above", level),
+            PriceAlert::Below(level) => ("below", level),
+        };
+
+        self.push(
+            "Price alert".to_string(),
+            format!(
+                "Price went {} {} (now {})",
+                direction,
+                main.format_price(&self.config, level, 2),
+                main.format_price(&self.config, price, 2)
+            ),
+        );
+    }
+
+    /// Raises the known all time high to `ath`, quietly, it is old news.
+    pub fn handle_ath_seed(&mut self, currency: PriceCurrency, ath: f64) {
+        let state = match currency == self.state.price.currency {
+            true => Some(&mut self.state.price),
+            false => self.state.other_prices.get_mut(&currency),
+        };
+
+        if let Some(state) = state.filter(|state| state.ath.is_none_or(|known| ath > known)) {
+            state.ath = Some(ath);
+            self.state.ath_unsaved = true;
+        }
+    }
+
+    pub fn handle_price_error(&mut self, currency: PriceCurrency, provider: String) {
+        let state = match currency == self.state.price.currency {
+            true => Some(&mut self.state.price),
+            false => self.state.other_prices.get_mut(&currency),
+        };
+
+        if let Some(state) = state {
+            state.provider = provider;
+            state.health.failing = true;
+        }
+
+        self.state.session.observe_error("price");
+    }
+
+    pub fn handle_fee_update(&mut self, mut state: FeesState) {
+        if let (Some(date), Some(rate)) = (self.today(), state.result.medium()) {
+            self.state.daily.observe_fee(&date, rate);
+        }
+
+        state.history = std::mem::take(&mut self.state.fees.history);
+        state.history_times = std::mem::take(&mut self.state.fees.history_times);
+        state.history.push_back(state.result.clone());
+        state.history_times.push_back(Utc::now().timestamp());
+
+        if state.history.len() > HISTORY_LEN {
+            state.history.pop_front();
+            state.history_times.pop_front();
+        }
+
         self.state.fees = state;
     }
 
+    pub fn handle_fee_error(&mut self) {
+        self.state.fees.health.failing = true;
+        self.state.session.observe_error("fees");
+    }
+
+    pub fn handle_host_update(&mut self, state: HostState) {
+        self.state.host = state;
+    }
+
+    pub fn handle_host_error(&mut self, error: String) {
+        self.state.host.health.failing = true;
+        self.state.session.observe_error("host");
+        self.state.host.error = Some(error);
+    }
+
+    pub fn handle_event(&mut self, event: Event) -> AppResult<()> {
+        self.state.diagnostics.record_event();
+
+        match event {
+            Event::Node(event) => self.handle_node_event(event),
+            Event::Market(event) => self.handle_market_event(event),
+            Event::Ui(event) => self.handle_ui_event(event)?,
+            Event::System(event) => self.handle_system_event(event),
+        }
+
+        Ok(())
+    }
+
+    fn handle_node_event(&mut self, event: NodeEvent) {
+        match event {
+            NodeEvent::HostUpdate(state) => self.handle_host_update(state),
+            NodeEvent::HostError(error) => self.handle_host_error(error),
+            NodeEvent::Preflight(checks) => self.state.preflight = Some(checks),
+            NodeEvent::CommandRefused(reason) => {
+                self.state.notice = Some((reason, Instant::now()));
+            }
+            NodeEvent::ExplorerTip(tip) => {
+                if self.state.explorer_requested_at.is_some() {
+                    self.state.explorer = Some(tip);
+                }
+            }
+        }
+    }
+
+    fn handle_market_event(&mut self, event: MarketEvent) {
+        match event {
+            MarketEvent::PriceUpdate(state) => self.handle_price_update(state),
+            MarketEvent::PriceError(currency, provider) => {
+                self.handle_price_error(currency, provider)
+            }
+            MarketEvent::PriceAlert(alert, price) => self.handle_price_alert(alert, price),
+            MarketEvent::AthSeed(currency, ath) => self.handle_ath_seed(currency, ath),
+            MarketEvent::FeeUpdate(state) => self.handle_fee_update(state),
+            MarketEvent::FeeError => self.handle_fee_error(),
+        }
+    }
+
+    fn handle_ui_event(&mut self, event: UiEvent) -> AppResult<()> {
+        match event {
+            UiEvent::Tick => self.tick(),
+            UiEvent::Key(key_event) => self.handle_key_events(key_event)?,
+            UiEvent::Mouse(_) => {}
+            UiEvent::Resize(_, _) => {}
+        }
+
+        Ok(())
+    }
+
+    fn handle_system_event(&mut self, event: SystemEvent) {
+        match event {
+            SystemEvent::TestNotification(sent) => {
+                self.state.test_notification = Some((sent, Instant::now()));
+            }
+            // For the notifier
+            SystemEvent::Push(_, _) | SystemEvent::TestPush => {}
+        }
+    }
+
     pub fn handle_key_events(&mut self, key_event: KeyEvent) -> AppResult<()> {
         // self.reset_last_hash_time();
+        if self.state.splash.is_some() {
+            self.state.splash = None;
+            return Ok(());
+        }
+
+        if self.state.pending_action.is_some() {
+            self.handle_pending_action_key(key_event);
+            return Ok(());
+        }
+
+        if self.state.note_input.is_some() {
+            self.handle_note_key(key_event);
+            return Ok(());
+        }
+
+        if self.state.mine_input.is_some() {
+            self.handle_mine_key(key_event);
+            return Ok(());
+        }
+
+        if self.state.peer_input.is_some() {
+            self.handle_peer_input_key(key_event);
+            return Ok(());
+        }
+
         match key_event.code {
+            KeyCode::Esc if self.state.detail.is_some() => {
+                self.state.detail = None;
+            }
             KeyCode::Esc | KeyCode::Char('q') => {
                 self.quit();
             }
-            KeyCode::Char('c') | KeyCode::Char('C') => {
-                if key_event.modifiers == KeyModifiers::CONTROL {
-                    self.quit();
-                }
+            KeyCode::Char('c') | KeyCode::Char('C')
+                if key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                self.quit();
+            }
+            KeyCode::Tab => {
+                self.focus_next();
+            }
+            KeyCode::BackTab => {
+                self.focus_previous();
+            }
+            KeyCode::Char('p') => {
+                self.toggle_pause();
+            }
+            KeyCode::Char('h') => {
+                self.toggle_detail(DetailView::BlockHeatmap);
+            }
+            KeyCode::Char('e') => {
+                self.toggle_detail(DetailView::DifficultyEpochs);
+            }
+            KeyCode::Char('t') => {
+                self.send_test_notification();
+            }
+            KeyCode::F(12) => {
+                self.state.diagnostics.visible = !self.state.diagnostics.visible;
+            }
+            KeyCode::Char('T') => {
+                self.test_connection();
+            }
+            KeyCode::Char('a') => {
+                self.state.note_input = Some(String::new());
+            }
+            KeyCode::Char('n') => {
+                self.toggle_detail(DetailView::Annotations);
+            }
+            KeyCode::Char('m') => {
+                self.start_mining();
+            }
+            KeyCode::Char('s') => {
+                self.next_price_provider();
+            }
+            KeyCode::Char('c') => {
+                self.next_currency();
+            }
+            KeyCode::Char('u') => {
+                self.state.price_in_sats = !self.state.price_in_sats;
+            }
+            KeyCode::Char('A') => {
+                self.arm_price_alerts();
+            }
+            KeyCode::Up
+            | KeyCode::Down
+            | KeyCode::PageUp
+            | KeyCode::PageDown
+            | KeyCode::Home
+            | KeyCode::End
+            | KeyCode::Char('b')
+            | KeyCode::Char('d')
+            | KeyCode::Char('+')
+                if self.state.focus == Panel::Peers =>
+            {
+                self.handle_peers_key(key_event.code);
             }
             KeyCode::Right => {
                 self.increment_counter();
@@ -0,0 +1,216 @@
+use anyhow::Result;
+use std::str::FromStr;
+
+use crate::config::BadgeSettings;
+use crate::fees::FeesState;
+use crate::node::NodeState;
+use crate::price::PriceState;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl FromStr for Comparison {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Comparison> {
+        match input {
+            ">" => Ok(Comparison::Greater),
+            ">=" => Ok(Comparison::GreaterOrEqual),
+            "<" => Ok(Comparison::Less),
+            "<=" => Ok(Comparison::LessOrEqual),
+            "==" => Ok(Comparison::Equal),
+            "!=" => Ok(Comparison::NotEqual),
+            _ => Err(anyhow::Error::msg("Comparison not supported")),
+        }
+    }
+}
+
+impl Comparison {
+    pub fn holds(&self, left: f64, right: f64) -> bool {
+        match self {
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+        }
+    }
+}
+
+/// Values a badge condition can refer to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    Price,
+    FeesLow,
+    FeesMedium,
+    FeesHigh,
+    NodeHeight,
+    NodeBehind,
+    NodePeers,
+}
+
+impl FromStr for Metric {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Metric> {
+        match input {
+            "price" => Ok(Metric::Price),
+            "fees.low" => Ok(Metric::FeesLow),
+            "fees.medium" => Ok(Metric::FeesMedium),
+            "fees.high" => Ok(Metric::FeesHigh),
+            "node.height" => Ok(Metric::NodeHeight),
+            "node.behind" => Ok(Metric::NodeBehind),
+            "node.peers" => Ok(Metric::NodePeers),
+            _ => Err(anyhow::Error::msg("Unknown metric")),
+        }
+    }
+}
+
+impl Metric {
+    pub fn value(&self, node: &NodeState, fees: &FeesState, price: &PriceState) -> Option<f64> {
+        match self {
+            Metric::Price => price.last_price_in_currency,
+            Metric::FeesLow => fees.result.low(),
+            Metric::FeesMedium => fees.result.medium(),
+            Metric::FeesHigh => fees.result.high(),
+            Metric::NodeHeight => Some(node.height as f64).filter(|height| *height > 0.0),
+            Metric::NodeBehind => Some(node.headers.saturating_sub(node.height) as f64),
+            Metric::NodePeers => Some(node.peers.len() as f64),
+        }
+    }
+}
+
+/// Label shown in the status bar while its condition holds, e.g. `fees.high > 100`.
+#[derive(Debug, Clone)]
+pub struct Badge {
+    pub label: String,
+    pub when: String,
+    pub metric: Metric,
+    pub comparison: Comparison,
+    pub threshold: f64,
+}
+
+impl TryFrom<&BadgeSettings> for Badge {
+    type Error = anyhow::Error;
+
+    fn try_from(settings: &BadgeSettings) -> Result<Badge> {
+        let [metric, comparison, threshold] = settings
+            .when
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .try_into()
+            .map_err(|_| anyhow::Error::msg("Expected a condition like `fees.high > 100`"))?;
+
+        Ok(Badge {
+            label: settings.label.clone(),
+            when: settings.when.clone(),
+            metric: Metric::from_str(metric)?,
+            comparison: Comparison::from_str(comparison)?,
+            threshold: threshold.parse()?,
+        })
+    }
+}
+
+impl Badge {
+    /// Missing data never triggers a badge.
+    pub fn is_active(&self, node: &NodeState, fees: &FeesState, price: &PriceState) -> bool {
+        self.metric
+            .value(node, fees, price)
+            .is_some_and(|value| self.comparison.holds(value, self.threshold))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn badge(when: &str) -> Result<Badge> {
+        Badge::try_from(&BadgeSettings {
+            label: "TEST".to_string(),
+            when: when.to_string(),
+        })
+    }
+
+    #[test]
+    fn parses_every_comparison() {
+        for (input, comparison) in [
+            (">", Comparison::Greater),
+            (">=", Comparison::GreaterOrEqual),
+            ("<", Comparison::Less),
+            ("<=", Comparison::LessOrEqual),
+            ("==", Comparison::Equal),
+            ("!=", Comparison::NotEqual),
+        ] {
+            let badge = badge(&format!("fees.high {} 100", input)).unwrap();
+            assert_eq!(badge.comparison, comparison, "{}", input);
+        }
+    }
+
+    #[test]
+    fn parses_metric_and_threshold() {
+        let parsed = badge("  node.behind   >=  2.5 ").unwrap();
+
+        assert_eq!(parsed.metric, Metric::NodeBehind);
+        assert_eq!(parsed.threshold, 2.5);
+        assert_eq!(parsed.label, "TEST");
+
+        assert_eq!(badge("price < -1").unwrap().threshold, -1.0);
+        assert_eq!(badge("node.peers == 0").unwrap().metric, Metric::NodePeers);
+    }
+
+    #[test]
+    fn rejects_malformed_conditions() {
+        for when in [
+            "",
+            "fees.high > ",
+            "fees.high >100",
+            "fees.high > 100 sat",
+            "fees.highest > 100",
+            "fees.high => 100",
+            "fees.high = 100",
+            "fees.high > lots",
+            "> fees.high 100",
+        ] {
+            assert!(badge(when).is_err(), "{}", when);
+        }
+    }
+
+    #[test]
+    fn comparisons_hold_at_the_threshold() {
+        assert!(!Comparison::Greater.holds(100.0, 100.0));
+        assert!(Comparison::GreaterOrEqual.holds(100.0, 100.0));
+        assert!(!Comparison::Less.holds(100.0, 100.0));
+        assert!(Comparison::LessOrEqual.holds(100.0, 100.0));
+        assert!(Comparison::Equal.holds(0.0, 0.0));
+        assert!(Comparison::NotEqual.holds(1.0, 0.0));
+    }
+
+    #[test]
+    fn missing_data_never_triggers() {
+        let node = NodeState::default();
+        let fees = FeesState::default();
+        let mut price = PriceState::default();
+
+        let cheap = badge("price < 1000000").unwrap();
+        assert!(!cheap.is_active(&node, &fees, &price));
+
+        price.last_price_in_currency = Some(50_000.0);
+        assert!(cheap.is_active(&node, &fees, &price));
+
+        // An unknown height is not a height of zero
+        assert!(!badge("node.height < 10")
+            .unwrap()
+            .is_active(&node, &fees, &price));
+        assert!(badge("node.peers == 0")
+            .unwrap()
+            .is_active(&node, &fees, &price));
+    }
+}
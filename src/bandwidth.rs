@@ -0,0 +1,102 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use tokio::time::{Duration, Instant};
+
+/// Rough size of the HTTP headers going back and forth with each request.
+pub const HTTP_OVERHEAD: usize = 300;
+
+/// The rate is not extrapolated from less than this, the first polls would look huge.
+const MIN_RATE_WINDOW: Duration = Duration::from_secs(15 * 60);
+
+/// Polling never slows down more than this much, however far over the budget.
+const MAX_STRETCH: u32 = 8;
+
+/// Where the traffic goes, what the diagnostics view breaks it down by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    Node,
+    Price,
+    Fees,
+    Host,
+}
+
+impl Source {
+    pub const ALL: [Source; 4] = [Source::Node, Source::Price, Source::Fees, Source::Host];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Source::Node => "node",
+            Source::Price => "price",
+            Source::Fees => "fees",
+            Source::Host => "host",
+        }
+    }
+}
+
+/// Approximate bytes sent and received by the pollers since startup. Process wide, the
+/// RPC transports are built far from anything that could hand them a handle.
+struct Usage {
+    bytes: [AtomicU64; 4],
+    /// Soft limit in bytes per hour, 0 for none.
+    budget: AtomicU64,
+}
+
+static USAGE: Usage = Usage {
+    bytes: [
+        AtomicU64::new(0),
+        AtomicU64::new(0),
+        AtomicU64::new(0),
+        AtomicU64::new(0),
+    ],
+    budget: AtomicU64::new(0),
+};
+
+static STARTED_AT: OnceLock<Instant> = OnceLock::new();
+
+/// Starts the clock for the hourly rate and sets the budget, 0 for none.
+pub fn start(budget_mb: u64) {
+    STARTED_AT.get_or_init(Instant::now);
+    USAGE
+        .budget
+        .store(budget_mb * 1024 * 1024, Ordering::Relaxed);
+}
+
+pub fn record(source: Source, bytes: usize) {
+    USAGE.bytes[source as usize].fetch_add(bytes as u64, Ordering::Relaxed);
+}
+
+pub fn bytes(source: Source) -> u64 {
+    USAGE.bytes[source as usize].load(Ordering::Relaxed)
+}
+
+pub fn total() -> u64 {
+    Source::ALL.iter().map(|source| bytes(*source)).sum()
+}
+
+/// Session average in bytes per hour.
+pub fn hourly_rate() -> u64 {
+    let elapsed = STARTED_AT
+        .get()
+        .map(|started_at| started_at.elapsed())
+        .unwrap_or_default()
+        .max(MIN_RATE_WINDOW);
+
+    (total() as f64 / elapsed.as_secs_f64() * 3600.0) as u64
+}
+
+/// How many times slower polling goes to get back under the budget, 1 when it is not
+/// exceeded or there is none.
+pub fn stretch_factor() -> u32 {
+    let budget = USAGE.budget.load(Ordering::Relaxed);
+
+    if budget == 0 {
+        return 1;
+    }
+
+    (hourly_rate().div_ceil(budget) as u32).clamp(1, MAX_STRETCH)
+}
+
+/// Poll interval slowed down while over the budget.
+pub fn stretch(interval: Duration) -> Duration {
+    interval * stretch_factor()
+}
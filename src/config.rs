@@ -1,7 +1,22 @@
 use argmap::List;
 use config::{Config, ConfigError, File};
+use ratatui::style::Color;
 use serde_derive::Deserialize;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tokio::time::Duration;
+
+use crate::app::Panel;
+use crate::badges::Badge;
+use crate::fees::{self, TYPICAL_TX_VBYTES};
+use crate::format::{DisplayTimezone, Locale};
+use crate::host::HostSource;
+use crate::notify::PushService;
+use crate::paths;
+use crate::price::{self, providers as price_providers, PriceAlert, PriceCurrency};
+use crate::quiet::QuietWindow;
+use crate::ui::price::PIXEL_SIZES;
 
 #[derive(Debug, Deserialize, Clone)]
 #[allow(unused)]
@@ -11,30 +26,299 @@ pub struct BitcoinCoreSettings {
     pub rpc_user: String,
     pub rpc_password: String,
     pub zmq_port: String,
+    pub zmq_rawtx_port: String,
+    pub zmq_sequence_port: String,
+    pub lightweight: bool,
+    /// Address mined regtest and signet blocks pay to.
+    pub mine_address: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct ElectrumSettings {
+    pub host: String,
+    pub port: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct CustomNodeSettings {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    pub height_path: String,
+    pub headers_path: String,
+    pub status_path: String,
+    pub online_value: String,
+    pub label_path: String,
+    pub poll_interval: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct NodeSettings {
+    pub provider: String,
+    pub sync_tolerance_blocks: u64,
+    pub share: bool,
+    /// Color of the node's border and status bar entry, to tell instances watching
+    /// different nodes apart. Empty for none.
+    pub accent: String,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 #[allow(unused)]
 pub struct PriceSettings {
     pub enabled: bool,
+    /// `coinbase`, `kraken`, `binance`, `yadio` or `mempool_space`.
+    pub provider: String,
+    /// Providers in failover order, also switched between with a key, replacing
+    /// `provider` when set.
+    #[serde(default)]
+    pub providers: Vec<String>,
+    /// `vwap` to poll every provider and average their prices by volume, empty for one
+    /// provider at a time.
+    pub aggregate: String,
+    /// mempool.space instance for the `mempool_space` provider, empty for `fees.mempool_url`.
+    pub mempool_url: String,
+    /// Stream the price from the Coinbase Exchange feed, polling only while it is down.
+    pub stream: bool,
+    pub stream_url: String,
     pub currency: String,
+    /// Currencies switched between with a key, replacing `currency` when set.
+    #[serde(default)]
+    pub currencies: Vec<String>,
+    pub symbol: bool,
+    /// `fiat`, or `sats` for how many sats one unit of the currency buys.
+    pub unit: String,
+    pub ath: f64,
+    /// Where to seed the all time high from at startup, `coingecko` or empty for nowhere.
+    pub ath_source: String,
+    /// Big text size, `full`, `half_height`, `half_width`, `third_height`, `quadrant` or
+    /// `sextant`. Smaller ones are used when the price doesn't fit.
+    pub pixel_size: String,
+    /// Minutes of prices in the sparkline under the price, 0 for none.
+    pub history_minutes: u64,
+    /// Windows of the moving averages under the price, e.g. `["1h", "24h"]`.
+    #[serde(default)]
+    pub show_ma: Vec<String>,
+    /// Levels that pop up an alert once crossed, 0 for none.
+    pub alert_above: f64,
+    pub alert_below: f64,
+    /// Percent away from the current price the alerts armed with a key are set.
+    pub alert_move: f64,
+    /// Ring the terminal bell along with the popup.
+    pub alert_bell: bool,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 #[allow(unused)]
 pub struct FeesSettings {
     pub enabled: bool,
+    /// `blockchain_info`, `mempool_space`, `esplora` or `bitcoin_core`.
+    pub provider: String,
+    /// Providers in failover order, replacing `provider` when set.
+    #[serde(default)]
+    pub providers: Vec<String>,
+    /// Base URL of the mempool.space instance, for self-hosted ones.
+    pub mempool_url: String,
+    /// Base URL of the Esplora API, e.g. a self-hosted esplora or electrs.
+    pub esplora_url: String,
+    /// Transaction sizes the fiat cost is shown for, e.g. a send and a consolidation.
+    pub tx_vbytes: Vec<u64>,
+    /// Confirmation targets in blocks shown, providers without an estimate for one
+    /// give the rate of the closest faster target.
+    pub targets: Vec<u64>,
+    /// Seconds between fee polls.
+    pub poll_interval: u64,
+    /// Flags rates under the `mempoolminfee` of the node, which it would reject.
+    pub check_min_fee: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct UiSettings {
+    pub banner: String,
+    pub splash: bool,
+    /// Missed polls before a panel is marked stale.
+    pub stale_polls: u32,
+    /// Summary of the day that just ended, shown at midnight.
+    pub day_summary: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct PerformanceSettings {
+    pub profile: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct BandwidthSettings {
+    /// Soft limit in MB per hour, 0 for none.
+    pub budget_mb: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct ExplorerSettings {
+    /// Esplora compatible API shown while the node is offline, empty for none.
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct HostSettings {
+    pub enabled: bool,
+    /// `local`, `ssh://user@host` or a node_exporter metrics URL.
+    pub source: String,
+    /// Mount point whose usage is shown, e.g. the one holding the datadir.
+    pub disk_path: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct DiskSettings {
+    /// Space available to the node in GB, 0 for no projection.
+    pub limit_gb: u64,
+    /// Warn once the limit is closer than this many days.
+    pub warn_days: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct NotifySettings {
+    pub service: String,
+    pub url: String,
+    pub topic: String,
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct BadgeSettings {
+    pub label: String,
+    pub when: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct QuietSettings {
+    /// Cron style start, e.g. `0 3 * * *` for every night at 3.
+    pub at: String,
+    pub minutes: u64,
+    #[serde(default)]
+    pub alerts: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct RotationSettings {
+    pub panels: Vec<String>,
+    pub interval: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct DifficultySettings {
+    pub epochs: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct PeersSettings {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct MempoolSettings {
+    pub enabled: bool,
+    /// Fee histogram of the projected next block, mempool_space only.
+    pub next_block: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct SessionSettings {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct SnapshotSettings {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct MirrorSettings {
+    pub enabled: bool,
+    /// Socket `btcmon attach` connects to, empty for `mirror.sock` in the runtime directory.
+    pub socket: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct ScriptSettings {
+    pub path: String,
+    pub title: String,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 #[allow(unused)]
 pub struct AppConfig {
     pub tick_rate: String,
+    pub locale: String,
+    pub timezone: String,
+    pub datetime_format: String,
+    pub control_mode: bool,
+    pub proxy: String,
+    pub format: String,
+    pub fields: Vec<String>,
+    pub stream_interval: u64,
+    /// JSON lines file every event is written to, empty to not record.
+    pub record: String,
+    /// Recording played back instead of polling any source.
+    pub replay: String,
+    /// How many times faster than recorded the replay runs.
+    pub replay_speed: f64,
+    pub ui: UiSettings,
+    pub performance: PerformanceSettings,
+    pub bandwidth: BandwidthSettings,
     pub price: PriceSettings,
     pub fees: FeesSettings,
+    pub mempool: MempoolSettings,
+    pub session: SessionSettings,
+    pub snapshot: SnapshotSettings,
+    pub mirror: MirrorSettings,
+    pub script: ScriptSettings,
+    pub peers: PeersSettings,
+    pub difficulty: DifficultySettings,
+    pub disk: DiskSettings,
+    pub host: HostSettings,
+    pub explorer: ExplorerSettings,
+    pub rotation: RotationSettings,
+    #[serde(default)]
+    pub badges: Vec<BadgeSettings>,
+    #[serde(default)]
+    pub quiet: Vec<QuietSettings>,
+    pub notify: NotifySettings,
+    pub node: NodeSettings,
     pub bitcoin_core: BitcoinCoreSettings,
+    pub electrum: ElectrumSettings,
+    pub custom_node: CustomNodeSettings,
+    /// Config files that were found and read, later ones take precedence.
+    #[serde(skip)]
+    pub config_files: Vec<PathBuf>,
+    /// File given with `-c` or `--config` that does not exist.
+    #[serde(skip)]
+    pub missing_config_file: Option<PathBuf>,
 }
 
+/// Slowest the low profile redraws on its own, keys still redraw right away.
+const LOW_PROFILE_TICK_RATE: u64 = 1000;
+
 fn match_string_to_bool(value: &str) -> bool {
     match value {
         "true" => true,
@@ -45,61 +329,174 @@ fn match_string_to_bool(value: &str) -> bool {
     }
 }
 
+/// Seconds in a window like `30m` or `24h`.
+fn parse_window(window: &str) -> Option<u64> {
+    let window = window.trim();
+    let (count, unit) = match (window.strip_suffix('m'), window.strip_suffix('h')) {
+        (Some(minutes), _) => (minutes, 60),
+        (_, Some(hours)) => (hours, 60 * 60),
+        _ => return None,
+    };
+
+    count.parse::<u64>().ok().map(|count| count * unit)
+}
+
 impl AppConfig {
     pub fn new(_args: List, argv: HashMap<String, Vec<String>>) -> Result<Self, ConfigError> {
-        let homedir = home::home_dir().unwrap();
-        let home_path = homedir.as_path().to_str();
-
         let mut s = Config::builder()
             // general
             .set_default("tick_rate", 250)?
+            .set_default("locale", "en_US")?
+            .set_default("timezone", "local")?
+            .set_default("datetime_format", "")?
+            .set_default("control_mode", false)?
+            .set_default("proxy", "")?
+            .set_default("format", "tui")?
+            .set_default("fields", vec!["price", "height", "fee", "node_ok"])?
+            .set_default("stream_interval", 5)?
+            .set_default("record", "")?
+            .set_default("replay", "")?
+            .set_default("replay_speed", 1.0)?
+            .set_default("ui.banner", "")?
+            .set_default("ui.splash", true)?
+            .set_default("ui.stale_polls", 3)?
+            .set_default("ui.day_summary", false)?
+            .set_default("performance.profile", "normal")?
+            .set_default("bandwidth.budget_mb", 0)?
+            // node
+            .set_default("node.provider", "bitcoin_core")?
+            .set_default("node.sync_tolerance_blocks", 2)?
+            .set_default("node.share", false)?
+            .set_default("node.accent", "")?
             // bitcoin core
             .set_default("bitcoin_core.host", "localhost")?
             .set_default("bitcoin_core.rpc_port", 8332)?
             .set_default("bitcoin_core.rpc_user", "username")?
             .set_default("bitcoin_core.rpc_password", "password")?
             .set_default("bitcoin_core.zmq_port", 28332)?
+            .set_default("bitcoin_core.zmq_rawtx_port", "")?
+            .set_default("bitcoin_core.zmq_sequence_port", "")?
+            .set_default("bitcoin_core.mine_address", "")?
+            .set_default("bitcoin_core.lightweight", false)?
+            // electrum
+            .set_default("electrum.host", "localhost")?
+            .set_default("electrum.port", 50001)?
+            // custom node
+            .set_default("custom_node.name", "Custom")?
+            .set_default("custom_node.url", "")?
+            .set_default("custom_node.height_path", "")?
+            .set_default("custom_node.headers_path", "")?
+            .set_default("custom_node.status_path", "")?
+            .set_default("custom_node.online_value", "true")?
+            .set_default("custom_node.label_path", "")?
+            .set_default("custom_node.poll_interval", 15)?
             // price
             .set_default("price.enabled", true)?
+            .set_default("price.provider", "coinbase")?
+            .set_default("price.mempool_url", "")?
+            .set_default("price.aggregate", "")?
+            .set_default("price.ath_source", "")?
+            .set_default("price.stream", false)?
+            .set_default("price.stream_url", "wss://ws-feed.exchange.coinbase.com")?
             .set_default("price.currency", "USD")?
+            .set_default("price.symbol", true)?
+            .set_default("price.unit", "fiat")?
+            .set_default("price.history_minutes", 60)?
+            .set_default("price.show_ma", Vec::<String>::new())?
+            .set_default("price.alert_above", 0.0)?
+            .set_default("price.alert_below", 0.0)?
+            .set_default("price.alert_move", 2.0)?
+            .set_default("price.alert_bell", false)?
+            .set_default("price.ath", 0.0)?
+            .set_default("price.pixel_size", "sextant")?
             // fees
-            .set_default("fees.enabled", true)?;
-
-        let mut default_config_file: String = String::from("/etc/btcmon/btcmon.toml");
-
-        let config_file = match (argv.contains_key("c"), argv.contains_key("config")) {
-            (true, false) => argv
-                .get("c")
-                .and_then(|v| Some(v.first().unwrap().as_str()))
-                .unwrap(),
-            (false, true) | (true, true) => argv
-                .get("config")
-                .and_then(|v| Some(v.first().unwrap().as_str()))
-                .unwrap(),
-            _ => match home_path {
-                Some(home_path) => {
-                    default_config_file = vec![home_path, "/.btcmon/btcmon.toml"].join("");
-                    default_config_file.as_str()
-                }
-                _ => default_config_file.as_str(),
-            },
+            .set_default("fees.enabled", true)?
+            .set_default("fees.provider", "blockchain_info")?
+            .set_default("fees.mempool_url", "https://mempool.space")?
+            .set_default("fees.esplora_url", "https://blockstream.info/api")?
+            .set_default("fees.tx_vbytes", vec![TYPICAL_TX_VBYTES])?
+            .set_default("fees.targets", vec![144, 6, 1])?
+            .set_default("fees.poll_interval", fees::CHECK_INTERVAL.as_secs())?
+            .set_default("fees.check_min_fee", false)?
+            // mempool
+            .set_default("mempool.enabled", false)?
+            .set_default("mempool.next_block", false)?
+            // session
+            .set_default("session.enabled", false)?
+            // snapshot
+            .set_default("snapshot.enabled", true)?
+            // mirror
+            .set_default("mirror.enabled", false)?
+            .set_default("mirror.socket", "")?
+            // script
+            .set_default("script.path", "")?
+            .set_default("script.title", "Custom")?
+            // peers
+            .set_default("peers.enabled", true)?
+            // difficulty
+            .set_default("difficulty.epochs", 10)?
+            // explorer
+            .set_default("explorer.url", "")?
+            // host
+            .set_default("host.enabled", false)?
+            .set_default("host.source", "local")?
+            .set_default("host.disk_path", "/")?
+            // disk
+            .set_default("disk.limit_gb", 0)?
+            .set_default("disk.warn_days", 30)?
+            // rotation
+            .set_default("rotation.panels", Vec::<String>::new())?
+            .set_default("rotation.interval", 15)?
+            // notify
+            .set_default("notify.service", "ntfy")?
+            .set_default("notify.url", "")?
+            .set_default("notify.topic", "btcmon")?
+            .set_default("notify.token", "")?;
+
+        let config_files = match (argv.contains_key("c"), argv.contains_key("config")) {
+            (true, false) => vec![PathBuf::from(argv.get("c").unwrap().first().unwrap())],
+            (false, true) | (true, true) => {
+                vec![PathBuf::from(argv.get("config").unwrap().first().unwrap())]
+            }
+            _ => paths::config_files(),
+        };
+
+        let missing_config_file = match argv.contains_key("c") || argv.contains_key("config") {
+            true => config_files.first().filter(|file| !file.is_file()).cloned(),
+            false => None,
         };
 
-        s = s.add_source(File::with_name(config_file).required(false));
+        for config_file in config_files.iter() {
+            s = s.add_source(File::with_name(&config_file.to_string_lossy()).required(false));
+        }
 
         let args = argv.clone();
         for key in argv.into_keys() {
-            if let Some(value) = args
-                .get(&key)
-                .and_then(|v| Some(v.first().unwrap().as_str()))
-            {
+            if let Some(value) = args.get(&key).and_then(|v| v.first()).map(String::as_str) {
                 match key.as_str() {
-                    "price.enabled" => {
+                    "price.enabled" | "price.symbol" => {
+                        s = s.set_override(key, match_string_to_bool(value))?;
+                    }
+                    "fees.enabled" | "bitcoin_core.lightweight" | "fees.check_min_fee" => {
                         s = s.set_override(key, match_string_to_bool(value))?;
                     }
-                    "fees.enabled" => {
+                    "control_mode" | "peers.enabled" | "node.share" | "ui.splash"
+                    | "ui.day_summary" => {
                         s = s.set_override(key, match_string_to_bool(value))?;
                     }
+                    "session.enabled" | "host.enabled" | "mempool.enabled" | "snapshot.enabled" => {
+                        s = s.set_override(key, match_string_to_bool(value))?;
+                    }
+                    "mempool.next_block" | "mirror.enabled" | "price.alert_bell"
+                    | "price.stream" => {
+                        s = s.set_override(key, match_string_to_bool(value))?;
+                    }
+                    "rotation.panels" | "fields" | "fees.tx_vbytes" | "fees.targets"
+                    | "fees.providers" | "price.providers" | "price.currencies"
+                    | "price.show_ma" => {
+                        let items: Vec<&str> = value.split(',').map(str::trim).collect();
+                        s = s.set_override(key, items)?;
+                    }
                     _ => {
                         s = s.set_override(key, value.to_string())?;
                     }
@@ -107,6 +504,404 @@ impl AppConfig {
             }
         }
 
-        s.build()?.try_deserialize()
+        let mut config: AppConfig = s.build()?.try_deserialize()?;
+
+        config.config_files = config_files
+            .into_iter()
+            .filter(|file| file.is_file())
+            .collect();
+        config.missing_config_file = missing_config_file;
+
+        Ok(config)
+    }
+
+    /// Settings that are not going to work, checked up front so they don't show up
+    /// later as an empty panel.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = vec![];
+
+        if let Some(file) = &self.missing_config_file {
+            warnings.push(format!("Config file {} not found", file.display()));
+        }
+
+        if !self.replay.is_empty() && !Path::new(&self.replay).is_file() {
+            warnings.push(format!("Recording {} not found", self.replay));
+        }
+
+        if Locale::from_str(&self.locale).is_err() {
+            warnings.push(format!("Locale {} not supported, using en_US", self.locale));
+        }
+
+        if DisplayTimezone::from_str(&self.timezone).is_err() {
+            warnings.push(format!("Timezone {} not known, using local", self.timezone));
+        }
+
+        if !self.proxy.is_empty() && self.onion_proxy().is_none() {
+            warnings.push(format!(
+                "Proxy {} is not a host:port, onion hosts unreachable",
+                self.proxy
+            ));
+        }
+
+        match self.node.provider.as_str() {
+            "bitcoin_core" | "electrum" => {}
+            "custom" if self.custom_node.url.is_empty() => {
+                warnings.push("custom_node.url is empty".to_string());
+            }
+            "custom" => {}
+            provider => warnings.push(format!(
+                "Node provider {} not known, using bitcoin_core",
+                provider
+            )),
+        }
+
+        if !self.node.accent.is_empty() && self.node_accent().is_none() {
+            warnings.push(format!("Node accent {} is not a color", self.node.accent));
+        }
+
+        if self.disk.limit_gb > 0 && ["electrum", "custom"].contains(&self.node.provider.as_str()) {
+            warnings.push(format!(
+                "Disk usage not reported by the {} provider, no projection",
+                self.node.provider
+            ));
+        }
+
+        // Lightweight mode only asks for getblockchaininfo
+        if self.fees.check_min_fee
+            && (["electrum", "custom"].contains(&self.node.provider.as_str())
+                || self.bitcoin_core.lightweight)
+        {
+            warnings.push("Fee check needs Bitcoin Core outside lightweight mode".to_string());
+        }
+
+        if self.host.enabled {
+            match HostSource::from_str(&self.host.source) {
+                Err(_) => warnings.push(format!(
+                    "Host source {} not known, host widget disabled",
+                    self.host.source
+                )),
+                Ok(HostSource::Local) if cfg!(not(target_os = "linux")) => warnings.push(
+                    "Local host figures need Linux, use ssh:// or a node_exporter URL".to_string(),
+                ),
+                Ok(_) => {}
+            }
+        }
+
+        for provider in self.fee_providers().iter().filter(|_| self.fees.enabled) {
+            if ![
+                "blockchain_info",
+                "mempool_space",
+                "esplora",
+                "bitcoin_core",
+            ]
+            .contains(&provider.as_str())
+            {
+                warnings.push(format!(
+                    "Fee provider {} not known, using blockchain_info",
+                    provider
+                ));
+            }
+        }
+
+        // Only these report the size of the backlog
+        if self.fees.enabled
+            && self.mempool.enabled
+            && !self.fee_providers().iter().any(|provider| {
+                ["mempool_space", "esplora", "bitcoin_core"].contains(&provider.as_str())
+            })
+        {
+            warnings.push(
+                "Mempool panel needs the mempool_space, esplora or bitcoin_core fee provider"
+                    .to_string(),
+            );
+        }
+
+        if self.fees.enabled
+            && self.mempool.next_block
+            && !self.fee_providers().iter().any(|p| p == "mempool_space")
+        {
+            warnings.push("Next block panel needs the mempool_space fee provider".to_string());
+        }
+
+        for code in self.price_currency_codes() {
+            match (self.price.enabled, PriceCurrency::from_str(&code)) {
+                (false, _) => {}
+                (true, Err(_)) => warnings.push(format!(
+                    "Price currency {} is not a three letter code, left out",
+                    code
+                )),
+                (true, Ok(currency)) => {
+                    if self.price.stream && !currency.listed_by("coinbase") {
+                        warnings.push(format!(
+                            "Coinbase streams no {} price, it is polled",
+                            currency
+                        ));
+                    }
+
+                    for provider in self.price_providers() {
+                        let known = price_providers::REGISTRY
+                            .iter()
+                            .any(|(name, _)| *name == provider);
+
+                        match (known, currency.listed_by(&provider)) {
+                            (true, true) => {}
+                            (true, false) => warnings.push(format!(
+                                "{} has no {} price, try price.provider = \"yadio\"",
+                                provider, currency
+                            )),
+                            (false, _) => warnings.push(format!(
+                                "Price provider {} not known, using coinbase",
+                                provider
+                            )),
+                        }
+                    }
+                }
+            }
+        }
+
+        if !PIXEL_SIZES
+            .iter()
+            .any(|(name, _)| *name == self.price.pixel_size)
+        {
+            warnings.push(format!(
+                "Price pixel size {} not known, using sextant",
+                self.price.pixel_size
+            ));
+        }
+
+        if !matches!(self.price.aggregate.as_str(), "" | "vwap") {
+            warnings.push(format!(
+                "Price aggregate {} not known, polling one provider at a time",
+                self.price.aggregate
+            ));
+        }
+
+        if !matches!(self.price.unit.as_str(), "fiat" | "sats") {
+            warnings.push(format!(
+                "Price unit {} not known, using fiat",
+                self.price.unit
+            ));
+        }
+
+        if !self.price.ath_source.is_empty()
+            && !price::ath::ATH_SOURCES.contains(&self.price.ath_source.as_str())
+        {
+            warnings.push(format!(
+                "Price ath_source {} not known, the all time high is not seeded",
+                self.price.ath_source
+            ));
+        }
+
+        if self.price.stream && self.price.aggregate == "vwap" {
+            warnings.push("Price stream is off while averaging providers".to_string());
+        }
+
+        for window in self.price.show_ma.iter() {
+            if parse_window(window)
+                .is_none_or(|seconds| seconds < 2 * price::CHECK_INTERVAL.as_secs())
+            {
+                warnings.push(format!(
+                    "Moving average window {} left out, use 1m or more, like 30m or 24h",
+                    window
+                ));
+            }
+        }
+
+        if self.price.alert_move <= 0.0 {
+            warnings.push("Price alert_move is not above 0, A arms no alerts".to_string());
+        }
+
+        for panel in self.rotation.panels.iter() {
+            if Panel::from_str(panel).is_err() {
+                warnings.push(format!("Rotation panel {} not known", panel));
+            }
+        }
+
+        for badge in self.badges.iter() {
+            if let Err(e) = Badge::try_from(badge) {
+                warnings.push(format!("Badge {}: {}", badge.label, e));
+            }
+        }
+
+        for window in self.quiet.iter() {
+            if let Err(e) = QuietWindow::try_from(window) {
+                warnings.push(format!("Quiet window {}: {}", window.at, e));
+            }
+        }
+
+        if !self.notify.url.is_empty() && PushService::from_str(&self.notify.service).is_err() {
+            warnings.push(format!("Push service {} not known", self.notify.service));
+        }
+
+        if !["normal", "low"].contains(&self.performance.profile.as_str()) {
+            warnings.push(format!(
+                "Performance profile {} not known, using normal",
+                self.performance.profile
+            ));
+        }
+
+        if !self.script.path.is_empty() {
+            if !Path::new(&self.script.path).is_file() {
+                warnings.push(format!("Script {} not found", self.script.path));
+            } else if cfg!(not(feature = "scripting")) {
+                warnings.push("Scripts need btcmon built with --features scripting".to_string());
+            }
+        }
+
+        let banner = Path::new(&self.ui.banner);
+        if banner
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("png"))
+            && !banner.is_file()
+        {
+            warnings.push(format!("Banner image {} not found", banner.display()));
+        }
+
+        warnings
+    }
+
+    /// `fees.providers` in failover order, or the single `fees.provider`.
+    pub fn fee_providers(&self) -> Vec<String> {
+        match self.fees.providers.is_empty() {
+            true => vec![self.fees.provider.clone()],
+            false => self.fees.providers.clone(),
+        }
+    }
+
+    /// `price.providers` in failover order, or the single `price.provider`.
+    pub fn price_providers(&self) -> Vec<String> {
+        match self.price.providers.is_empty() {
+            true => vec![self.price.provider.clone()],
+            false => self.price.providers.clone(),
+        }
+    }
+
+    /// `price.currencies` as written, or the single `price.currency`.
+    pub fn price_currency_codes(&self) -> Vec<String> {
+        match self.price.currencies.is_empty() {
+            true => vec![self.price.currency.clone()],
+            false => self.price.currencies.clone(),
+        }
+    }
+
+    /// `price.currencies`, or the single `price.currency`, without the codes that don't
+    /// parse and the ones listed twice. The first one is the main currency, the one
+    /// alerts, the status bar output and the session summary use.
+    pub fn price_currencies(&self) -> Vec<PriceCurrency> {
+        let mut currencies: Vec<PriceCurrency> = vec![];
+
+        for currency in self
+            .price_currency_codes()
+            .iter()
+            .filter_map(|code| PriceCurrency::from_str(code).ok())
+        {
+            // One checker and one step of `c` each, e.g. for "usd" and "USD"
+            if !currencies.contains(&currency) {
+                currencies.push(currency);
+            }
+        }
+
+        currencies
+    }
+
+    /// `node.accent` as a color, a name like `cyan` or RGB like `#ff8800`.
+    pub fn node_accent(&self) -> Option<Color> {
+        Color::from_str(&self.node.accent).ok()
+    }
+
+    /// `proxy` for HTTP clients, as socks5h so it resolves onion hosts itself.
+    pub fn onion_proxy(&self) -> Option<reqwest::Proxy> {
+        reqwest::Proxy::all(format!("socks5h://{}", self.proxy)).ok()
+    }
+
+    /// Prices in the sparkline, one per poll over `price.history_minutes`.
+    pub fn price_history_len(&self) -> usize {
+        (self.price.history_minutes * 60 / price::CHECK_INTERVAL.as_secs()) as usize
+    }
+
+    /// `price.show_ma` windows that parse, as written and in prices, one per poll.
+    pub fn price_ma_windows(&self) -> Vec<(String, usize)> {
+        self.price
+            .show_ma
+            .iter()
+            .filter_map(|window| {
+                let seconds = parse_window(window)?;
+                Some((
+                    window.trim().to_string(),
+                    (seconds / price::CHECK_INTERVAL.as_secs()) as usize,
+                ))
+            })
+            .filter(|(_, samples)| *samples > 1)
+            .collect()
+    }
+
+    /// Prices kept in memory, enough for the sparkline and the longest moving average.
+    pub fn price_history_kept(&self) -> usize {
+        self.price_ma_windows()
+            .iter()
+            .map(|(_, samples)| *samples)
+            .chain([self.price_history_len()])
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Feed `currency` is streamed from, none when it is only polled.
+    pub fn price_stream_url(&self, currency: &PriceCurrency) -> Option<String> {
+        (self.price.stream && self.price.aggregate != "vwap" && currency.listed_by("coinbase"))
+            .then(|| self.price.stream_url.clone())
+    }
+
+    /// Alerts armed from `price.alert_above` and `price.alert_below`.
+    pub fn price_alerts(&self) -> Vec<PriceAlert> {
+        let above =
+            (self.price.alert_above > 0.0).then_some(PriceAlert::Above(self.price.alert_above));
+        let below =
+            (self.price.alert_below > 0.0).then_some(PriceAlert::Below(self.price.alert_below));
+
+        above.into_iter().chain(below).collect()
+    }
+
+    /// Local date of `timestamp`, e.g. `2024-08-01`, days start at midnight in `timezone`.
+    pub fn date(&self, timestamp: i64) -> Option<String> {
+        self.locale()
+            .to_datetime(timestamp)
+            .map(|datetime| datetime.format("%Y-%m-%d").to_string())
+    }
+
+    /// Age at which data polled every `interval` is stale.
+    pub fn stale_after(&self, interval: Duration) -> Duration {
+        interval * self.ui.stale_polls.max(1)
+    }
+
+    /// Time between fee polls, never below 5 seconds.
+    pub fn fees_interval(&self) -> Duration {
+        Duration::from_secs(self.fees.poll_interval.max(5))
+    }
+
+    /// Slow redraws, no big text and no sparklines, for boards like the Pi Zero driving a small display.
+    pub fn low_profile(&self) -> bool {
+        self.performance.profile == "low"
+    }
+
+    /// Milliseconds between ticks, never below a second in the low profile.
+    pub fn tick_interval(&self) -> u64 {
+        let tick_rate = self.tick_rate.parse().unwrap_or(250);
+
+        match self.low_profile() {
+            true => tick_rate.max(LOW_PROFILE_TICK_RATE),
+            false => tick_rate,
+        }
+    }
+
+    pub fn locale(&self) -> Locale {
+        let mut locale = Locale::from_str(&self.locale).unwrap_or_default();
+
+        locale.timezone = DisplayTimezone::from_str(&self.timezone).unwrap_or_default();
+
+        if !self.datetime_format.is_empty() {
+            locale.datetime_format = self.datetime_format.clone();
+        }
+
+        locale
     }
 }
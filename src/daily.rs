@@ -0,0 +1,250 @@
+use serde::{Deserialize, Serialize};
+
+use crate::format::Locale;
+
+/// Key of the per day statistics in the store.
+pub const STORE_KEY: &str = "daily_stats";
+
+/// Days kept for reports, older ones are dropped.
+const DAYS_KEPT: usize = 90;
+
+/// What btcmon saw of one local day, over however many sessions ran during it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DayStats {
+    /// `2024-08-01`, in the configured timezone.
+    pub date: String,
+    pub blocks: u64,
+    /// Medium fee rates seen, summed for the average.
+    pub fee_sum: f64,
+    pub fee_samples: u64,
+    /// Main currency of the prices, they start over if it changes during the day.
+    pub currency: String,
+    pub price_open: Option<f64>,
+    pub price_close: Option<f64>,
+    pub price_low: Option<f64>,
+    pub price_high: Option<f64>,
+}
+
+impl DayStats {
+    fn new(date: &str) -> Self {
+        Self {
+            date: date.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn average_fee(&self) -> Option<f64> {
+        (self.fee_samples > 0).then(|| self.fee_sum / self.fee_samples as f64)
+    }
+
+    /// Relative, `0.05` is +5% from the first price of the day to the last.
+    pub fn price_change(&self) -> Option<f64> {
+        match (self.price_open, self.price_close) {
+            (Some(open), Some(close)) if open > 0.0 => Some(close / open - 1.0),
+            _ => None,
+        }
+    }
+
+    /// Lines for `btcmon report --day` and the end of day summary.
+    pub fn report(&self, locale: &Locale) -> Vec<String> {
+        let price = |value: Option<f64>| match value {
+            Some(value) => format!(
+                "{} {}",
+                locale.format_number(value.trunc(), 0),
+                self.currency
+            ),
+            None => "-".to_string(),
+        };
+
+        let change = match self.price_change() {
+            Some(change) => format!(
+                " ({}{}%)",
+                if change > 0.0 { "+" } else { "" },
+                locale.format_number(change * 100.0, 2)
+            ),
+            None => String::new(),
+        };
+
+        let fee = match self.average_fee() {
+            Some(fee) => format!("{} sat/vB", locale.format_number(fee, 1)),
+            None => "-".to_string(),
+        };
+
+        vec![
+            format!("  {:<14}{}", "Blocks seen", self.blocks),
+            format!("  {:<14}{}", "Average fee", fee),
+            format!("  {:<14}{}", "Price open", price(self.price_open)),
+            format!(
+                "  {:<14}{}{}",
+                "Price close",
+                price(self.price_close),
+                change
+            ),
+            format!(
+                "  {:<14}{} - {}",
+                "Price range",
+                price(self.price_low),
+                price(self.price_high)
+            ),
+        ]
+    }
+}
+
+/// Statistics of the last [`DAYS_KEPT`] days, oldest first, persisted in the store.
+#[derive(Debug, Clone, Default)]
+pub struct DailyStats {
+    pub days: Vec<DayStats>,
+}
+
+impl DailyStats {
+    pub fn new(days: Vec<DayStats>) -> Self {
+        Self { days }
+    }
+
+    pub fn get(&self, date: &str) -> Option<&DayStats> {
+        self.days.iter().find(|day| day.date == date)
+    }
+
+    /// Starts `date` when it is not the current day yet, and hands back the last day
+    /// kept before it, however long ago that was.
+    pub fn roll(&mut self, date: &str) -> Option<DayStats> {
+        if self.days.last().is_some_and(|day| day.date == date) {
+            return None;
+        }
+
+        let previous = self.days.last().cloned();
+        self.days.push(DayStats::new(date));
+        if self.days.len() > DAYS_KEPT {
+            self.days.remove(0);
+        }
+
+        previous
+    }
+
+    fn today(&mut self, date: &str) -> &mut DayStats {
+        self.roll(date);
+        self.days.last_mut().unwrap()
+    }
+
+    pub fn observe_blocks(&mut self, date: &str, blocks: u64) {
+        self.today(date).blocks += blocks;
+    }
+
+    pub fn observe_fee(&mut self, date: &str, rate: f64) {
+        let day = self.today(date);
+        day.fee_sum += rate;
+        day.fee_samples += 1;
+    }
+
+    pub fn observe_price(&mut self, date: &str, currency: &str, price: f64) {
+        let day = self.today(date);
+
+        if day.currency != currency {
+            day.currency = currency.to_string();
+            day.price_open = None;
+            day.price_low = None;
+            day.price_high = None;
+        }
+
+        day.price_open = day.price_open.or(Some(price));
+        day.price_close = Some(price);
+        day.price_low = Some(day.price_low.map_or(price, |low| low.min(price)));
+        day.price_high = Some(day.price_high.map_or(price, |high| high.max(price)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolls_over_on_a_new_date() {
+        let mut stats = DailyStats::default();
+
+        assert_eq!(stats.roll("2024-08-01"), None);
+        stats.observe_blocks("2024-08-01", 3);
+
+        let previous = stats.roll("2024-08-02").unwrap();
+        assert_eq!(previous.date, "2024-08-01");
+        assert_eq!(previous.blocks, 3);
+        assert_eq!(stats.days.last().map(|day| day.blocks), Some(0));
+    }
+
+    #[test]
+    fn same_date_does_not_roll() {
+        let mut stats = DailyStats::default();
+
+        stats.observe_blocks("2024-08-01", 1);
+        assert_eq!(stats.roll("2024-08-01"), None);
+        stats.observe_blocks("2024-08-01", 2);
+
+        assert_eq!(stats.days.len(), 1);
+        assert_eq!(stats.get("2024-08-01").map(|day| day.blocks), Some(3));
+    }
+
+    #[test]
+    fn hands_back_the_last_day_kept_after_a_gap() {
+        let mut stats = DailyStats::default();
+
+        stats.observe_fee("2024-08-01", 10.0);
+        let previous = stats.roll("2024-08-05").unwrap();
+
+        assert_eq!(previous.date, "2024-08-01");
+        assert_eq!(stats.days.len(), 2);
+    }
+
+    #[test]
+    fn keeps_the_last_days_only() {
+        let mut stats = DailyStats::default();
+
+        for day in 0..DAYS_KEPT + 5 {
+            stats.roll(&format!("day-{:03}", day));
+        }
+
+        assert_eq!(stats.days.len(), DAYS_KEPT);
+        assert_eq!(stats.days[0].date, "day-005");
+    }
+
+    #[test]
+    fn tracks_the_price_of_the_day() {
+        let mut stats = DailyStats::default();
+
+        for price in [100.0, 90.0, 120.0, 110.0] {
+            stats.observe_price("2024-08-01", "USD", price);
+        }
+
+        let day = stats.get("2024-08-01").unwrap();
+        assert_eq!(day.price_open, Some(100.0));
+        assert_eq!(day.price_close, Some(110.0));
+        assert_eq!(day.price_low, Some(90.0));
+        assert_eq!(day.price_high, Some(120.0));
+        assert_eq!(day.price_change(), Some(110.0 / 100.0 - 1.0));
+    }
+
+    #[test]
+    fn restarts_the_prices_on_a_currency_change() {
+        let mut stats = DailyStats::default();
+
+        stats.observe_price("2024-08-01", "USD", 100.0);
+        stats.observe_price("2024-08-01", "EUR", 90.0);
+
+        let day = stats.get("2024-08-01").unwrap();
+        assert_eq!(day.currency, "EUR");
+        assert_eq!(day.price_open, Some(90.0));
+        assert_eq!(day.price_low, Some(90.0));
+        assert_eq!(day.price_high, Some(90.0));
+    }
+
+    #[test]
+    fn averages_the_fees_of_the_day() {
+        let mut stats = DailyStats::default();
+
+        assert_eq!(DayStats::new("2024-08-01").average_fee(), None);
+        stats.observe_fee("2024-08-01", 10.0);
+        stats.observe_fee("2024-08-01", 20.0);
+        stats.observe_fee("2024-08-02", 50.0);
+
+        assert_eq!(stats.get("2024-08-01").unwrap().average_fee(), Some(15.0));
+        assert_eq!(stats.get("2024-08-02").unwrap().average_fee(), Some(50.0));
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::VecDeque;
+use tokio::time::{Duration, Instant};
+
+use crate::throughput::{self, Feed};
+
+/// Frames kept for the render time figures.
+const FRAME_SAMPLES: usize = 60;
+
+/// Update counts are sampled every second, a minute of them gives the rates.
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Event loop figures for the F12 overlay, to tell a slow terminal from a busy loop.
+#[derive(Debug)]
+pub struct Diagnostics {
+    pub visible: bool,
+    /// Render times of the latest frames, newest last.
+    frame_times: VecDeque<Duration>,
+    window_start: Instant,
+    window_events: u32,
+    /// Events handled during the last full second.
+    pub events_per_second: u32,
+    /// Events waiting in the channel when the last frame was drawn.
+    pub channel_depth: usize,
+    /// Background tasks still running: pollers, pushes, connection tests.
+    pub tasks: usize,
+    /// Update counts by feed, sampled once a second over the last minute.
+    throughput: VecDeque<(Instant, [u64; 6])>,
+}
+
+impl Default for Diagnostics {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            frame_times: VecDeque::with_capacity(FRAME_SAMPLES),
+            window_start: Instant::now(),
+            window_events: 0,
+            events_per_second: 0,
+            channel_depth: 0,
+            tasks: 0,
+            throughput: VecDeque::new(),
+        }
+    }
+}
+
+impl Diagnostics {
+    pub fn record_event(&mut self) {
+        self.window_events += 1;
+
+        if self.window_start.elapsed() >= Duration::from_secs(1) {
+            self.events_per_second = self.window_events;
+            self.window_events = 0;
+            self.window_start = Instant::now();
+            self.sample_throughput();
+        }
+    }
+
+    fn sample_throughput(&mut self) {
+        let now = Instant::now();
+
+        while self
+            .throughput
+            .get(1)
+            .is_some_and(|(at, _)| now.duration_since(*at) >= THROUGHPUT_WINDOW)
+        {
+            self.throughput.pop_front();
+        }
+
+        self.throughput.push_back((now, throughput::counts()));
+    }
+
+    /// Updates of `feed` per minute over the last minute, or since the first sample.
+    pub fn per_minute(&self, feed: Feed) -> Option<f64> {
+        let ((first_at, first), (last_at, last)) =
+            (self.throughput.front()?, self.throughput.back()?);
+        let elapsed = last_at.duration_since(*first_at);
+
+        if elapsed.is_zero() {
+            return None;
+        }
+
+        let updates = last[feed as usize] - first[feed as usize];
+        Some(updates as f64 / elapsed.as_secs_f64() * 60.0)
+    }
+
+    pub fn record_frame(&mut self, elapsed: Duration) {
+        if self.frame_times.len() == FRAME_SAMPLES {
+            self.frame_times.pop_front();
+        }
+
+        self.frame_times.push_back(elapsed);
+    }
+
+    pub fn last_frame(&self) -> Option<Duration> {
+        self.frame_times.back().copied()
+    }
+
+    pub fn average_frame(&self) -> Option<Duration> {
+        let count = self.frame_times.len() as u32;
+        (count > 0).then(|| self.frame_times.iter().sum::<Duration>() / count)
+    }
+
+    pub fn slowest_frame(&self) -> Option<Duration> {
+        self.frame_times.iter().max().copied()
+    }
+}
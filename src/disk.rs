@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+
+/// Key of the size samples in the store.
+pub const STORE_KEY: &str = "disk_samples";
+
+/// One sample an hour is plenty, the chain grows by the day.
+const SAMPLE_INTERVAL: i64 = 60 * 60;
+
+/// Samples older than this are dropped, the rate follows the recent usage.
+const WINDOW: i64 = 30 * 24 * 60 * 60;
+
+/// Less history than this gives a rate thrown off by a single big block.
+const MIN_SPAN: i64 = 6 * 60 * 60;
+
+const DAY: f64 = 24.0 * 60.0 * 60.0;
+
+pub const GB: u64 = 1024 * 1024 * 1024;
+
+/// `size_on_disk` reported by the node at some point.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DiskSample {
+    pub time: i64,
+    pub size: u64,
+}
+
+/// Disk usage history of the node, persisted so the rate is known right after a restart.
+#[derive(Debug, Clone, Default)]
+pub struct DiskGrowth {
+    pub samples: Vec<DiskSample>,
+}
+
+impl DiskGrowth {
+    pub fn new(samples: Vec<DiskSample>) -> Self {
+        Self { samples }
+    }
+
+    pub fn latest(&self) -> Option<u64> {
+        self.samples.last().map(|sample| sample.size)
+    }
+
+    /// Keeps the sample once the interval since the last one passed, tells whether it did.
+    pub fn observe(&mut self, size: u64, now: i64) -> bool {
+        if self
+            .samples
+            .last()
+            .is_some_and(|last| now - last.time < SAMPLE_INTERVAL)
+        {
+            return false;
+        }
+
+        self.samples.push(DiskSample { time: now, size });
+        self.samples.retain(|sample| now - sample.time <= WINDOW);
+        true
+    }
+
+    /// Average growth over the kept samples, pruning or a reindex can make it negative.
+    pub fn bytes_per_day(&self) -> Option<f64> {
+        let (first, last) = (self.samples.first()?, self.samples.last()?);
+        let span = last.time - first.time;
+
+        if span < MIN_SPAN {
+            return None;
+        }
+
+        Some((last.size as f64 - first.size as f64) / span as f64 * DAY)
+    }
+
+    /// Days until the usage reaches the limit at the current rate, none without a limit
+    /// or when it doesn't grow.
+    pub fn days_left(&self, limit: u64) -> Option<f64> {
+        if limit == 0 {
+            return None;
+        }
+
+        let rate = self.bytes_per_day().filter(|rate| *rate > 0.0)?;
+        let latest = self.latest()?;
+
+        Some(limit.saturating_sub(latest) as f64 / rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 60 * 60;
+
+    /// Growth sampled hourly from `start` GB, `per_day` GB a day, over `hours`.
+    fn growth(start: u64, per_day: u64, hours: i64) -> DiskGrowth {
+        let mut growth = DiskGrowth::default();
+
+        for hour in 0..=hours {
+            growth.observe(start * GB + per_day * GB * hour as u64 / 24, hour * HOUR);
+        }
+
+        growth
+    }
+
+    #[test]
+    fn keeps_one_sample_an_hour() {
+        let mut growth = DiskGrowth::default();
+
+        assert!(growth.observe(GB, 0));
+        assert!(!growth.observe(2 * GB, HOUR - 1));
+        assert!(growth.observe(2 * GB, HOUR));
+        assert_eq!(growth.samples.len(), 2);
+    }
+
+    #[test]
+    fn drops_samples_outside_the_window() {
+        let mut growth = DiskGrowth::default();
+
+        growth.observe(GB, 0);
+        growth.observe(GB, HOUR);
+        growth.observe(GB, WINDOW + HOUR);
+
+        assert_eq!(growth.samples.first().map(|sample| sample.time), Some(HOUR));
+    }
+
+    #[test]
+    fn projects_the_days_left_at_the_current_rate() {
+        let growth = growth(600, 1, 48);
+
+        assert_eq!(growth.bytes_per_day(), Some(GB as f64));
+        assert_eq!(growth.days_left(700 * GB), Some(98.0));
+    }
+
+    #[test]
+    fn needs_enough_history_for_a_rate() {
+        let growth = growth(600, 1, 5);
+
+        assert_eq!(growth.bytes_per_day(), None);
+        assert_eq!(growth.days_left(700 * GB), None);
+    }
+
+    #[test]
+    fn no_projection_without_a_limit_or_growth() {
+        assert_eq!(growth(600, 1, 48).days_left(0), None);
+        assert_eq!(growth(600, 0, 48).days_left(700 * GB), None);
+    }
+
+    #[test]
+    fn no_days_left_once_over_the_limit() {
+        assert_eq!(growth(600, 1, 48).days_left(500 * GB), Some(0.0));
+    }
+}
@@ -1,19 +1,145 @@
+use std::collections::VecDeque;
 use std::time::Duration;
 
 use crossterm::event::{Event as CrosstermEvent, KeyEvent, MouseEvent};
 use futures::{FutureExt, StreamExt};
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 
-use crate::{app::AppResult, fees::FeesState, price::PriceState};
+use crate::{
+    app::AppResult,
+    explorer::ExplorerTip,
+    fees::FeesState,
+    host::HostState,
+    preflight::Check,
+    price::{PriceAlert, PriceCurrency, PriceState},
+};
 
-#[derive(Clone, Debug)]
+/// Which part of the app an event is about, subscribers pick the ones they care for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topic {
+    Node,
+    Market,
+    Ui,
+    System,
+}
+
+impl Topic {
+    pub const ALL: [Topic; 4] = [Topic::Node, Topic::Market, Topic::Ui, Topic::System];
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Event {
+    Node(NodeEvent),
+    Market(MarketEvent),
+    Ui(UiEvent),
+    System(SystemEvent),
+}
+
+impl Event {
+    pub fn topic(&self) -> Topic {
+        match self {
+            Event::Node(_) => Topic::Node,
+            Event::Market(_) => Topic::Market,
+            Event::Ui(_) => Topic::Ui,
+            Event::System(_) => Topic::System,
+        }
+    }
+}
+
+/// The node and the machines around it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum NodeEvent {
+    HostUpdate(HostState),
+    HostError(String),
+    /// Chain tip fetched from the explorer while the node is offline.
+    ExplorerTip(ExplorerTip),
+    /// Results of the connection test triggered from the interface.
+    Preflight(Vec<Check>),
+    /// Why a control action was not run, shown like a key that did nothing.
+    CommandRefused(String),
+}
+
+/// Price and fee rates.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum MarketEvent {
+    PriceUpdate(PriceState),
+    /// Currency of the checker and name of the provider that failed.
+    PriceError(PriceCurrency, String),
+    /// An armed alert was crossed, at this price.
+    PriceAlert(PriceAlert, f64),
+    /// All time high from `price.ath_source`, kept when above the known one.
+    AthSeed(PriceCurrency, f64),
+    FeeUpdate(FeesState),
+    FeeError,
+}
+
+/// Terminal input and the redraw clock.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum UiEvent {
     Tick,
     Key(KeyEvent),
     Mouse(MouseEvent),
     Resize(u16, u16),
-    PriceUpdate(PriceState),
-    FeeUpdate(FeesState),
+}
+
+/// Outcomes of what the app itself did.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum SystemEvent {
+    /// Title and message of a push, past the quiet windows already.
+    Push(String, String),
+    /// Test push asked for from the interface, answered with a `TestNotification`.
+    TestPush,
+    /// Whether the test push triggered from the interface went through.
+    TestNotification(bool),
+}
+
+impl From<NodeEvent> for Event {
+    fn from(event: NodeEvent) -> Self {
+        Event::Node(event)
+    }
+}
+
+impl From<MarketEvent> for Event {
+    fn from(event: MarketEvent) -> Self {
+        Event::Market(event)
+    }
+}
+
+impl From<UiEvent> for Event {
+    fn from(event: UiEvent) -> Self {
+        Event::Ui(event)
+    }
+}
+
+impl From<SystemEvent> for Event {
+    fn from(event: SystemEvent) -> Self {
+        Event::System(event)
+    }
+}
+
+/// Hands the events the app handles to the subsystems subscribed to their topic as well,
+/// so those don't have to be wired into the app's own handling.
+#[derive(Debug, Default)]
+pub struct EventBus {
+    subscribers: Vec<(Vec<Topic>, mpsc::UnboundedSender<Event>)>,
+}
+
+impl EventBus {
+    pub fn subscribe(&mut self, topics: &[Topic]) -> mpsc::UnboundedReceiver<Event> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.subscribers.push((topics.to_vec(), sender));
+        receiver
+    }
+
+    /// Subscribers that went away are dropped.
+    pub fn publish(&mut self, event: &Event) {
+        let topic = event.topic();
+
+        self.subscribers.retain(|(topics, sender)| {
+            !topics.contains(&topic) || sender.send(event.clone()).is_ok()
+        });
+    }
 }
 
 #[allow(dead_code)]
@@ -22,6 +148,8 @@ pub struct EventHandler {
     pub sender: mpsc::UnboundedSender<Event>,
     pub receiver: mpsc::UnboundedReceiver<Event>,
     handler: tokio::task::JoinHandle<()>,
+    /// Events taken off the channel to count them, handed out before newer ones.
+    pending: VecDeque<Event>,
 }
 
 impl EventHandler {
@@ -43,20 +171,20 @@ impl EventHandler {
                     break;
                   }
                   _ = tick_delay => {
-                    _sender.send(Event::Tick).unwrap();
+                    _sender.send(UiEvent::Tick.into()).unwrap();
                   }
                   Some(Ok(evt)) = crossterm_event => {
                     match evt {
                       CrosstermEvent::Key(key) => {
                         if key.kind == crossterm::event::KeyEventKind::Press {
-                          _sender.send(Event::Key(key)).unwrap();
+                          _sender.send(UiEvent::Key(key).into()).unwrap();
                         }
                       },
                       CrosstermEvent::Mouse(mouse) => {
-                        _sender.send(Event::Mouse(mouse)).unwrap();
+                        _sender.send(UiEvent::Mouse(mouse).into()).unwrap();
                       },
                       CrosstermEvent::Resize(x, y) => {
-                        _sender.send(Event::Resize(x, y)).unwrap();
+                        _sender.send(UiEvent::Resize(x, y).into()).unwrap();
                       },
                       CrosstermEvent::FocusLost => {
                       },
@@ -73,6 +201,36 @@ impl EventHandler {
             sender,
             receiver,
             handler,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Ticks only, for output modes without a terminal to read keys from.
+    pub fn ticks(
+        tick_rate: u64,
+        sender: mpsc::UnboundedSender<Event>,
+        receiver: mpsc::UnboundedReceiver<Event>,
+    ) -> Self {
+        let tick_rate = Duration::from_millis(tick_rate);
+        let _sender = sender.clone();
+        let handler = tokio::spawn(async move {
+            let mut tick = tokio::time::interval(tick_rate);
+            loop {
+                tokio::select! {
+                  _ = _sender.closed() => {
+                    break;
+                  }
+                  _ = tick.tick() => {
+                    _sender.send(UiEvent::Tick.into()).unwrap();
+                  }
+                };
+            }
+        });
+        Self {
+            sender,
+            receiver,
+            handler,
+            pending: VecDeque::new(),
         }
     }
 
@@ -81,10 +239,23 @@ impl EventHandler {
     /// This function will always block the current thread if
     /// there is no data available and it's possible for more data to be sent.
     pub async fn next(&mut self) -> AppResult<Event> {
+        if let Some(event) = self.pending.pop_front() {
+            return Ok(event);
+        }
+
         let receiver = &mut self.receiver;
-        receiver.recv().await.ok_or(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "This is an IO error",
-        )))
+        receiver
+            .recv()
+            .await
+            .ok_or(Box::new(std::io::Error::other("This is an IO error")))
+    }
+
+    /// Events waiting to be handled.
+    pub fn depth(&mut self) -> usize {
+        while let Ok(event) = self.receiver.try_recv() {
+            self.pending.push_back(event);
+        }
+
+        self.pending.len()
     }
 }
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::time::{self, Duration};
+
+use crate::app::AppThread;
+use crate::bandwidth::{self, Source, HTTP_OVERHEAD};
+use crate::config::AppConfig;
+use crate::event::NodeEvent;
+use crate::node::providers::http_client;
+
+/// How often the explorer is asked while the node stays offline.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Chain tip as an Esplora compatible explorer sees it, shown while the node is offline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExplorerTip {
+    pub height: u64,
+    pub hash: String,
+    pub time: Option<i64>,
+    /// When it was fetched, to tell how old the fallback is.
+    pub fetched_at: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockResponse {
+    height: u64,
+    timestamp: i64,
+}
+
+async fn get(client: &reqwest::Client, url: String) -> Result<String> {
+    let body = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    bandwidth::record(Source::Node, body.len() + HTTP_OVERHEAD);
+
+    Ok(body)
+}
+
+/// Tip height, hash and time from `explorer.url`, e.g. `https://mempool.space/api`.
+pub async fn fetch_tip(config: &AppConfig) -> Result<ExplorerTip> {
+    let base_url = config.explorer.url.trim_end_matches('/');
+    let client = http_client(config, base_url);
+
+    let fetch = async {
+        let hash = get(&client, format!("{}/blocks/tip/hash", base_url)).await?;
+        let hash = hash.trim().to_string();
+        // The height of the block itself, the tip may have moved on since the hash
+        let block = get(&client, format!("{}/block/{}", base_url, hash)).await?;
+        let block = serde_json::from_str::<BlockResponse>(&block)?;
+
+        Ok::<_, anyhow::Error>(ExplorerTip {
+            height: block.height,
+            time: Some(block.timestamp),
+            hash,
+            fetched_at: Utc::now().timestamp(),
+        })
+    };
+
+    time::timeout(TIMEOUT, fetch)
+        .await
+        .map_err(|_| anyhow::Error::msg("Timed out"))?
+}
+
+/// Fetches the tip once, failures are dropped and the next attempt comes a minute later.
+pub fn spawn_explorer_fetch(thread: AppThread, config: AppConfig) {
+    thread.tracker.spawn(async move {
+        tokio::select! {
+            () = thread.token.cancelled() => {}
+            tip = fetch_tip(&config) => {
+                if let Ok(tip) = tip {
+                    let _ = thread.sender.send(NodeEvent::ExplorerTip(tip).into());
+                }
+            }
+        }
+    });
+}
@@ -1,40 +1,187 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
 use tokio::sync::mpsc;
+use tokio::time::{timeout, Duration, Instant};
 use tokio_util::sync::CancellationToken;
 
-use crate::{app::AppThread, event::Event};
+use crate::{
+    app::AppThread,
+    bandwidth,
+    config::AppConfig,
+    event::{Event, MarketEvent},
+    health::SourceHealth,
+    throughput::{self, Feed},
+};
 
 pub mod providers;
 
-#[derive(Debug, Clone)]
+/// Default of `fees.poll_interval`.
+pub const CHECK_INTERVAL: Duration = Duration::from_millis(20 * 1000);
+
+/// Consecutive failures after which the next provider of `fees.providers` takes over.
+const FAILOVER_AFTER: u32 = 3;
+
+/// How long a fallback provider is used before the first one of `fees.providers` is
+/// given another try.
+const RETRY_FIRST_AFTER: Duration = Duration::from_secs(5 * 60);
+
+/// Fee samples kept for the sparkline, two hours at the default interval.
+pub const HISTORY_LEN: usize = 360;
+
+/// Name of the provider estimating with the node itself.
+const LOCAL_PROVIDER: &str = "bitcoin_core";
+
+/// Times one normal rate has to be the other for the local node and an external API
+/// to be called at odds.
+const DIVERGENCE_RATIO: f64 = 3.0;
+
+/// Below this difference in sat/vB the rates are close enough, whatever the ratio.
+const DIVERGENCE_MIN_DIFFERENCE: f64 = 5.0;
+
+/// Longest a request made next to the rates is waited for, the backlog, the next block
+/// or the other side of the comparison. The rates go out without it past that.
+const EXTRA_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Size of a typical one input, two output segwit payment, the default fiat costing size.
+pub const TYPICAL_TX_VBYTES: u64 = 140;
+
+/// Rates in sat/vB by confirmation target in blocks.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FeeResult {
-    pub low: Option<String>,
-    pub medium: Option<String>,
-    pub high: Option<String>,
+    pub rates: BTreeMap<u64, f64>,
+}
+
+impl FeeResult {
+    /// Rate for the target, or for the closest faster one the provider estimates.
+    pub fn rate(&self, target: u64) -> Option<f64> {
+        self.rates
+            .range(..=target)
+            .next_back()
+            .map(|(_, rate)| *rate)
+    }
+
+    /// Only the configured targets, whatever the provider happens to estimate.
+    pub fn for_targets(&self, targets: &[u64]) -> FeeResult {
+        FeeResult {
+            rates: targets
+                .iter()
+                .filter_map(|target| Some((*target, self.rate(*target)?)))
+                .collect(),
+        }
+    }
+
+    /// Rate of the slowest target.
+    pub fn low(&self) -> Option<f64> {
+        self.rates.values().next_back().copied()
+    }
+
+    /// Rate of the middle target, the normal priority with the default targets.
+    pub fn medium(&self) -> Option<f64> {
+        self.rates.values().nth(self.rates.len() / 2).copied()
+    }
+
+    /// Rate of the fastest target.
+    pub fn high(&self) -> Option<f64> {
+        self.rates.values().next().copied()
+    }
+}
+
+/// Normal rates of the node and of an external API far apart, usually a node that just
+/// restarted or sees too few transactions to estimate from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeDivergence {
+    /// `estimatesmartfee` of the node.
+    pub local: f64,
+    pub external: f64,
+    /// Provider the external rate came from.
+    pub external_provider: String,
+}
+
+impl FeeDivergence {
+    /// Some when `local` and `external` are far enough apart to point at a problem.
+    pub fn check(local: f64, external: f64, external_provider: &str) -> Option<FeeDivergence> {
+        let (low, high) = (local.min(external), local.max(external));
+
+        match high - low >= DIVERGENCE_MIN_DIFFERENCE && high >= low * DIVERGENCE_RATIO {
+            true => Some(FeeDivergence {
+                local,
+                external,
+                external_provider: external_provider.to_string(),
+            }),
+            false => None,
+        }
+    }
+}
+
+/// Transactions waiting in the mempool as a provider sees it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MempoolStats {
+    pub count: u64,
+    pub vsize: u64,
+}
+
+impl MempoolStats {
+    /// Full blocks needed to clear the backlog if nothing else came in.
+    pub fn blocks_to_clear(&self) -> u64 {
+        self.vsize.div_ceil(MAX_BLOCK_VSIZE)
+    }
 }
 
+/// Fee rates the provider expects the next block to include.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectedBlock {
+    /// Rates in sat/vB from the lowest to the highest included, as mempool.space gives
+    /// them: minimum, 10th, 25th, 50th, 75th and 90th percentile and maximum.
+    pub fee_range: Vec<f64>,
+    pub median_fee: f64,
+    pub count: u64,
+    pub vsize: u64,
+}
+
+/// Four million weight units.
+pub const MAX_BLOCK_VSIZE: u64 = 1_000_000;
+
 #[async_trait]
 pub trait FeeServiceProvider {
-    fn new() -> Self;
+    fn new(config: &AppConfig) -> Self
+    where
+        Self: Sized;
     async fn fetch_current_fees(&mut self) -> Result<FeeResult, Box<dyn std::error::Error>>;
+    /// Size of the backlog, for providers that know it.
+    async fn fetch_mempool(&mut self) -> Result<Option<MempoolStats>, Box<dyn std::error::Error>> {
+        Ok(None)
+    }
+    /// Projection of the next block, for providers that make one.
+    async fn fetch_next_block(
+        &mut self,
+    ) -> Result<Option<ProjectedBlock>, Box<dyn std::error::Error>> {
+        Ok(None)
+    }
 }
 
-#[derive(Debug, Clone)]
+/// A provider with the name it is configured by.
+pub type NamedProvider = (String, Box<dyn FeeServiceProvider + Send>);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FeesState {
     pub result: FeeResult,
-}
-
-impl Default for FeesState {
-    fn default() -> Self {
-        Self {
-            result: FeeResult {
-                low: None,
-                medium: None,
-                high: None,
-            },
-        }
-    }
+    #[serde(skip, default = "SourceHealth::fresh")]
+    pub health: SourceHealth,
+    /// Name of the provider the result came from.
+    pub provider: String,
+    pub mempool: Option<MempoolStats>,
+    pub next_block: Option<ProjectedBlock>,
+    /// Set when the node and an external API of `fees.providers` disagree.
+    #[serde(default)]
+    pub divergence: Option<FeeDivergence>,
+    /// Previous results, oldest first, kept in memory by the app.
+    #[serde(skip)]
+    pub history: VecDeque<FeeResult>,
+    /// Unix time of each result in the history.
+    #[serde(skip)]
+    pub history_times: VecDeque<i64>,
 }
 
 impl FeesState {
@@ -49,63 +196,250 @@ pub struct FeeService<TProvider: FeeServiceProvider> {
 }
 
 impl<TProvider: FeeServiceProvider> FeeService<TProvider> {
-    pub fn new() -> Self {
-        Self::default()
-    }
-}
-
-impl<TProvider: FeeServiceProvider> Default for FeeService<TProvider> {
-    fn default() -> Self {
+    pub fn new(config: &AppConfig) -> Self {
         Self {
-            provider: TProvider::new(),
+            provider: TProvider::new(config),
             result: None,
         }
     }
 }
 
-pub fn spawn_fees_checker<T: FeeServiceProvider>(thread: AppThread)
-where
-    T: Send,
-{
+/// Polls the first provider, falling through to the next one, and on around, once
+/// the active one failed [`FAILOVER_AFTER`] times in a row. The first one is back
+/// after [`RETRY_FIRST_AFTER`], falling through again if it still fails.
+pub fn spawn_fees_checker(thread: AppThread, config: &AppConfig, providers: Vec<NamedProvider>) {
+    let targets = config.fees.targets.clone();
+    let interval = config.fees_interval();
+    // Extra requests are only made for the panels that show them
+    let extras = (config.mempool.enabled, config.mempool.next_block);
+
     thread.tracker.spawn(async move {
         tokio::select! {
             () = thread.token.cancelled() => {}
-            () = fees_checker::<T>(thread.sender, thread.token.clone()) => {}
+            () = fees_checker(thread.sender, thread.token.clone(), providers, targets, interval, extras) => {}
         }
     });
 }
 
-async fn fees_checker<T: FeeServiceProvider>(
+/// Which of `fees.providers` is asked, falling through to the next one on failures
+/// and back to the first one after a while.
+#[derive(Debug, Default)]
+struct Failover {
+    active: usize,
+    failures: u32,
+    failed_over_at: Option<Instant>,
+}
+
+impl Failover {
+    /// Back to the first provider once a fallback was used for [`RETRY_FIRST_AFTER`].
+    fn retry_first(&mut self, now: Instant) {
+        if self.active != 0
+            && self
+                .failed_over_at
+                .is_some_and(|at| now.duration_since(at) >= RETRY_FIRST_AFTER)
+        {
+            *self = Failover::default();
+        }
+    }
+
+    fn succeeded(&mut self) {
+        self.failures = 0;
+    }
+
+    /// Counts a failure of the active one of `providers`, whether the next one took over.
+    fn failed(&mut self, providers: usize, now: Instant) -> bool {
+        self.failures += 1;
+
+        if self.failures < FAILOVER_AFTER || providers < 2 {
+            return false;
+        }
+
+        self.active = (self.active + 1) % providers;
+        self.failures = 0;
+        self.failed_over_at = Some(now);
+        true
+    }
+}
+
+async fn fees_checker(
     sender: mpsc::UnboundedSender<Event>,
     token: CancellationToken,
+    mut providers: Vec<NamedProvider>,
+    targets: Vec<u64>,
+    interval: Duration,
+    (with_mempool, with_next_block): (bool, bool),
 ) {
-    let mut provider = T::new();
-    let interval = tokio::time::Duration::from_millis(20 * 1000);
+    let mut failover = Failover::default();
 
-    loop {
+    while !providers.is_empty() {
         if token.is_cancelled() {
             break;
         }
-        tokio::select! {
-            () = token.cancelled() => {}
-            res = provider.fetch_current_fees() => {
-                let _ = match res {
-                    Ok(res) => sender.send(Event::FeeUpdate(FeesState {
-                        result: FeeResult {
-                            low: res.low,
-                            medium: res.medium,
-                            high: res.high,
-                        }
-                    })),
-                    Err(_) => Ok(()),
+
+        failover.retry_first(Instant::now());
+
+        let (name, provider) = &mut providers[failover.active];
+        let name = name.clone();
+
+        // The error is not kept, it can't be held over the backlog request
+        let res = tokio::select! {
+            () = token.cancelled() => break,
+            res = provider.fetch_current_fees() => res.ok(),
+        };
+
+        let failed_over = match res {
+            Some(res) => {
+                failover.succeeded();
+                throughput::record(Feed::Fees);
+                // A backlog that can't be read doesn't make the rates any worse
+                let mempool = match with_mempool {
+                    true => timeout(EXTRA_TIMEOUT, provider.fetch_mempool())
+                        .await
+                        .ok()
+                        .and_then(|res| res.ok().flatten()),
+                    false => None,
+                };
+                let next_block = match with_next_block {
+                    true => timeout(EXTRA_TIMEOUT, provider.fetch_next_block())
+                        .await
+                        .ok()
+                        .and_then(|res| res.ok().flatten()),
+                    false => None,
+                };
+                let result = res.for_targets(&targets);
+                let divergence = tokio::select! {
+                    () = token.cancelled() => break,
+                    divergence = timeout(
+                        EXTRA_TIMEOUT,
+                        compare(&mut providers, &name, &result, &targets),
+                    ) => divergence.ok().flatten(),
                 };
 
+                let _ = sender.send(
+                    MarketEvent::FeeUpdate(FeesState {
+                        result,
+                        health: SourceHealth::fresh(),
+                        provider: name,
+                        mempool,
+                        next_block,
+                        divergence,
+                        ..FeesState::default()
+                    })
+                    .into(),
+                );
+                false
+            }
+            None => {
+                let _ = sender.send(MarketEvent::FeeError.into());
+                failover.failed(providers.len(), Instant::now())
             }
+        };
+
+        // The next provider is asked right away rather than after another interval
+        if failed_over {
+            continue;
         }
 
         tokio::select! {
             () = token.cancelled() => {}
-            () = tokio::time::sleep(interval) => {}
+            () = tokio::time::sleep(bandwidth::stretch(interval)) => {}
+        }
+    }
+}
+
+/// Asks the other side, the node when the active provider is an external API or the
+/// first external one when it is the node, and compares their normal rates. Nothing to
+/// compare unless `fees.providers` has both.
+async fn compare(
+    providers: &mut [NamedProvider],
+    active: &str,
+    result: &FeeResult,
+    targets: &[u64],
+) -> Option<FeeDivergence> {
+    let local = active == LOCAL_PROVIDER;
+    let (other, provider) = providers
+        .iter_mut()
+        .find(|(name, _)| name != active && (name == LOCAL_PROVIDER) != local)?;
+
+    let other_result = provider
+        .fetch_current_fees()
+        .await
+        .ok()?
+        .for_targets(targets);
+    let (rate, other_rate) = (result.medium()?, other_result.medium()?);
+
+    match local {
+        true => FeeDivergence::check(rate, other_rate, other),
+        false => FeeDivergence::check(other_rate, rate, active),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failover_walks_the_providers_in_order() {
+        let now = Instant::now();
+        let mut failover = Failover::default();
+
+        for provider in [1, 2, 0, 1] {
+            for _ in 1..FAILOVER_AFTER {
+                assert!(!failover.failed(3, now));
+            }
+            assert!(failover.failed(3, now));
+            assert_eq!(failover.active, provider);
+        }
+    }
+
+    #[test]
+    fn failover_needs_failures_in_a_row() {
+        let now = Instant::now();
+        let mut failover = Failover::default();
+
+        for _ in 0..5 {
+            for _ in 1..FAILOVER_AFTER {
+                assert!(!failover.failed(2, now));
+            }
+            failover.succeeded();
+        }
+
+        assert_eq!(failover.active, 0);
+    }
+
+    #[test]
+    fn failover_stays_on_a_single_provider() {
+        let now = Instant::now();
+        let mut failover = Failover::default();
+
+        for _ in 0..FAILOVER_AFTER * 3 {
+            assert!(!failover.failed(1, now));
+        }
+
+        assert_eq!(failover.active, 0);
+    }
+
+    #[test]
+    fn failover_retries_the_first_provider() {
+        let now = Instant::now();
+        let mut failover = Failover::default();
+
+        for _ in 0..FAILOVER_AFTER {
+            failover.failed(3, now);
+        }
+        assert_eq!(failover.active, 1);
+
+        failover.retry_first(now + RETRY_FIRST_AFTER - Duration::from_secs(1));
+        assert_eq!(failover.active, 1);
+
+        failover.retry_first(now + RETRY_FIRST_AFTER);
+        assert_eq!(failover.active, 0);
+        assert_eq!(failover.failures, 0);
+
+        // The first one still failing falls through again
+        for _ in 0..FAILOVER_AFTER {
+            failover.failed(3, now + RETRY_FIRST_AFTER);
         }
+        assert_eq!(failover.active, 1);
     }
 }
@@ -0,0 +1,65 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::config::AppConfig;
+use crate::fees::{FeeResult, FeeServiceProvider, MempoolStats};
+use crate::node::providers::bitcoin_core_client;
+use bitcoincore_rpc::json::EstimateMode;
+use bitcoincore_rpc::RpcApi;
+
+/// Fee estimates of the configured Bitcoin Core, no third party involved.
+pub struct FeesBitcoinCore {
+    rpc_client: Arc<bitcoincore_rpc::Client>,
+    /// Confirmation targets in blocks, as configured.
+    targets: Vec<u64>,
+}
+
+impl FeesBitcoinCore {
+    /// Rate in sat/vB for a confirmation within `target` blocks, none while the node
+    /// has not seen enough transactions to estimate.
+    async fn estimate(&self, target: u64) -> Result<Option<f64>, Box<dyn std::error::Error>> {
+        let rpc_client = self.rpc_client.clone();
+        // The node estimates up to 1008 blocks
+        let blocks = target.clamp(1, 1008) as u16;
+        let estimate = tokio::task::spawn_blocking(move || {
+            rpc_client.estimate_smart_fee(blocks, Some(EstimateMode::Conservative))
+        })
+        .await??;
+
+        // BTC per kvB
+        Ok(estimate.fee_rate.map(|rate| rate.to_sat() as f64 / 1000.0))
+    }
+}
+
+#[async_trait]
+impl FeeServiceProvider for FeesBitcoinCore {
+    fn new(config: &AppConfig) -> Self {
+        Self {
+            rpc_client: Arc::new(bitcoin_core_client(config)),
+            targets: config.fees.targets.clone(),
+        }
+    }
+
+    async fn fetch_current_fees(&mut self) -> Result<FeeResult, Box<dyn std::error::Error>> {
+        let mut result = FeeResult::default();
+
+        for target in self.targets.iter() {
+            if let Some(rate) = self.estimate(*target).await? {
+                result.rates.insert(*target, rate);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// `bytes` of getmempoolinfo is the sum of the virtual sizes.
+    async fn fetch_mempool(&mut self) -> Result<Option<MempoolStats>, Box<dyn std::error::Error>> {
+        let rpc_client = self.rpc_client.clone();
+        let info = tokio::task::spawn_blocking(move || rpc_client.get_mempool_info()).await??;
+
+        Ok(Some(MempoolStats {
+            count: info.size as u64,
+            vsize: info.bytes as u64,
+        }))
+    }
+}
@@ -0,0 +1,74 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::bandwidth::{self, Source, HTTP_OVERHEAD};
+use crate::config::AppConfig;
+use crate::fees::{FeeResult, FeeServiceProvider, MempoolStats};
+use crate::node::providers::http_client;
+
+/// Fee estimates of a Blockstream Esplora API, like a self-hosted esplora or electrs.
+pub struct FeesEsplora {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MempoolResponse {
+    count: u64,
+    vsize: u64,
+}
+
+#[async_trait]
+impl FeeServiceProvider for FeesEsplora {
+    /// API at `fees.esplora_url`, through the proxy when it is an onion service.
+    fn new(config: &AppConfig) -> Self {
+        Self {
+            client: http_client(config, &config.fees.esplora_url),
+            base_url: config.fees.esplora_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn fetch_current_fees(&mut self) -> Result<FeeResult, Box<dyn std::error::Error>> {
+        let bytes = self
+            .client
+            .get(format!("{}/fee-estimates", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        bandwidth::record(Source::Fees, bytes.len() + HTTP_OVERHEAD);
+
+        // Keyed by target as a string, only a few between 25 and 1008 blocks
+        let estimates = serde_json::from_slice::<HashMap<String, f64>>(&bytes)?;
+
+        Ok(FeeResult {
+            rates: estimates
+                .into_iter()
+                .filter_map(|(target, rate)| Some((target.parse().ok()?, rate)))
+                .collect(),
+        })
+    }
+
+    async fn fetch_mempool(&mut self) -> Result<Option<MempoolStats>, Box<dyn std::error::Error>> {
+        let bytes = self
+            .client
+            .get(format!("{}/mempool", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        bandwidth::record(Source::Fees, bytes.len() + HTTP_OVERHEAD);
+
+        let body = serde_json::from_slice::<MempoolResponse>(&bytes)?;
+
+        Ok(Some(MempoolStats {
+            count: body.count,
+            vsize: body.vsize,
+        }))
+    }
+}
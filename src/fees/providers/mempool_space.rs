@@ -0,0 +1,121 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::bandwidth::{self, Source, HTTP_OVERHEAD};
+use crate::config::AppConfig;
+use crate::fees::{FeeResult, FeeServiceProvider, MempoolStats, ProjectedBlock};
+use crate::node::providers::http_client;
+
+/// Recommended fees of mempool.space or a self-hosted instance of it.
+pub struct FeesMempoolSpace {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecommendedFeesResponse {
+    fastest_fee: f64,
+    half_hour_fee: f64,
+    hour_fee: f64,
+    economy_fee: f64,
+    minimum_fee: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct MempoolResponse {
+    count: u64,
+    vsize: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MempoolBlockResponse {
+    block_v_size: f64,
+    n_tx: u64,
+    median_fee: f64,
+    fee_range: Vec<f64>,
+}
+
+#[async_trait]
+impl FeeServiceProvider for FeesMempoolSpace {
+    /// Instance at `fees.mempool_url`, through the proxy when it is an onion service.
+    fn new(config: &AppConfig) -> Self {
+        Self {
+            client: http_client(config, &config.fees.mempool_url),
+            base_url: config.fees.mempool_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn fetch_current_fees(&mut self) -> Result<FeeResult, Box<dyn std::error::Error>> {
+        let bytes = self
+            .client
+            .get(format!("{}/api/v1/fees/recommended", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        bandwidth::record(Source::Fees, bytes.len() + HTTP_OVERHEAD);
+
+        let body = serde_json::from_slice::<RecommendedFeesResponse>(&bytes)?;
+
+        // Next block, half an hour, an hour, about a day and whenever
+        Ok(FeeResult {
+            rates: [
+                (1, body.fastest_fee),
+                (3, body.half_hour_fee),
+                (6, body.hour_fee),
+                (144, body.economy_fee),
+                (1008, body.minimum_fee),
+            ]
+            .into(),
+        })
+    }
+
+    async fn fetch_mempool(&mut self) -> Result<Option<MempoolStats>, Box<dyn std::error::Error>> {
+        let bytes = self
+            .client
+            .get(format!("{}/api/mempool", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        bandwidth::record(Source::Fees, bytes.len() + HTTP_OVERHEAD);
+
+        let body = serde_json::from_slice::<MempoolResponse>(&bytes)?;
+
+        Ok(Some(MempoolStats {
+            count: body.count,
+            vsize: body.vsize,
+        }))
+    }
+
+    async fn fetch_next_block(
+        &mut self,
+    ) -> Result<Option<ProjectedBlock>, Box<dyn std::error::Error>> {
+        let bytes = self
+            .client
+            .get(format!("{}/api/v1/fees/mempool-blocks", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        bandwidth::record(Source::Fees, bytes.len() + HTTP_OVERHEAD);
+
+        // Projected blocks in mining order, only the first one is of interest
+        let blocks = serde_json::from_slice::<Vec<MempoolBlockResponse>>(&bytes)?;
+
+        Ok(blocks.into_iter().next().map(|block| ProjectedBlock {
+            fee_range: block.fee_range,
+            median_fee: block.median_fee,
+            count: block.n_tx,
+            vsize: block.block_v_size as u64,
+        }))
+    }
+}
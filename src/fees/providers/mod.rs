@@ -2,25 +2,25 @@ use async_trait::async_trait;
 use serde::Deserialize;
 
 use super::{FeeResult, FeeServiceProvider};
-pub struct FeesBlockchainInfo;
+use crate::bandwidth::{self, Source, HTTP_OVERHEAD};
+use crate::config::AppConfig;
 
-#[derive(Debug, Deserialize)]
-struct BlockchainInfoResponseLimits {
-    // min: u32,
-    // max: u32,
-}
+pub mod bitcoin_core;
+pub mod esplora;
+pub mod mempool_space;
+
+pub struct FeesBlockchainInfo;
 
 #[derive(Debug, Deserialize)]
 struct BlockchainInfoResponse {
-    // limits: BlockchainInfoResponseLimits,
-    regular: u32,
-    priority: u32,
+    regular: f64,
+    priority: f64,
 }
 
 #[async_trait]
 impl FeeServiceProvider for FeesBlockchainInfo {
-    fn new() -> Self {
-        Self::default()
+    fn new(_config: &AppConfig) -> Self {
+        Self
     }
 
     async fn fetch_current_fees(&mut self) -> Result<FeeResult, Box<dyn std::error::Error>> {
@@ -39,18 +39,20 @@ impl FeeServiceProvider for FeesBlockchainInfo {
             return Err(e.into());
         }
 
-        let json = request.unwrap().json::<BlockchainInfoResponse>().await;
+        let bytes = request.unwrap().bytes().await;
 
-        if let Err(e) = json {
+        if let Err(e) = bytes {
             return Err(e.into());
         }
 
-        let body = json.unwrap();
+        let bytes = bytes.unwrap();
+        bandwidth::record(Source::Fees, bytes.len() + HTTP_OVERHEAD);
+
+        let body = serde_json::from_slice::<BlockchainInfoResponse>(&bytes)?;
 
+        // Priority aims at the next block, regular at about an hour
         Ok(FeeResult {
-            high: Some(format!("{}", body.priority)),
-            medium: Some(format!("{}", body.regular)),
-            low: None,
+            rates: [(1, body.priority), (6, body.regular)].into(),
         })
     }
 }
@@ -0,0 +1,298 @@
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset, Local, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::fmt;
+use std::str::FromStr;
+
+/// Timezone used to render absolute timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DisplayTimezone {
+    #[default]
+    Local,
+    Named(Tz),
+}
+
+impl FromStr for DisplayTimezone {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<DisplayTimezone> {
+        match input {
+            "" | "local" => Ok(DisplayTimezone::Local),
+            _ => Tz::from_str(input)
+                .map(DisplayTimezone::Named)
+                .map_err(|_| anyhow::Error::msg("Timezone not supported")),
+        }
+    }
+}
+
+/// Number and date conventions used to render values on screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Locale {
+    pub decimal_separator: char,
+    pub group_separator: Option<char>,
+    pub datetime_format: String,
+    pub timezone: DisplayTimezone,
+    /// `$68,512` rather than `68.512 €`.
+    pub symbol_first: bool,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self {
+            decimal_separator: '.',
+            group_separator: Some(','),
+            datetime_format: "%m/%d/%Y %H:%M:%S".to_string(),
+            timezone: DisplayTimezone::Local,
+            symbol_first: true,
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = anyhow::Error;
+
+    /// Accepts POSIX style tags like `de_DE`, `de-DE`, `de_DE.UTF-8` or a bare language.
+    fn from_str(input: &str) -> Result<Locale> {
+        let tag = input
+            .split('.')
+            .next()
+            .unwrap_or_default()
+            .replace('-', "_");
+        let (language, region) = tag.split_once('_').unwrap_or((tag.as_str(), ""));
+
+        let locale = match (
+            language.to_lowercase().as_str(),
+            region.to_uppercase().as_str(),
+        ) {
+            ("en", "US") | ("en", "") => Locale::default(),
+            ("en", _) => Locale {
+                datetime_format: "%d/%m/%Y %H:%M:%S".to_string(),
+                ..Locale::default()
+            },
+            ("de", "CH") => Locale {
+                decimal_separator: '.',
+                group_separator: Some('\''),
+                datetime_format: "%d.%m.%Y %H:%M:%S".to_string(),
+                ..Locale::default()
+            },
+            ("de", _) => Locale {
+                decimal_separator: ',',
+                group_separator: Some('.'),
+                datetime_format: "%d.%m.%Y %H:%M:%S".to_string(),
+                symbol_first: false,
+                ..Locale::default()
+            },
+            ("nl", _) | ("id", _) => Locale {
+                decimal_separator: ',',
+                group_separator: Some('.'),
+                datetime_format: "%d/%m/%Y %H:%M:%S".to_string(),
+                ..Locale::default()
+            },
+            ("es", _) | ("it", _) | ("pt", _) | ("tr", _) => Locale {
+                decimal_separator: ',',
+                group_separator: Some('.'),
+                datetime_format: "%d/%m/%Y %H:%M:%S".to_string(),
+                symbol_first: false,
+                ..Locale::default()
+            },
+            ("fr", _) | ("ru", _) | ("pl", _) | ("cs", _) | ("sv", _) | ("fi", _) | ("nb", _) => {
+                Locale {
+                    decimal_separator: ',',
+                    group_separator: Some('\u{202F}'),
+                    datetime_format: "%d/%m/%Y %H:%M:%S".to_string(),
+                    symbol_first: false,
+                    ..Locale::default()
+                }
+            }
+            ("ja", _) | ("zh", _) | ("ko", _) => Locale {
+                datetime_format: "%Y/%m/%d %H:%M:%S".to_string(),
+                ..Locale::default()
+            },
+            ("c", _) | ("posix", _) => Locale {
+                group_separator: None,
+                datetime_format: "%Y-%m-%d %H:%M:%S".to_string(),
+                ..Locale::default()
+            },
+            _ => return Err(anyhow::Error::msg("Locale not supported")),
+        };
+
+        Ok(locale)
+    }
+}
+
+impl Locale {
+    pub fn format_number(&self, value: f64, decimals: usize) -> String {
+        let formatted = format!("{:.*}", decimals, value.abs());
+        let (integer, fraction) = formatted
+            .split_once('.')
+            .unwrap_or((formatted.as_str(), ""));
+
+        let mut result = String::new();
+
+        if value.is_sign_negative() && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            result.push('-');
+        }
+
+        for (i, digit) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                if let Some(separator) = self.group_separator {
+                    result.push(separator);
+                }
+            }
+            result.push(digit);
+        }
+
+        if !fraction.is_empty() {
+            result.push(self.decimal_separator);
+            result.push_str(fraction);
+        }
+
+        result
+    }
+
+    /// Amount with a currency symbol on the side the locale puts it, single character
+    /// symbols are attached when leading, longer ones like ISO codes are spaced.
+    pub fn format_currency(&self, value: f64, decimals: usize, symbol: &str) -> String {
+        let number = self.format_number(value, decimals);
+
+        match (self.symbol_first, symbol.chars().count()) {
+            (true, 1) => format!("{}{}", symbol, number),
+            (true, _) => format!("{} {}", symbol, number),
+            (false, _) => format!("{} {}", number, symbol),
+        }
+    }
+
+    pub fn format_datetime<Tz: TimeZone>(&self, datetime: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: fmt::Display,
+    {
+        datetime.format(&self.datetime_format).to_string()
+    }
+
+    /// Formats a unix timestamp in the configured timezone.
+    pub fn format_timestamp(&self, timestamp: i64) -> String {
+        let Some(datetime) = DateTime::<Utc>::from_timestamp(timestamp, 0) else {
+            return "-".to_string();
+        };
+
+        match self.timezone {
+            DisplayTimezone::Local => self.format_datetime(&datetime.with_timezone(&Local)),
+            DisplayTimezone::Named(tz) => self.format_datetime(&datetime.with_timezone(&tz)),
+        }
+    }
+
+    /// Unix timestamp in the configured timezone, for calendar math like weekday or hour.
+    pub fn to_datetime(&self, timestamp: i64) -> Option<DateTime<FixedOffset>> {
+        let datetime = DateTime::<Utc>::from_timestamp(timestamp, 0)?;
+
+        Some(match self.timezone {
+            DisplayTimezone::Local => datetime.with_timezone(&Local).fixed_offset(),
+            DisplayTimezone::Named(tz) => datetime.with_timezone(&tz).fixed_offset(),
+        })
+    }
+}
+
+/// Compact human readable duration, e.g. `42s`, `7m` or `3h 12m`.
+pub fn format_elapsed(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m", seconds / 60),
+        3600..=86399 => format!("{}h {}m", seconds / 3600, (seconds % 3600) / 60),
+        _ => format!("{}d {}h", seconds / 86400, (seconds % 86400) / 3600),
+    }
+}
+
+/// Compact byte count, e.g. `512 B`, `3.2 kB`, `1.4 MB` or `685.7 GB`.
+pub fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1048575 => format!("{:.1} kB", bytes as f64 / 1024.0),
+        1048576..=1073741823 => format!("{:.1} MB", bytes as f64 / 1024.0 / 1024.0),
+        _ => format!("{:.1} GB", bytes as f64 / 1024.0 / 1024.0 / 1024.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locale(tag: &str) -> Locale {
+        Locale::from_str(tag).unwrap()
+    }
+
+    #[test]
+    fn groups_thousands_with_the_locale_separators() {
+        assert_eq!(
+            locale("en_US").format_number(1234567.891, 2),
+            "1,234,567.89"
+        );
+        assert_eq!(
+            locale("de_DE").format_number(1234567.891, 2),
+            "1.234.567,89"
+        );
+        assert_eq!(
+            locale("de_CH").format_number(1234567.891, 2),
+            "1'234'567.89"
+        );
+        assert_eq!(
+            locale("fr_FR").format_number(1234567.891, 2),
+            "1\u{202F}234\u{202F}567,89"
+        );
+        assert_eq!(locale("C").format_number(1234567.891, 2), "1234567.89");
+    }
+
+    #[test]
+    fn groups_only_full_thousands() {
+        let locale = Locale::default();
+
+        assert_eq!(locale.format_number(0.0, 0), "0");
+        assert_eq!(locale.format_number(999.0, 0), "999");
+        assert_eq!(locale.format_number(1000.0, 0), "1,000");
+        assert_eq!(locale.format_number(100000.0, 0), "100,000");
+    }
+
+    #[test]
+    fn keeps_the_sign_of_negative_numbers() {
+        assert_eq!(locale("en_US").format_number(-1234.5, 1), "-1,234.5");
+        assert_eq!(locale("de_DE").format_number(-1234.5, 1), "-1.234,5");
+        assert_eq!(locale("en_US").format_number(-999.0, 0), "-999");
+    }
+
+    #[test]
+    fn drops_the_sign_when_rounding_to_zero() {
+        let locale = Locale::default();
+
+        assert_eq!(locale.format_number(-0.004, 2), "0.00");
+        assert_eq!(locale.format_number(-0.0, 0), "0");
+    }
+
+    #[test]
+    fn accepts_tag_variants() {
+        assert_eq!(locale("de-DE"), locale("de_DE"));
+        assert_eq!(locale("de_DE.UTF-8"), locale("de_DE"));
+        assert_eq!(locale("en"), Locale::default());
+        assert!(Locale::from_str("xx_XX").is_err());
+    }
+
+    #[test]
+    fn places_the_currency_symbol_where_the_locale_does() {
+        assert_eq!(locale("en_US").format_currency(68512.0, 0, "$"), "$68,512");
+        assert_eq!(
+            locale("en_US").format_currency(68512.0, 0, "CHF"),
+            "CHF 68,512"
+        );
+        assert_eq!(locale("de_DE").format_currency(68512.0, 0, "€"), "68.512 €");
+        assert_eq!(
+            locale("es_ES").format_currency(68512.5, 2, "€"),
+            "68.512,50 €"
+        );
+    }
+
+    #[test]
+    fn formats_negative_currency_amounts() {
+        assert_eq!(locale("en_US").format_currency(-1250.0, 0, "$"), "$-1,250");
+        assert_eq!(locale("de_DE").format_currency(-1250.0, 0, "€"), "-1.250 €");
+    }
+}
@@ -0,0 +1,41 @@
+use tokio::time::{Duration, Instant};
+
+/// How trustworthy the data of a polled source currently is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DataHealth {
+    Fresh,
+    Stale,
+    Failing,
+}
+
+/// When a source last answered and whether its latest fetch failed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SourceHealth {
+    pub updated_at: Option<Instant>,
+    pub failing: bool,
+}
+
+impl SourceHealth {
+    pub fn fresh() -> Self {
+        Self {
+            updated_at: Some(Instant::now()),
+            failing: false,
+        }
+    }
+
+    /// Data is stale once older than `stale_after`, or before the first poll arrived.
+    pub fn health(&self, stale_after: Duration) -> DataHealth {
+        match self.updated_at {
+            _ if self.failing => DataHealth::Failing,
+            Some(updated_at) if updated_at.elapsed() < stale_after => DataHealth::Fresh,
+            _ => DataHealth::Stale,
+        }
+    }
+
+    /// Data arrived but is older than `stale_after`, still on screen since the polls
+    /// stopped succeeding.
+    pub fn is_stale(&self, stale_after: Duration) -> bool {
+        self.updated_at
+            .is_some_and(|updated_at| updated_at.elapsed() >= stale_after)
+    }
+}
@@ -0,0 +1,335 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tokio::time::{self, Duration};
+use tokio_util::sync::CancellationToken;
+
+use crate::app::AppThread;
+use crate::bandwidth::{self, Source, HTTP_OVERHEAD};
+use crate::config::AppConfig;
+use crate::event::{Event, NodeEvent};
+use crate::health::SourceHealth;
+use crate::throughput::{self, Feed};
+
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// SSH and node_exporter answers slower than this count as failures.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where the host figures are read from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HostSource {
+    /// `/proc` and `df` on this machine.
+    Local,
+    /// The same files over `ssh`, e.g. `ssh://admin@node.lan`.
+    Ssh(String),
+    /// A node_exporter metrics URL, e.g. `http://node.lan:9100/metrics`.
+    Exporter(String),
+}
+
+impl FromStr for HostSource {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<HostSource> {
+        match input {
+            "" | "local" => Ok(HostSource::Local),
+            _ if input.starts_with("http://") || input.starts_with("https://") => {
+                Ok(HostSource::Exporter(input.to_string()))
+            }
+            _ => match input.strip_prefix("ssh://") {
+                Some(target) if !target.is_empty() => Ok(HostSource::Ssh(target.to_string())),
+                _ => Err(anyhow::Error::msg("Host source not supported")),
+            },
+        }
+    }
+}
+
+impl fmt::Display for HostSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HostSource::Local => write!(f, "local"),
+            HostSource::Ssh(target) => write!(f, "{}", target),
+            HostSource::Exporter(url) => write!(
+                f,
+                "{}",
+                reqwest::Url::parse(url)
+                    .ok()
+                    .and_then(|url| url.host_str().map(str::to_string))
+                    .unwrap_or_else(|| url.clone())
+            ),
+        }
+    }
+}
+
+/// Raw counters of one read, CPU usage comes from the difference between two.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HostSample {
+    pub cpu_total: f64,
+    pub cpu_idle: f64,
+    pub load: Option<f64>,
+    pub memory_total: Option<u64>,
+    pub memory_available: Option<u64>,
+    pub disk_total: Option<u64>,
+    pub disk_available: Option<u64>,
+}
+
+impl HostSample {
+    /// Parses `/proc/stat`, `/proc/loadavg`, `/proc/meminfo` and `df -Pk` output, in any
+    /// order and all in one text so the same works locally and over SSH.
+    pub fn from_proc(text: &str) -> HostSample {
+        let mut sample = HostSample::default();
+        let mut lines = text.lines();
+
+        while let Some(line) = lines.next() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+
+            match fields.as_slice() {
+                ["cpu", counters @ ..] => {
+                    let counters: Vec<f64> =
+                        counters.iter().filter_map(|c| c.parse().ok()).collect();
+                    // user nice system idle iowait irq softirq steal, guests are in user
+                    sample.cpu_total = counters.iter().take(8).sum();
+                    sample.cpu_idle = counters.iter().skip(3).take(2).sum();
+                }
+                ["MemTotal:", kb, ..] => {
+                    sample.memory_total = kb.parse::<u64>().ok().map(|kb| kb * 1024)
+                }
+                ["MemAvailable:", kb, ..] => {
+                    sample.memory_available = kb.parse::<u64>().ok().map(|kb| kb * 1024)
+                }
+                ["Filesystem", ..] => {
+                    let Some(df) = lines.next() else {
+                        continue;
+                    };
+                    let df: Vec<&str> = df.split_whitespace().collect();
+
+                    if let [_, total, _, available, ..] = df.as_slice() {
+                        sample.disk_total = total.parse::<u64>().ok().map(|kb| kb * 1024);
+                        sample.disk_available = available.parse::<u64>().ok().map(|kb| kb * 1024);
+                    }
+                }
+                [load, _, _, _, _] if sample.load.is_none() && load.contains('.') => {
+                    sample.load = load.parse().ok();
+                }
+                _ => {}
+            }
+        }
+
+        sample
+    }
+
+    /// Parses the node_exporter text format, the disk is the filesystem mounted at `mount`.
+    pub fn from_exporter(text: &str, mount: &str) -> HostSample {
+        let mut sample = HostSample::default();
+        let mountpoint = format!("mountpoint=\"{}\"", mount);
+
+        for line in text.lines().filter(|line| !line.starts_with('#')) {
+            let Some((metric, value)) = line.rsplit_once(' ') else {
+                continue;
+            };
+            let Ok(value) = value.parse::<f64>() else {
+                continue;
+            };
+            let (name, labels) = metric.split_once('{').unwrap_or((metric, ""));
+
+            match name {
+                "node_cpu_seconds_total" => {
+                    sample.cpu_total += value;
+
+                    if labels.contains("mode=\"idle\"") || labels.contains("mode=\"iowait\"") {
+                        sample.cpu_idle += value;
+                    }
+                }
+                "node_load1" => sample.load = Some(value),
+                "node_memory_MemTotal_bytes" => sample.memory_total = Some(value as u64),
+                "node_memory_MemAvailable_bytes" => sample.memory_available = Some(value as u64),
+                "node_filesystem_size_bytes" if labels.contains(&mountpoint) => {
+                    sample.disk_total = Some(value as u64)
+                }
+                "node_filesystem_avail_bytes" if labels.contains(&mountpoint) => {
+                    sample.disk_available = Some(value as u64)
+                }
+                _ => {}
+            }
+        }
+
+        sample
+    }
+}
+
+/// Used and total amounts, e.g. bytes of memory.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Usage {
+    pub used: u64,
+    pub total: u64,
+}
+
+impl Usage {
+    fn from_available(total: Option<u64>, available: Option<u64>) -> Option<Usage> {
+        let total = total.filter(|total| *total > 0)?;
+
+        Some(Usage {
+            used: total.saturating_sub(available?),
+            total,
+        })
+    }
+
+    pub fn ratio(&self) -> f64 {
+        self.used as f64 / self.total as f64
+    }
+}
+
+/// Load of the machine running the node, since node trouble is often host trouble.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HostState {
+    pub name: String,
+    /// Busy share of the CPU time since the previous read, none after the first one.
+    pub cpu: Option<f64>,
+    pub load: Option<f64>,
+    pub memory: Option<Usage>,
+    pub disk: Option<Usage>,
+    #[serde(skip, default = "SourceHealth::fresh")]
+    pub health: SourceHealth,
+    pub error: Option<String>,
+}
+
+impl HostState {
+    fn from_samples(name: String, previous: Option<&HostSample>, sample: &HostSample) -> Self {
+        let cpu = previous.and_then(|previous| {
+            let total = sample.cpu_total - previous.cpu_total;
+            let idle = sample.cpu_idle - previous.cpu_idle;
+            (total > 0.0).then(|| (1.0 - idle / total).clamp(0.0, 1.0))
+        });
+
+        Self {
+            name,
+            cpu,
+            load: sample.load,
+            memory: Usage::from_available(sample.memory_total, sample.memory_available),
+            disk: Usage::from_available(sample.disk_total, sample.disk_available),
+            health: SourceHealth::fresh(),
+            error: None,
+        }
+    }
+}
+
+async fn read_local(disk_path: &str) -> Result<String> {
+    let mut text = String::new();
+
+    for file in ["/proc/stat", "/proc/loadavg", "/proc/meminfo"] {
+        text.push_str(&tokio::fs::read_to_string(file).await?);
+    }
+
+    let df = Command::new("df")
+        .args(["-Pk", disk_path])
+        .kill_on_drop(true)
+        .output()
+        .await?;
+    text.push_str(&String::from_utf8_lossy(&df.stdout));
+
+    Ok(text)
+}
+
+async fn read_ssh(target: &str, disk_path: &str) -> Result<String> {
+    let output = Command::new("ssh")
+        .args(["-o", "BatchMode=yes", "-o", "ConnectTimeout=5"])
+        // ssh takes the URI form too, it is the one allowing a port
+        .arg(format!("ssh://{}", target))
+        .arg(format!(
+            "cat /proc/stat /proc/loadavg /proc/meminfo && df -Pk '{}'",
+            disk_path.replace('\'', "")
+        ))
+        .kill_on_drop(true)
+        .output()
+        .await?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    bandwidth::record(Source::Host, stdout.len());
+
+    match output.status.success() {
+        true => Ok(stdout),
+        false => Err(anyhow::Error::msg(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        )),
+    }
+}
+
+async fn read_exporter(url: &str) -> Result<String> {
+    let bytes = reqwest::get(url).await?.error_for_status()?.bytes().await?;
+    bandwidth::record(Source::Host, bytes.len() + HTTP_OVERHEAD);
+
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+pub async fn read_sample(source: &HostSource, disk_path: &str) -> Result<HostSample> {
+    let read = async {
+        match source {
+            HostSource::Local => read_local(disk_path)
+                .await
+                .map(|text| HostSample::from_proc(&text)),
+            HostSource::Ssh(target) => read_ssh(target, disk_path)
+                .await
+                .map(|text| HostSample::from_proc(&text)),
+            HostSource::Exporter(url) => read_exporter(url)
+                .await
+                .map(|text| HostSample::from_exporter(&text, disk_path)),
+        }
+    };
+
+    let sample = time::timeout(TIMEOUT, read)
+        .await
+        .map_err(|_| anyhow::Error::msg("Timed out"))??;
+
+    match sample.memory_total.is_some() || sample.cpu_total > 0.0 {
+        true => Ok(sample),
+        false => Err(anyhow::Error::msg("No host figures in the answer")),
+    }
+}
+
+/// An unknown `host.source` is reported on the splash screen.
+pub fn spawn_host_checker(thread: AppThread, config: &AppConfig) {
+    let Ok(source) = HostSource::from_str(&config.host.source) else {
+        return;
+    };
+    let disk_path = config.host.disk_path.clone();
+
+    thread.tracker.spawn(async move {
+        tokio::select! {
+            () = thread.token.cancelled() => {}
+            () = host_checker(thread.sender, thread.token.clone(), source, disk_path) => {}
+        }
+    });
+}
+
+async fn host_checker(
+    sender: mpsc::UnboundedSender<Event>,
+    token: CancellationToken,
+    source: HostSource,
+    disk_path: String,
+) {
+    let mut previous: Option<HostSample> = None;
+
+    loop {
+        if token.is_cancelled() {
+            break;
+        }
+
+        let _ = match read_sample(&source, &disk_path).await {
+            Ok(sample) => {
+                throughput::record(Feed::Host);
+                let state = HostState::from_samples(source.to_string(), previous.as_ref(), &sample);
+                previous = Some(sample);
+                sender.send(NodeEvent::HostUpdate(state).into())
+            }
+            Err(e) => sender.send(NodeEvent::HostError(e.to_string()).into()),
+        };
+
+        tokio::select! {
+            () = token.cancelled() => {}
+            () = tokio::time::sleep(bandwidth::stretch(CHECK_INTERVAL)) => {}
+        }
+    }
+}
@@ -0,0 +1,465 @@
+use std::collections::HashMap;
+
+/// Kind of file settings are imported from, told apart by the keys in it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    /// `bitcoin.conf` of the node itself.
+    BitcoinConf,
+    /// `lnd.conf`, its `bitcoind.*` settings point at the node.
+    LndConf,
+    /// `.env` of BTC RPC Explorer, `BTCEXP_*` variables.
+    ExplorerEnv,
+}
+
+impl Source {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Source::BitcoinConf => "bitcoin.conf",
+            Source::LndConf => "lnd.conf",
+            Source::ExplorerEnv => "BTC RPC Explorer .env",
+        }
+    }
+}
+
+/// btcmon settings read out of another tool's config, as dotted keys.
+#[derive(Debug, Clone, Default)]
+pub struct Import {
+    pub settings: Vec<(&'static str, String)>,
+    /// What could not be carried over, printed as comments.
+    pub notes: Vec<String>,
+}
+
+impl Import {
+    fn set(&mut self, key: &'static str, value: &str) {
+        match self.settings.iter_mut().find(|(other, _)| *other == key) {
+            Some((_, old)) => *old = value.to_string(),
+            None => self.settings.push((key, value.to_string())),
+        }
+    }
+
+    /// Settings of `other` win, it was given later.
+    pub fn merge(&mut self, other: Import) {
+        for (key, value) in other.settings {
+            self.set(key, &value);
+        }
+        self.notes.extend(other.notes);
+    }
+
+    /// TOML for a config of its own or to copy into btcmon.toml, grouped by section.
+    pub fn to_toml(&self) -> String {
+        let mut lines: Vec<String> = self
+            .notes
+            .iter()
+            .map(|note| format!("# {}", note))
+            .collect();
+        let mut sections: Vec<&str> = vec![];
+
+        for (key, _) in self.settings.iter() {
+            let (section, _) = key.split_once('.').unwrap_or(("", key));
+            if !sections.contains(&section) {
+                sections.push(section);
+            }
+        }
+
+        for section in sections {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push(format!("[{}]", section));
+
+            for (key, value) in self.settings.iter() {
+                if let Some(name) = key.strip_prefix(section).and_then(|k| k.strip_prefix('.')) {
+                    lines.push(format!("{} = {}", name, toml_string(value)));
+                }
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Settings of one file, whichever of the supported kinds it is.
+pub fn import(contents: &str) -> (Source, Import) {
+    let entries = parse(contents);
+    let source = detect(&entries);
+
+    let import = match source {
+        Source::BitcoinConf => from_bitcoin_conf(&entries),
+        Source::LndConf => from_lnd_conf(&entries),
+        Source::ExplorerEnv => from_explorer_env(&entries),
+    };
+
+    (source, import)
+}
+
+/// `key = value` lines with the section each one is in, comments and blank lines left out.
+/// Covers the INI style of bitcoin.conf and lnd.conf and shell style `.env` files.
+fn parse(contents: &str) -> Vec<(String, String, String)> {
+    let mut section = String::new();
+    let mut entries = vec![];
+
+    for line in contents.lines() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().to_lowercase();
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value);
+
+            entries.push((section.clone(), key.trim().to_string(), value.to_string()));
+        }
+    }
+
+    entries
+}
+
+fn detect(entries: &[(String, String, String)]) -> Source {
+    if entries.iter().any(|(_, key, _)| key.starts_with("BTCEXP_")) {
+        Source::ExplorerEnv
+    } else if entries
+        .iter()
+        .any(|(_, key, _)| key.starts_with("bitcoind."))
+    {
+        Source::LndConf
+    } else {
+        Source::BitcoinConf
+    }
+}
+
+fn from_bitcoin_conf(entries: &[(String, String, String)]) -> Import {
+    let top: HashMap<&str, &str> = entries
+        .iter()
+        .filter(|(section, _, _)| section.is_empty())
+        .map(|(_, key, value)| (key.as_str(), value.as_str()))
+        .collect();
+
+    let chain = match top.get("chain") {
+        Some(chain) => chain.to_string(),
+        None if top.get("testnet") == Some(&"1") => "test".to_string(),
+        None if top.get("signet") == Some(&"1") => "signet".to_string(),
+        None if top.get("regtest") == Some(&"1") => "regtest".to_string(),
+        None => "main".to_string(),
+    };
+    // `chain=testnet` selects the same section as `testnet=1`
+    let section = match chain.as_str() {
+        "testnet" | "testnet3" => "test",
+        chain => chain,
+    };
+
+    // Settings of the chain's own section override the ones at the top
+    let mut values = top;
+    for (_, key, value) in entries.iter().filter(|(s, _, _)| s == section) {
+        values.insert(key.as_str(), value.as_str());
+    }
+
+    let mut import = Import::default();
+    import.set("node.provider", "bitcoin_core");
+
+    if let Some(host) = values.get("rpcconnect") {
+        import.set("bitcoin_core.host", host);
+    }
+
+    let rpc_port = match section {
+        "test" => "18332",
+        "testnet4" => "48332",
+        "signet" => "38332",
+        "regtest" => "18443",
+        _ => "8332",
+    };
+    import.set(
+        "bitcoin_core.rpc_port",
+        values.get("rpcport").copied().unwrap_or(rpc_port),
+    );
+
+    match (values.get("rpcuser"), values.get("rpcpassword")) {
+        (Some(user), Some(password)) => {
+            import.set("bitcoin_core.rpc_user", user);
+            import.set("bitcoin_core.rpc_password", password);
+        }
+        // Cookie and rpcauth logins have nothing to copy over
+        _ => import
+            .notes
+            .push("No rpcuser and rpcpassword in bitcoin.conf, set them by hand".to_string()),
+    }
+
+    let zmq = [
+        ("zmqpubhashblock", "bitcoin_core.zmq_port"),
+        ("zmqpubrawtx", "bitcoin_core.zmq_rawtx_port"),
+        ("zmqpubsequence", "bitcoin_core.zmq_sequence_port"),
+    ];
+    for (option, key) in zmq {
+        if let Some(port) = values.get(option).and_then(|url| port_of(url)) {
+            import.set(key, port);
+        }
+    }
+
+    if !values.contains_key("zmqpubhashblock") {
+        import
+            .notes
+            .push("No zmqpubhashblock, blocks show up on the next poll".to_string());
+    }
+
+    import
+}
+
+fn from_lnd_conf(entries: &[(String, String, String)]) -> Import {
+    let values: HashMap<&str, &str> = entries
+        .iter()
+        .map(|(_, key, value)| (key.as_str(), value.as_str()))
+        .collect();
+
+    let mut import = Import::default();
+    import.set("node.provider", "bitcoin_core");
+
+    if let Some(rpc_host) = values.get("bitcoind.rpchost") {
+        match split_host_port(rpc_host) {
+            Some((host, port)) => {
+                import.set("bitcoin_core.host", host);
+                import.set("bitcoin_core.rpc_port", port);
+            }
+            None => import.set("bitcoin_core.host", rpc_host),
+        }
+    }
+
+    if let Some(user) = values.get("bitcoind.rpcuser") {
+        import.set("bitcoin_core.rpc_user", user);
+    }
+    if let Some(password) = values.get("bitcoind.rpcpass") {
+        import.set("bitcoin_core.rpc_password", password);
+    }
+
+    if let Some(port) = values
+        .get("bitcoind.zmqpubrawtx")
+        .and_then(|url| port_of(url))
+    {
+        import.set("bitcoin_core.zmq_rawtx_port", port);
+    }
+
+    // LND listens for raw blocks, the tip moves on hash notifications
+    if values.contains_key("bitcoind.zmqpubrawblock") {
+        import.notes.push(
+            "bitcoind.zmqpubrawblock left out, btcmon needs zmqpubhashblock for zmq_port"
+                .to_string(),
+        );
+    }
+
+    import
+}
+
+fn from_explorer_env(entries: &[(String, String, String)]) -> Import {
+    let values: HashMap<&str, &str> = entries
+        .iter()
+        .map(|(_, key, value)| (key.as_str(), value.as_str()))
+        .collect();
+
+    let mut import = Import::default();
+
+    let bitcoind = [
+        ("BTCEXP_BITCOIND_HOST", "bitcoin_core.host"),
+        ("BTCEXP_BITCOIND_PORT", "bitcoin_core.rpc_port"),
+        ("BTCEXP_BITCOIND_USER", "bitcoin_core.rpc_user"),
+        ("BTCEXP_BITCOIND_PASS", "bitcoin_core.rpc_password"),
+    ];
+    for (variable, key) in bitcoind {
+        if let Some(value) = values.get(variable) {
+            import.set(key, value);
+        }
+    }
+
+    if values.contains_key("BTCEXP_BITCOIND_COOKIE") {
+        import
+            .notes
+            .push("BTCEXP_BITCOIND_COOKIE left out, cookie logins are not supported".to_string());
+    }
+
+    // The explorer takes a list, btcmon speaks plain TCP to one server
+    let servers = values
+        .get("BTCEXP_ELECTRUM_SERVERS")
+        .or(values.get("BTCEXP_ELECTRUMX_SERVERS"));
+    if let Some(servers) = servers {
+        let server = servers
+            .split(',')
+            .filter_map(|server| server.trim().strip_prefix("tcp://"))
+            .find_map(split_host_port);
+
+        match server {
+            Some((host, port)) => {
+                import.set("electrum.host", host);
+                import.set("electrum.port", port);
+            }
+            None => import
+                .notes
+                .push("No tcp:// Electrum server, btcmon does not speak SSL to it".to_string()),
+        }
+    }
+
+    let provider = match import
+        .settings
+        .iter()
+        .any(|(key, _)| key.starts_with("bitcoin_core."))
+    {
+        true => "bitcoin_core",
+        false
+            if import
+                .settings
+                .iter()
+                .any(|(key, _)| key.starts_with("electrum.")) =>
+        {
+            "electrum"
+        }
+        false => return import,
+    };
+    import.set("node.provider", provider);
+
+    import
+}
+
+/// `host:port`, with IPv6 hosts in brackets like `[::1]:8332`.
+fn split_host_port(address: &str) -> Option<(&str, &str)> {
+    let (host, port) = address.rsplit_once(':')?;
+    port.parse::<u16>().ok()?;
+
+    match host.strip_prefix('[').and_then(|h| h.strip_suffix(']')) {
+        Some(host) => Some((host, port)),
+        None if !host.contains(':') => Some((host, port)),
+        None => None,
+    }
+}
+
+/// Port of a ZMQ endpoint like `tcp://127.0.0.1:28332`.
+fn port_of(url: &str) -> Option<&str> {
+    let address = url.split_once("://").map_or(url, |(_, address)| address);
+    split_host_port(address).map(|(_, port)| port)
+}
+
+fn toml_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get<'a>(import: &'a Import, key: &str) -> Option<&'a str> {
+        import
+            .settings
+            .iter()
+            .find(|(other, _)| *other == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn bitcoin_conf_chain_section_overrides_top() {
+        let (source, import) = import(
+            "# node\n\
+             chain=signet\n\
+             rpcuser=alice\n\
+             rpcpassword=top\n\
+             zmqpubhashblock=tcp://127.0.0.1:28332\n\
+             \n\
+             [main]\n\
+             rpcport=9999\n\
+             \n\
+             [signet]\n\
+             rpcpassword=secret\n\
+             zmqpubrawtx=tcp://127.0.0.1:28333\n",
+        );
+
+        assert_eq!(source, Source::BitcoinConf);
+        assert_eq!(get(&import, "bitcoin_core.rpc_port"), Some("38332"));
+        assert_eq!(get(&import, "bitcoin_core.rpc_user"), Some("alice"));
+        assert_eq!(get(&import, "bitcoin_core.rpc_password"), Some("secret"));
+        assert_eq!(get(&import, "bitcoin_core.zmq_port"), Some("28332"));
+        assert_eq!(get(&import, "bitcoin_core.zmq_rawtx_port"), Some("28333"));
+        assert!(import.notes.is_empty());
+    }
+
+    #[test]
+    fn bitcoin_conf_testnet_flag_and_cookie_login() {
+        let (_, import) = import("testnet=1\n[test]\nrpcconnect=10.0.0.2\n");
+
+        assert_eq!(get(&import, "bitcoin_core.host"), Some("10.0.0.2"));
+        assert_eq!(get(&import, "bitcoin_core.rpc_port"), Some("18332"));
+        assert_eq!(get(&import, "bitcoin_core.rpc_user"), None);
+        assert_eq!(import.notes.len(), 2);
+    }
+
+    #[test]
+    fn lnd_conf_ipv6_host() {
+        let (source, import) = import(
+            "[Application Options]\n\
+             alias=mynode\n\
+             \n\
+             [Bitcoind]\n\
+             bitcoind.rpchost=[::1]:8332\n\
+             bitcoind.rpcuser=lnd\n\
+             bitcoind.rpcpass=hunter2\n\
+             bitcoind.zmqpubrawblock=tcp://127.0.0.1:28332\n\
+             bitcoind.zmqpubrawtx=tcp://127.0.0.1:28333\n",
+        );
+
+        assert_eq!(source, Source::LndConf);
+        assert_eq!(get(&import, "bitcoin_core.host"), Some("::1"));
+        assert_eq!(get(&import, "bitcoin_core.rpc_port"), Some("8332"));
+        assert_eq!(get(&import, "bitcoin_core.rpc_password"), Some("hunter2"));
+        assert_eq!(get(&import, "bitcoin_core.zmq_rawtx_port"), Some("28333"));
+        assert_eq!(import.notes.len(), 1);
+    }
+
+    #[test]
+    fn explorer_env_quotes_and_export() {
+        let (source, import) = import(
+            "export BTCEXP_BITCOIND_HOST=\"node.local\"\n\
+             BTCEXP_BITCOIND_PORT='8332'\n\
+             BTCEXP_BITCOIND_USER=explorer\n\
+             BTCEXP_BITCOIND_PASS=\"p=ss\"\n\
+             BTCEXP_ELECTRUM_SERVERS=ssl://a.example:50002,tcp://[fe80::1]:50001\n",
+        );
+
+        assert_eq!(source, Source::ExplorerEnv);
+        assert_eq!(get(&import, "bitcoin_core.host"), Some("node.local"));
+        assert_eq!(get(&import, "bitcoin_core.rpc_port"), Some("8332"));
+        assert_eq!(get(&import, "bitcoin_core.rpc_password"), Some("p=ss"));
+        assert_eq!(get(&import, "electrum.host"), Some("fe80::1"));
+        assert_eq!(get(&import, "electrum.port"), Some("50001"));
+        assert_eq!(get(&import, "node.provider"), Some("bitcoin_core"));
+    }
+
+    #[test]
+    fn host_port_splitting() {
+        assert_eq!(split_host_port("[::1]:8332"), Some(("::1", "8332")));
+        assert_eq!(
+            split_host_port("127.0.0.1:8332"),
+            Some(("127.0.0.1", "8332"))
+        );
+        assert_eq!(split_host_port("::1:8332"), None);
+        assert_eq!(split_host_port("localhost"), None);
+        assert_eq!(split_host_port("localhost:port"), None);
+        assert_eq!(port_of("tcp://127.0.0.1:28332"), Some("28332"));
+    }
+
+    #[test]
+    fn later_files_win_and_toml_escapes() {
+        let (_, mut merged) = import("rpcuser=a\nrpcpassword=one\n");
+        let (_, later) = import("rpcuser=a\nrpcpassword=say \"hi\"\\\n");
+        merged.merge(later);
+
+        let toml = merged.to_toml();
+        assert!(toml.contains("[bitcoin_core]"));
+        assert!(toml.contains(r#"rpc_password = "say \"hi\"\\""#));
+        assert_eq!(toml.matches("[bitcoin_core]").count(), 1);
+    }
+}
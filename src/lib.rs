@@ -21,3 +21,73 @@ pub mod node;
 
 /// Fees
 pub mod fees;
+
+/// Formatting
+pub mod format;
+
+/// Session statistics
+pub mod session;
+
+/// Persisted state
+pub mod store;
+
+/// Data source health
+pub mod health;
+
+/// Status bar badges
+pub mod badges;
+
+/// JSON output for status bars
+pub mod stream;
+
+/// Push notifications
+pub mod notify;
+
+/// Scheduled quiet windows for pushes
+pub mod quiet;
+
+/// Notes pinned to blocks
+pub mod annotations;
+
+/// Connection checks
+pub mod preflight;
+
+/// Config, state, cache and runtime locations
+pub mod paths;
+
+/// Frame and event loop timings
+pub mod diagnostics;
+
+/// User scripted panel
+pub mod script;
+
+/// Traffic accounting
+pub mod bandwidth;
+
+/// Update counts by source
+pub mod throughput;
+
+/// Chain size growth
+pub mod disk;
+
+/// Resources of the node host
+pub mod host;
+
+/// Chain tip from a block explorer while the node is offline
+pub mod explorer;
+
+/// Event recording and replay
+pub mod record;
+
+/// Last values shown, for the next start
+pub mod snapshot;
+
+/// Settings taken from other tools' config files
+pub mod import;
+
+/// Statistics kept per day, for reports
+pub mod daily;
+
+/// Read only copies of the dashboard on other terminals
+#[cfg(unix)]
+pub mod mirror;
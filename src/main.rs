@@ -1,68 +1,352 @@
 use btcmon::app::{App, AppResult, AppThread};
+use btcmon::badges::Badge;
+use btcmon::bandwidth;
 use btcmon::config;
-use btcmon::event::{Event, EventHandler};
-use btcmon::node::providers::bitcoin_core::BitcoinCore;
-use btcmon::node::NodeProvider;
+use btcmon::daily::{DailyStats, STORE_KEY as DAILY_KEY};
+use btcmon::event::{Event, EventBus, EventHandler, Topic, UiEvent};
+use btcmon::import::{self, Import};
+#[cfg(unix)]
+use btcmon::mirror::{self, Mirror};
+use btcmon::node::providers;
+use btcmon::notify::{spawn_notifier, test_message, Notifier};
+use btcmon::paths;
+use btcmon::preflight;
+use btcmon::record::{spawn_recorder, Recorder};
+use btcmon::store::Store;
+use btcmon::stream::JsonStream;
 use btcmon::tui::Tui;
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
-use std::{env, io};
+use std::path::Path;
+use std::{env, fs, io};
 use tokio::sync::mpsc;
+use tokio::time::Duration;
 
-#[tokio::main]
-async fn main() -> AppResult<()> {
+/// How long quitting waits for blocking calls still in flight, e.g. RPC over Tor.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_millis(500);
+
+fn main() -> AppResult<()> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    let result = runtime.block_on(run());
+
+    // Abandoned blocking calls would otherwise keep the process alive until they time out
+    runtime.shutdown_timeout(SHUTDOWN_TIMEOUT);
+    result
+}
+
+/// Version with the optional integrations compiled in, e.g. for distro packages.
+fn version() -> String {
+    let features = [
+        ("zmq", cfg!(feature = "zmq")),
+        ("scripting", cfg!(feature = "scripting")),
+    ];
+
+    let features: Vec<String> = features
+        .iter()
+        .map(|(name, enabled)| format!("{}{}", if *enabled { '+' } else { '-' }, name))
+        .collect();
+
+    format!(
+        "btcmon {}\nfeatures: {}",
+        env!("CARGO_PKG_VERSION"),
+        features.join(" ")
+    )
+}
+
+async fn run() -> AppResult<()> {
     let (args, argv) = argmap::parse(env::args());
+    let day = argv.get("day").and_then(|day| day.first()).cloned();
+
+    if argv.contains_key("version") || argv.contains_key("V") {
+        println!("{}", version());
+        return Ok(());
+    }
+
+    let command: Vec<String> = args.iter().skip(1).cloned().collect();
     let config = config::AppConfig::new(args, argv).unwrap();
 
+    if command.starts_with(&["alerts".to_string(), "test".to_string()]) {
+        return alerts_test(&config, command.get(2)).await;
+    }
+
+    if command.first().map(String::as_str) == Some("test-node") {
+        return test_node(&config, command.get(1)).await;
+    }
+
+    #[cfg(unix)]
+    if command.first().map(String::as_str) == Some("attach") {
+        let path = match command.get(1) {
+            Some(path) => path.into(),
+            None => mirror::socket_path(&config)
+                .ok_or("No private runtime directory for the mirror socket, set mirror.socket")?,
+        };
+        return mirror::attach(&path).await;
+    }
+
+    if command.first().map(String::as_str) == Some("import") {
+        return import_settings(&command[1..]);
+    }
+
+    if command.first().map(String::as_str) == Some("report") {
+        return report(&config, day);
+    }
+
+    if command.first().map(String::as_str) == Some("paths") {
+        for (name, path) in paths::describe() {
+            println!("{:<8}{}", name, path);
+        }
+        return Ok(());
+    }
+
     let (sender, receiver) = mpsc::unbounded_channel();
 
     let sender_clone = sender.clone();
     let thread = AppThread::new(sender_clone);
 
     let mut app = App::new(thread);
+    let tick_rate = config.tick_interval();
+
+    // Subsystems following events next to the app, the recorder and the notifier
+    let mut bus = EventBus::default();
+    let recorder = match config.record.is_empty() {
+        true => None,
+        false => Some((
+            Recorder::create(Path::new(&config.record))?,
+            bus.subscribe(&Topic::ALL),
+        )),
+    };
+    let notifier =
+        Notifier::from_config(&config).map(|notifier| (notifier, bus.subscribe(&[Topic::System])));
+
+    // No terminal is set up at all, status bars run us without one
+    if let Some(mut stream) = JsonStream::from_config(&config) {
+        let mut events = EventHandler::ticks(tick_rate, app.thread.sender.clone(), receiver);
+
+        init_sources(&config, &mut app, recorder, notifier);
+
+        while app.running {
+            let event = events.next().await?;
+
+            bus.publish(&event);
+            app.handle_event(event)?;
+
+            // The consumer closing the pipe is the way to stop
+            if stream.tick(&app.state).is_err() {
+                app.running = false;
+            }
+        }
+
+        shutdown(&mut app).await;
+        return Ok(());
+    }
 
     let backend = CrosstermBackend::new(io::stderr());
     let terminal = Terminal::new(backend)?;
-    let events = EventHandler::new(
-        config.tick_rate.parse::<u64>().unwrap(),
-        app.thread.sender.clone(),
-        receiver,
-    );
+    let events = EventHandler::new(tick_rate, app.thread.sender.clone(), receiver);
 
     let mut tui = Tui::new(terminal, events);
+    #[cfg(unix)]
+    {
+        tui.mirror = Mirror::from_config(&config, &app.thread);
+    }
     tui.init()?;
     tui.draw(&config, &mut app)?;
 
-    let provider: Box<dyn NodeProvider + Send + 'static> = match config.bitcoin_core.host {
-        _ => Box::new(BitcoinCore::new(&config)),
-    };
+    init_sources(&config, &mut app, recorder, notifier);
+
+    let mut redraw = true;
+    while app.running {
+        if redraw {
+            tui.draw(&config, &mut app)?;
+        }
 
-    app.init_node(provider);
+        let event = tui.events.next().await?;
+        bus.publish(&event);
+
+        // Updates wait for the next tick in the low profile, one frame for all of them
+        redraw = !config.low_profile()
+            || matches!(
+                event,
+                Event::Ui(UiEvent::Tick | UiEvent::Key(_) | UiEvent::Resize(_, _))
+            );
+
+        app.handle_event(event)?;
+    }
+
+    shutdown(&mut app).await;
+
+    tui.exit()?;
+
+    let currency = app.state.price.currency.to_string();
+    for line in app.state.session.report(&config.locale(), &currency) {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+fn init_sources(
+    config: &config::AppConfig,
+    app: &mut App,
+    recorder: Option<(Recorder, mpsc::UnboundedReceiver<Event>)>,
+    notifier: Option<(Notifier, mpsc::UnboundedReceiver<Event>)>,
+) {
+    match config.replay.is_empty() {
+        true => init_live_sources(config, app),
+        false => app.init_replay(),
+    }
+
+    // Nothing of a replay is live, the pushes it plays back go nowhere
+    if let (true, Some((notifier, events))) = (app.pushes, notifier) {
+        spawn_notifier(app.thread.clone(), notifier, events);
+    }
+
+    // The node is set up by now, its state is recorded along with the events
+    if let Some((recorder, events)) = recorder {
+        let node = app.state.node.clone();
+        spawn_recorder(app.thread.clone(), recorder, events, node);
+    }
+}
+
+fn init_live_sources(config: &config::AppConfig, app: &mut App) {
+    bandwidth::start(config.bandwidth.budget_mb);
+
+    app.init_node(providers::shared_from_config(config));
 
     if config.price.enabled {
         app.init_price();
+        app.init_ath();
     }
 
     if config.fees.enabled {
         app.init_fees();
     }
 
-    while app.running {
-        tui.draw(&config, &mut app)?;
-        match tui.events.next().await? {
-            Event::Tick => app.tick(),
-            Event::Key(key_event) => app.handle_key_events(key_event)?,
-            Event::Mouse(_) => {}
-            Event::Resize(_, _) => {}
-            Event::PriceUpdate(state) => app.handle_price_update(state),
-            Event::FeeUpdate(state) => app.handle_fee_update(state),
-        }
+    if config.host.enabled {
+        app.init_host();
     }
+}
 
+async fn shutdown(app: &mut App) {
     app.thread.tracker.close();
     app.thread.token.cancel();
     app.thread.tracker.wait().await;
+}
 
-    tui.exit()?;
+/// `btcmon alerts test [badge]`: one synthetic push per badge, or only the named one.
+async fn alerts_test(config: &config::AppConfig, name: Option<&String>) -> AppResult<()> {
+    let Some(notifier) = Notifier::from_config(config) else {
+        return Err("No push service configured, see [notify] in the example config".into());
+    };
+
+    let badges: Vec<Badge> = config
+        .badges
+        .iter()
+        .filter_map(|badge| Badge::try_from(badge).ok())
+        .filter(|badge| name.is_none_or(|name| badge.label.eq_ignore_ascii_case(name)))
+        .collect();
+
+    let messages = match (badges.is_empty(), name) {
+        (true, Some(name)) => return Err(format!("No badge named {}", name).into()),
+        (true, None) => vec![test_message(None)],
+        (false, _) => badges
+            .iter()
+            .map(|badge| test_message(Some(badge)))
+            .collect(),
+    };
+
+    let mut failed = false;
+
+    for (title, message) in messages {
+        match notifier.send(&title, &message).await {
+            Ok(()) => println!("Sent: {}", title),
+            Err(e) => {
+                println!("Failed: {}: {}", title, e);
+                failed = true;
+            }
+        }
+    }
+
+    match failed {
+        true => Err("Some test notifications were not delivered".into()),
+        false => Ok(()),
+    }
+}
+
+/// `btcmon test-node [name]`: connection checks for one node, the configured one by default.
+async fn test_node(config: &config::AppConfig, name: Option<&String>) -> AppResult<()> {
+    let node = name.unwrap_or(&config.node.provider);
+
+    if !preflight::NODES.contains(&node.as_str()) {
+        return Err(format!(
+            "No node named {}, use one of {}",
+            node,
+            preflight::NODES.join(", ")
+        )
+        .into());
+    }
+
+    println!("Testing {}", node);
+
+    let checks = preflight::test_node(config, node).await;
+
+    for check in checks.iter() {
+        println!("  {}", check);
+    }
+
+    match checks.iter().any(|check| check.result.is_err()) {
+        true => Err("Some checks failed".into()),
+        false => Ok(()),
+    }
+}
+
+/// `btcmon report [--day <date>]`: statistics of a day, today by default, for journaling.
+/// The date is `2024-08-01` or `yesterday`.
+fn report(config: &config::AppConfig, day: Option<String>) -> AppResult<()> {
+    let now = chrono::Utc::now().timestamp();
+    let date = match day.as_deref() {
+        None | Some("today") => config.date(now),
+        Some("yesterday") => config.date(now - 24 * 60 * 60),
+        Some(date) => Some(date.to_string()),
+    }
+    .ok_or("Can't tell the date in the configured timezone")?;
+
+    let daily = DailyStats::new(Store::open().get(DAILY_KEY).unwrap_or_default());
+    let Some(stats) = daily.get(&date) else {
+        return Err(format!("No statistics for {}, btcmon did not run that day", date).into());
+    };
+
+    println!("btcmon day of {}", stats.date);
+    for line in stats.report(&config.locale()) {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+/// `btcmon import <file>...`: settings out of bitcoin.conf, lnd.conf or a BTC RPC Explorer
+/// `.env`, printed as TOML for btcmon.toml. Later files win.
+fn import_settings(files: &[String]) -> AppResult<()> {
+    if files.is_empty() {
+        return Err("Usage: btcmon import <bitcoin.conf|lnd.conf|.env>...".into());
+    }
+
+    let mut settings = Import::default();
+
+    for file in files {
+        let contents =
+            fs::read_to_string(file).map_err(|e| format!("Can't read {}: {}", file, e))?;
+        let (source, import) = import::import(&contents);
+
+        eprintln!(
+            "{}: {}, {} settings",
+            file,
+            source.name(),
+            import.settings.len()
+        );
+        settings.merge(import);
+    }
+
+    println!("{}", settings.to_toml());
     Ok(())
 }
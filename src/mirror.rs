@@ -0,0 +1,181 @@
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{Event as CrosstermEvent, EventStream, KeyCode, KeyModifiers};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use futures::StreamExt;
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::buffer::Buffer;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::watch;
+
+use crate::app::{AppResult, AppThread};
+use crate::config::AppConfig;
+use crate::paths;
+
+/// Read only copies of the dashboard. Every frame drawn is also sent as escape sequences
+/// to the `btcmon attach` clients on a unix socket, so another terminal, local or over
+/// SSH, shows the same screen without polling anything itself.
+#[derive(Debug)]
+pub struct Mirror {
+    frames: watch::Sender<Arc<Vec<u8>>>,
+    path: PathBuf,
+    /// Held for as long as the socket is ours.
+    _lock: File,
+}
+
+/// `mirror.socket`, or `mirror.sock` in the runtime directory when there is a private one.
+pub fn socket_path(config: &AppConfig) -> Option<PathBuf> {
+    match config.mirror.socket.is_empty() {
+        true => paths::runtime_dir().map(|dir| dir.join("mirror.sock")),
+        false => Some(PathBuf::from(&config.mirror.socket)),
+    }
+}
+
+impl Mirror {
+    /// Binds the socket, `None` when mirroring is off or another instance is already
+    /// mirroring there.
+    pub fn from_config(config: &AppConfig, thread: &AppThread) -> Option<Mirror> {
+        if !config.mirror.enabled {
+            return None;
+        }
+
+        let path = socket_path(config)?;
+
+        if std::os::unix::net::UnixStream::connect(&path).is_ok() {
+            return None;
+        }
+
+        let lock = paths::lock_socket(&path)?;
+
+        // Nobody answers on the socket, it is left over from an instance that died
+        let _ = fs::remove_file(&path);
+
+        let listener = UnixListener::bind(&path).ok()?;
+        paths::restrict_socket(&path);
+        let (frames, receiver) = watch::channel(Arc::new(vec![]));
+
+        let token = thread.token.clone();
+        let clients = thread.clone();
+        thread.tracker.spawn(async move {
+            tokio::select! {
+                () = serve(listener, receiver, clients) => {},
+                () = token.cancelled() => {},
+            }
+        });
+
+        Some(Mirror {
+            frames,
+            path,
+            _lock: lock,
+        })
+    }
+
+    /// Sends the frame just drawn to the attached clients, if there are any.
+    pub fn publish(&self, buffer: &Buffer) {
+        // The accepting task holds one receiver, every client another
+        if self.frames.receiver_count() < 2 {
+            return;
+        }
+
+        if let Ok(frame) = encode(buffer) {
+            self.frames.send_replace(Arc::new(frame));
+        }
+    }
+}
+
+impl Drop for Mirror {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+async fn serve(listener: UnixListener, frames: watch::Receiver<Arc<Vec<u8>>>, thread: AppThread) {
+    while let Ok((mut stream, _)) = listener.accept().await {
+        let mut frames = frames.clone();
+        let token = thread.token.clone();
+
+        thread.tracker.spawn(async move {
+            loop {
+                // Slow clients skip to the latest frame instead of falling behind
+                let frame = frames.borrow_and_update().clone();
+
+                // A client that stopped reading must not hold up quitting
+                tokio::select! {
+                    written = stream.write_all(&frame) => if written.is_err() { break },
+                    () = token.cancelled() => break,
+                }
+
+                tokio::select! {
+                    changed = frames.changed() => if changed.is_err() { break },
+                    () = token.cancelled() => break,
+                }
+            }
+        });
+    }
+}
+
+/// Whole frame, cleared and drawn from the top left. Clients attach at any time and only
+/// the latest frame is sent, so there is no previous one to diff against.
+fn encode(buffer: &Buffer) -> io::Result<Vec<u8>> {
+    let mut frame = vec![];
+    crossterm::queue!(frame, MoveTo(0, 0), Clear(ClearType::All))?;
+
+    let mut backend = CrosstermBackend::new(&mut frame);
+    let updates = Buffer::empty(buffer.area).diff(buffer);
+    backend.draw(updates.into_iter())?;
+
+    Ok(frame)
+}
+
+/// `btcmon attach [socket]`: shows the dashboard of the instance mirroring on the socket
+/// until `q`, Esc or Ctrl-C, or until that instance quits.
+pub async fn attach(path: &Path) -> AppResult<()> {
+    let mut stream = UnixStream::connect(path).await.map_err(|e| {
+        format!(
+            "No btcmon mirroring on {}, start one with mirror.enabled = true: {}",
+            path.display(),
+            e
+        )
+    })?;
+
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    crossterm::execute!(stdout, EnterAlternateScreen, Hide)?;
+
+    let mut keys = EventStream::new();
+    let mut bytes = vec![0; 64 * 1024];
+
+    let result: AppResult<()> = loop {
+        tokio::select! {
+            read = stream.read(&mut bytes) => match read {
+                Ok(0) => break Ok(()),
+                Ok(n) => {
+                    if let Err(e) = stdout.write_all(&bytes[..n]).and_then(|()| stdout.flush()) {
+                        break Err(e.into());
+                    }
+                }
+                Err(e) => break Err(e.into()),
+            },
+            key = keys.next() => match key {
+                Some(Ok(CrosstermEvent::Key(key)))
+                    if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+                        || (key.code == KeyCode::Char('c')
+                            && key.modifiers.contains(KeyModifiers::CONTROL)) =>
+                {
+                    break Ok(())
+                }
+                None => break Ok(()),
+                _ => {}
+            },
+        }
+    };
+
+    crossterm::execute!(stdout, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    result
+}
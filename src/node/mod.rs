@@ -1,19 +1,27 @@
 pub mod providers;
+pub mod widgets;
 
-use crate::{app::AppThread, config::AppConfig};
+use crate::{app::AppThread, config::AppConfig, health::SourceHealth};
 use anyhow::Result;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fmt,
     marker::Sized,
     sync::{Arc, Mutex},
 };
 use tokio::{
-    sync::mpsc::{UnboundedReceiver, UnboundedSender},
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
     time::Instant,
 };
 
+/// Key of the block timestamps in the store.
+pub const BLOCK_TIMES_KEY: &str = "block_times";
+
+/// How far back block arrivals are kept for the heatmap.
+pub const BLOCK_TIMES_WINDOW: i64 = 7 * 24 * 60 * 60;
+
 pub enum NodeKind {
     BitcoinCore,
     CLightning,
@@ -25,11 +33,13 @@ pub struct NodeChannel<E> {
     pub receiver: Arc<Mutex<UnboundedReceiver<E>>>,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum NodeStatus {
     Online,
     Offline,
     Synchronizing,
+    /// Up but not ready yet, warming up after a (re)start.
+    Starting,
 }
 
 impl fmt::Display for NodeStatus {
@@ -38,31 +48,141 @@ impl fmt::Display for NodeStatus {
     }
 }
 
-pub enum NodeEvent {
-    NewBlock(String),
-    Status(NodeStatus),
-    State(NodeState),
+impl NodeStatus {
+    /// Online unless the node is further behind its headers than the tolerance.
+    pub fn from_sync(height: u64, headers: u64, tolerance: u64) -> Self {
+        if headers.saturating_sub(height) > tolerance {
+            NodeStatus::Synchronizing
+        } else {
+            NodeStatus::Online
+        }
+    }
 }
 
+/// Actions forwarded to the running provider.
 #[derive(Clone, Debug)]
+pub enum NodeCommand {
+    DisconnectPeer(u64),
+    /// `addnode <address> onetry`, a single connection attempt.
+    AddNode(String),
+    /// `generatetoaddress` on regtest or signet.
+    MineBlocks(u64),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NodePeer {
+    pub id: u64,
+    pub address: String,
+    pub client: String,
+    pub inbound: bool,
+    pub ping: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NodeBan {
+    pub address: String,
+    pub banned_until: i64,
+}
+
+/// Implementation badge from a BIP14 user agent like `/Satoshi:27.1.0/Knots:20240801/`,
+/// the last component names the actual software.
+pub fn parse_implementation(subversion: &str) -> Option<String> {
+    let (name, version) = subversion
+        .split('/')
+        .rfind(|component| !component.is_empty())?
+        .split_once(':')?;
+
+    let name = match name {
+        "Satoshi" => "Core",
+        name => name,
+    };
+
+    Some(format!(
+        "{} {}",
+        name,
+        version.split('(').next().unwrap_or(version)
+    ))
+}
+
+/// Blocks between difficulty adjustments.
+pub const RETARGET_INTERVAL: u64 = 2016;
+
+/// Difficulty adjustment at the start of an epoch.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NodeEpoch {
+    pub height: u64,
+    pub time: i64,
+    pub difficulty: f64,
+    /// Relative to the previous epoch, `0.05` is +5%.
+    pub change: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NodeState {
+    pub name: String,
+    pub label: Option<String>,
+    pub implementation: Option<String>,
+    pub policy: Option<String>,
+    /// Lowest rate in sat/vB the mempool accepts right now, rises as it fills up.
+    #[serde(default)]
+    pub mempool_min_fee: Option<f64>,
+    /// `main`, `test`, `signet` or `regtest`, when the provider knows it.
+    #[serde(default)]
+    pub chain: Option<String>,
     pub status: NodeStatus,
+    /// What the node is busy with while starting, e.g. `Verifying blocks…`.
+    #[serde(default)]
+    pub starting: Option<String>,
     pub height: u64,
     pub headers: u64,
+    /// Bytes used by the block and undo files, as reported by the node.
+    pub size_on_disk: Option<u64>,
     pub last_hash: String,
+    #[serde(skip)]
     pub last_hash_instant: Option<Instant>,
+    pub last_hash_time: Option<i64>,
+    /// Timestamps of the blocks of the last week by height, persisted across restarts.
+    pub block_times: BTreeMap<u64, i64>,
+    /// Latest retargets, newest first.
+    pub epochs: Vec<NodeEpoch>,
+    /// Expected change at the next retarget given the current epoch pace.
+    pub difficulty_estimate: Option<f64>,
     pub services: HashMap<String, NodeStatus>,
+    pub peers: Vec<NodePeer>,
+    pub banned: Vec<NodeBan>,
+    /// When the provider last polled the node successfully.
+    #[serde(skip, default = "SourceHealth::fresh")]
+    pub health: SourceHealth,
+    /// Polls that failed since the provider started, for the session summary.
+    #[serde(default)]
+    pub failed_polls: u64,
 }
 
 impl Default for NodeState {
     fn default() -> Self {
         Self {
+            name: "Node".to_string(),
+            label: None,
+            implementation: None,
+            policy: None,
+            mempool_min_fee: None,
+            chain: None,
             status: NodeStatus::Offline,
+            starting: None,
             height: 0,
             headers: 0,
+            size_on_disk: None,
             last_hash: "".to_string(),
             last_hash_instant: None,
+            last_hash_time: None,
+            block_times: BTreeMap::new(),
+            epochs: vec![],
+            difficulty_estimate: None,
             services: HashMap::new(),
+            peers: vec![],
+            banned: vec![],
+            health: SourceHealth::default(),
+            failed_polls: 0,
         }
     }
 }
@@ -71,6 +191,11 @@ impl NodeState {
     pub fn new() -> Arc<Mutex<Self>> {
         Arc::new(Mutex::new(Self::default()))
     }
+
+    /// Test chains where blocks can be mined on demand.
+    pub fn can_mine(&self) -> bool {
+        matches!(self.chain.as_deref(), Some("regtest" | "signet"))
+    }
 }
 
 #[async_trait]
@@ -78,13 +203,18 @@ pub trait NodeProvider {
     fn new(config: &AppConfig) -> Self
     where
         Self: Sized;
-    async fn init(&mut self, thread: AppThread) -> Result<()>;
+    async fn init(
+        &mut self,
+        thread: AppThread,
+        commands: UnboundedReceiver<NodeCommand>,
+    ) -> Result<()>;
     fn get_state(&self) -> Arc<Mutex<NodeState>>;
 }
 
 pub struct Node {
     pub thread: AppThread,
     handler: Option<tokio::task::JoinHandle<()>>,
+    commands: Option<UnboundedSender<NodeCommand>>,
 }
 
 impl Node {
@@ -92,6 +222,7 @@ impl Node {
         Self {
             thread: thread.clone(),
             handler: None,
+            commands: None,
         }
     }
 
@@ -100,13 +231,22 @@ impl Node {
             handler.abort();
         }
 
+        let (sender, receiver) = unbounded_channel();
+        self.commands = Some(sender);
+
         let token = self.thread.token.clone();
         let thread = self.thread.clone();
         self.handler = Some(self.thread.tracker.spawn(async move {
             tokio::select! {
-                _ = provider.init(thread) => {},
+                _ = provider.init(thread, receiver) => {},
                 () = token.cancelled() => {},
             }
         }));
     }
+
+    pub fn send(&self, command: NodeCommand) {
+        if let Some(commands) = &self.commands {
+            let _ = commands.send(command);
+        }
+    }
 }
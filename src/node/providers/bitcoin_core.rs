@@ -1,41 +1,133 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use bitcoin::{Address, BlockHash};
 use bitcoincore_rpc::{json::GetBlockchainInfoResult, RpcApi};
+#[cfg(feature = "zmq")]
 use bitcoincore_zmq::subscribe_async_monitor_stream::MessageStream;
+#[cfg(feature = "zmq")]
 use bitcoincore_zmq::{subscribe_async_wait_handshake, SocketEvent, SocketMessage};
+use chrono::Utc;
+#[cfg(feature = "zmq")]
 use futures::StreamExt;
+use serde_json::Value;
+use std::str::FromStr;
 use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::UnboundedReceiver;
 use tokio::time;
+#[cfg(feature = "zmq")]
 use tokio::time::Instant;
 
+use super::bitcoin_core_client;
+#[cfg(feature = "zmq")]
+use super::{host_port, is_onion};
 use crate::{
     app::AppThread,
+    bandwidth,
     config::AppConfig,
-    node::{NodeProvider, NodeState, NodeStatus},
+    health::SourceHealth,
+    node::{
+        parse_implementation, NodeBan, NodeCommand, NodeEpoch, NodePeer, NodeProvider, NodeState,
+        NodeStatus, BLOCK_TIMES_WINDOW, RETARGET_INTERVAL,
+    },
+    throughput::{self, Feed},
 };
 
 #[derive(Clone)]
 pub struct BitcoinCore {
     rpc_client: Arc<bitcoincore_rpc::Client>,
-    zmq_url: Option<String>,
+    #[cfg(feature = "zmq")]
+    zmq_topics: Vec<ZmqTopic>,
+    /// Height of a block found older than the heatmap window, none are fetched below it.
+    block_times_floor: Option<u64>,
+    epochs_count: u64,
+    sync_tolerance: u64,
+    /// Only the chain tip, rarely, for remote nodes behind slow links.
+    lightweight: bool,
+    /// Where mined blocks pay to, a new wallet address when empty.
+    mine_address: String,
+    /// Seconds the node was up at the last poll, a lower one means it restarted.
+    uptime: Option<u64>,
     state: Arc<Mutex<NodeState>>,
 }
 
+/// ZMQ endpoint subscribed on its own, so every topic reports its own status.
+#[cfg(feature = "zmq")]
+#[derive(Clone)]
+struct ZmqTopic {
+    service: &'static str,
+    url: String,
+}
+
+#[cfg(feature = "zmq")]
+const ZMQ_BLOCKS: &str = "ZMQ-blocks";
+
+pub const CHECK_INTERVAL: time::Duration = time::Duration::from_secs(15);
+/// A couple of blocks worth of polls is plenty when all that is shown is the tip.
+pub const LIGHTWEIGHT_CHECK_INTERVAL: time::Duration = time::Duration::from_secs(5 * 60);
+
+/// Nodes up for less than this many seconds are still finding peers, shown as starting.
+const STARTING_UPTIME: u64 = 60;
+
+/// RPC error Bitcoin Core answers with while loading the block index, verifying blocks
+/// or rescanning, before it takes calls.
+const RPC_IN_WARMUP: i32 = -28;
+
+/// What the node says it is busy with, when the call failed because it is warming up.
+fn warmup_message(error: &anyhow::Error) -> Option<String> {
+    use bitcoincore_rpc::jsonrpc::error::Error as JsonRpcError;
+
+    match error.downcast_ref::<bitcoincore_rpc::Error>()? {
+        bitcoincore_rpc::Error::JsonRpc(JsonRpcError::Rpc(e)) if e.code == RPC_IN_WARMUP => {
+            Some(e.message.clone())
+        }
+        _ => None,
+    }
+}
+
 impl BitcoinCore {
+    /// Runs a blocking RPC call off the async workers, so a stuck one (e.g. over Tor)
+    /// can be abandoned when quitting instead of holding everything up.
+    async fn rpc<T, F>(rpc_client: &Arc<bitcoincore_rpc::Client>, call: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&bitcoincore_rpc::Client) -> bitcoincore_rpc::Result<T> + Send + 'static,
+    {
+        let rpc_client = rpc_client.clone();
+        Ok(tokio::task::spawn_blocking(move || call(&rpc_client)).await??)
+    }
+
     async fn get_blockchain_info(&mut self) -> Result<GetBlockchainInfoResult> {
-        match self.rpc_client.get_blockchain_info() {
+        match BitcoinCore::rpc(&self.rpc_client, |rpc| rpc.get_blockchain_info()).await {
             Ok(blockchain_info) => {
-                let mut state = self.state.lock().unwrap();
-                let new_status = if blockchain_info.blocks < blockchain_info.headers {
-                    NodeStatus::Synchronizing
-                } else {
-                    NodeStatus::Online
+                throughput::record(Feed::NodePolls);
+
+                let block_time = match self.lightweight {
+                    true => None,
+                    false => {
+                        BitcoinCore::get_block_time(
+                            &self.rpc_client,
+                            blockchain_info.best_block_hash,
+                        )
+                        .await
+                    }
                 };
 
+                let mut state = self.state.lock().unwrap();
+                let new_status = NodeStatus::from_sync(
+                    blockchain_info.blocks,
+                    blockchain_info.headers,
+                    self.sync_tolerance,
+                );
+
                 state.status = new_status;
+                state.starting = None;
+                state.health = SourceHealth::fresh();
                 state.last_hash = blockchain_info.best_block_hash.to_string();
+                state.last_hash_time = block_time;
                 state.headers = blockchain_info.headers;
                 state.height = blockchain_info.blocks;
+                state.size_on_disk = Some(blockchain_info.size_on_disk);
+                state.chain = Some(blockchain_info.chain.to_core_arg().to_string());
 
                 *state
                     .services
@@ -45,24 +137,263 @@ impl BitcoinCore {
                 Ok(blockchain_info)
             }
             Err(e) => {
+                let warmup = warmup_message(&e);
+                let status = match warmup {
+                    Some(_) => NodeStatus::Starting,
+                    None => NodeStatus::Offline,
+                };
+
                 let mut state = self.state.lock().unwrap();
-                *state
-                    .services
-                    .entry("RPC".to_string())
-                    .or_insert(NodeStatus::Offline) = NodeStatus::Offline;
-                state.status = NodeStatus::Offline;
-                Err(e.into())
+                *state.services.entry("RPC".to_string()).or_insert(status) = status;
+                state.status = status;
+                // A node warming up answered, it is not down
+                match warmup {
+                    Some(message) => {
+                        state.starting = Some(message);
+                        state.health = SourceHealth::fresh();
+                    }
+                    None => {
+                        state.starting = None;
+                        state.health.failing = true;
+                        state.failed_polls += 1;
+                    }
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Walks back from the tip until a block already known with the same time, or one
+    /// older than the window, so only new headers are fetched once the times persisted
+    /// by the app are loaded. Blocks replaced by a reorg are overwritten at their height.
+    /// Heights still missing, from a walk cut short or a long time offline, are filled in
+    /// after that.
+    async fn get_block_times(&mut self, tip: BlockHash, tip_height: u64) -> Result<()> {
+        let cutoff = Utc::now().timestamp() - BLOCK_TIMES_WINDOW;
+        let mut hash = Some(tip);
+
+        while let Some(current) = hash {
+            let header = BitcoinCore::rpc(&self.rpc_client, move |rpc| {
+                rpc.get_block_header_info(&current)
+            })
+            .await?;
+
+            let time = header.time as i64;
+            let height = header.height as u64;
+            let mut state = self.state.lock().unwrap();
+
+            if time < cutoff {
+                self.block_times_floor = Some(height);
+                break;
+            }
+            if state.block_times.get(&height) == Some(&time) {
+                break;
             }
+
+            state.block_times.insert(height, time);
+            hash = header.previous_block_hash;
         }
+
+        let floor = self.block_times_floor.unwrap_or(0);
+        for height in (floor + 1..=tip_height).rev() {
+            if self.state.lock().unwrap().block_times.contains_key(&height) {
+                continue;
+            }
+
+            let header = BitcoinCore::rpc(&self.rpc_client, move |rpc| {
+                rpc.get_block_header_info(&rpc.get_block_hash(height)?)
+            })
+            .await?;
+
+            if (header.time as i64) < cutoff {
+                self.block_times_floor = Some(height);
+                break;
+            }
+
+            let mut state = self.state.lock().unwrap();
+            state.block_times.insert(height, header.time as i64);
+        }
+
+        let mut state = self.state.lock().unwrap();
+
+        // Heights past the tip were left by a reorg to a shorter chain
+        state
+            .block_times
+            .retain(|height, time| *height <= tip_height && *time >= cutoff);
+
+        Ok(())
     }
 
+    /// Fetches the epoch start headers again only when a new retarget happened, the
+    /// estimate is refreshed on every call.
+    async fn get_epochs(&mut self, height: u64, tip_time: Option<i64>) -> Result<()> {
+        let current_start = height - height % RETARGET_INTERVAL;
+        let known_start = self.state.lock().unwrap().epochs.first().map(|e| e.height);
+
+        if known_start != Some(current_start) {
+            let mut epochs = vec![];
+
+            // One extra epoch so the oldest one listed still has a change
+            for i in 0..=self.epochs_count {
+                let Some(start) = current_start.checked_sub(i * RETARGET_INTERVAL) else {
+                    break;
+                };
+
+                let header = BitcoinCore::rpc(&self.rpc_client, move |rpc| {
+                    rpc.get_block_header_info(&rpc.get_block_hash(start)?)
+                })
+                .await?;
+
+                epochs.push(NodeEpoch {
+                    height: start,
+                    time: header.time as i64,
+                    difficulty: header.difficulty,
+                    change: None,
+                });
+            }
+
+            for i in 0..epochs.len().saturating_sub(1) {
+                epochs[i].change = Some(epochs[i].difficulty / epochs[i + 1].difficulty - 1.0);
+            }
+
+            epochs.truncate(self.epochs_count as usize);
+            self.state.lock().unwrap().epochs = epochs;
+        }
+
+        let mut state = self.state.lock().unwrap();
+
+        state.difficulty_estimate = match (state.epochs.first(), tip_time) {
+            (Some(epoch), Some(tip_time)) if height > epoch.height && tip_time > epoch.time => {
+                let expected = ((height - epoch.height) * 600) as f64;
+                let actual = (tip_time - epoch.time) as f64;
+                // Consensus caps every adjustment to a factor of four
+                Some((expected / actual).clamp(0.25, 4.0) - 1.0)
+            }
+            _ => None,
+        };
+
+        Ok(())
+    }
+
+    async fn get_peers(&mut self) -> Result<()> {
+        let (peers, banned) = BitcoinCore::rpc(&self.rpc_client, |rpc| {
+            Ok((rpc.get_peer_info()?, rpc.list_banned()?))
+        })
+        .await?;
+
+        let mut state = self.state.lock().unwrap();
+
+        state.peers = peers
+            .into_iter()
+            .map(|peer| NodePeer {
+                id: peer.id,
+                address: peer.addr,
+                client: peer.subver,
+                inbound: peer.inbound,
+                ping: peer.pingtime,
+            })
+            .collect();
+
+        state.banned = banned
+            .into_iter()
+            .map(|ban| NodeBan {
+                address: ban.address,
+                banned_until: ban.banned_until as i64,
+            })
+            .collect();
+
+        Ok(())
+    }
+
+    /// Reads the implementation from the user agent, the mempool policy settings
+    /// that only recent Core and Knots (with its extended policy) report, and the
+    /// minimum fee the mempool currently accepts.
+    async fn get_implementation(&mut self) -> Result<()> {
+        let (network_info, mempool_info) = BitcoinCore::rpc(&self.rpc_client, |rpc| {
+            Ok((
+                rpc.get_network_info()?,
+                rpc.call::<Value>("getmempoolinfo", &[])?,
+            ))
+        })
+        .await?;
+
+        let mut policy = vec![];
+
+        if let Some(size) = mempool_info["maxdatacarriersize"].as_u64() {
+            policy.push(format!("datacarrier {} B", size));
+        }
+        if let Some(fullrbf) = mempool_info["fullrbf"].as_bool() {
+            policy.push(format!("fullrbf {}", if fullrbf { "on" } else { "off" }));
+        }
+        if let Some(bare_multisig) = mempool_info["permitbaremultisig"].as_bool() {
+            policy.push(format!(
+                "bare multisig {}",
+                if bare_multisig { "on" } else { "off" }
+            ));
+        }
+
+        let mut state = self.state.lock().unwrap();
+
+        state.implementation = parse_implementation(&network_info.subversion);
+        // In BTC/kvB
+        state.mempool_min_fee = mempool_info["mempoolminfee"]
+            .as_f64()
+            .map(|fee| fee * 100_000.0);
+        state.policy = match policy.is_empty() {
+            true => None,
+            false => Some(policy.join(" · ")),
+        };
+
+        Ok(())
+    }
+
+    async fn execute(&mut self, command: NodeCommand) -> Result<()> {
+        match command {
+            NodeCommand::DisconnectPeer(id) => {
+                BitcoinCore::rpc(&self.rpc_client, move |rpc| {
+                    rpc.disconnect_node_by_id(id as u32)
+                })
+                .await?;
+
+                self.get_peers().await
+            }
+            NodeCommand::AddNode(address) => {
+                BitcoinCore::rpc(&self.rpc_client, move |rpc| rpc.onetry_node(&address)).await?;
+
+                self.get_peers().await
+            }
+            NodeCommand::MineBlocks(blocks) => {
+                let address = match self.mine_address.is_empty() {
+                    true => None,
+                    false => Some(Address::from_str(&self.mine_address)?.assume_checked()),
+                };
+
+                BitcoinCore::rpc(&self.rpc_client, move |rpc| {
+                    let address = match address {
+                        Some(address) => address,
+                        None => rpc.get_new_address(None, None)?.assume_checked(),
+                    };
+                    rpc.generate_to_address(blocks, &address)
+                })
+                .await?;
+
+                self.poll().await;
+                Ok(())
+            }
+        }
+    }
+
+    #[cfg(feature = "zmq")]
     fn spawn_zmq_listener(
         &self,
         thread: &AppThread,
+        topic: &ZmqTopic,
         mut stream: MessageStream,
     ) -> tokio::task::JoinHandle<()> {
+        let service = topic.service;
         let token = thread.token.clone();
         let state = self.state.clone();
+        let rpc_client = self.rpc_client.clone();
         thread.tracker.spawn(async move {
             loop {
                 let recv = tokio::select! {
@@ -72,26 +403,52 @@ impl BitcoinCore {
 
                 if let Some(ref msg) = recv {
                     match msg {
-                        Ok(SocketMessage::Message(msg)) => match msg {
-                            bitcoincore_zmq::Message::HashBlock(hash, _) => {
+                        Ok(SocketMessage::Message(msg)) => {
+                            bandwidth::record(
+                                bandwidth::Source::Node,
+                                msg.serialize_data_to_vec().len(),
+                            );
+
+                            // Endpoints may be shared, only the blocks topic moves the tip
+                            let is_block = matches!(msg, bitcoincore_zmq::Message::HashBlock(..));
+                            throughput::record(match is_block {
+                                true => Feed::ZmqBlocks,
+                                false => Feed::ZmqOther,
+                            });
+
+                            if service != ZMQ_BLOCKS {
+                                continue;
+                            }
+
+                            if let bitcoincore_zmq::Message::HashBlock(hash, _) = msg {
+                                let block_time =
+                                    BitcoinCore::get_block_time(&rpc_client, *hash).await;
                                 let hash = hash.to_string();
                                 let mut locked_state = state.lock().unwrap();
 
                                 if locked_state.last_hash != hash {
                                     locked_state.height += 1;
                                     locked_state.last_hash = hash;
+                                    locked_state.last_hash_time = block_time;
                                 }
 
                                 locked_state.last_hash_instant = Some(Instant::now());
                             }
-                            _ => {}
-                        },
+                        }
                         Ok(SocketMessage::Event(event)) => match event.event {
                             SocketEvent::Disconnected { .. } => {
-                                BitcoinCore::set_service_status(&state, "ZMQ", NodeStatus::Offline);
+                                BitcoinCore::set_service_status(
+                                    &state,
+                                    service,
+                                    NodeStatus::Offline,
+                                );
                             }
                             SocketEvent::HandshakeSucceeded => {
-                                BitcoinCore::set_service_status(&state, "ZMQ", NodeStatus::Online);
+                                BitcoinCore::set_service_status(
+                                    &state,
+                                    service,
+                                    NodeStatus::Online,
+                                );
                             }
                             _ => {}
                         },
@@ -104,16 +461,17 @@ impl BitcoinCore {
                 }
             }
 
-            BitcoinCore::set_service_status(&state, "ZMQ", NodeStatus::Offline);
+            BitcoinCore::set_service_status(&state, service, NodeStatus::Offline);
         })
     }
 
+    #[cfg(feature = "zmq")]
     async fn subscribe(
         &mut self,
         thread: &AppThread,
-        zmq_url: &str,
+        topic: &ZmqTopic,
     ) -> Result<tokio::task::JoinHandle<()>> {
-        let urls = [zmq_url];
+        let urls = [topic.url.as_str()];
 
         let select = tokio::select! {
             r = tokio::time::timeout(
@@ -125,28 +483,96 @@ impl BitcoinCore {
 
         let stream = match select {
             Some(Ok(stream)) => {
-                BitcoinCore::set_service_status(&self.state, "ZMQ", NodeStatus::Online);
+                BitcoinCore::set_service_status(&self.state, topic.service, NodeStatus::Online);
                 stream
             }
             _ => {
+                BitcoinCore::set_service_status(&self.state, topic.service, NodeStatus::Offline);
                 return Err(anyhow::Error::msg("Failed to subscribe to ZMQ"));
             }
         };
 
-        Ok(self.spawn_zmq_listener(thread, stream))
+        Ok(self.spawn_zmq_listener(thread, topic, stream))
     }
 
+    /// Subscribes again to every topic whose listener is gone or never started.
+    #[cfg(feature = "zmq")]
     async fn try_subscribe(
         &mut self,
         thread: &AppThread,
-    ) -> Option<Result<tokio::task::JoinHandle<()>>> {
-        if let Some(url) = self.zmq_url.clone() {
-            return Some(self.subscribe(&thread, &url).await);
-        };
+        handlers: &mut Vec<Option<Result<tokio::task::JoinHandle<()>>>>,
+    ) {
+        handlers.resize_with(self.zmq_topics.len(), || None);
+
+        for (topic, handler) in self.zmq_topics.clone().iter().zip(handlers.iter_mut()) {
+            match handler {
+                Some(Ok(handle)) if !handle.is_finished() => {}
+                _ => *handler = Some(self.subscribe(thread, topic).await),
+            }
+        }
+    }
+
+    #[cfg(not(feature = "zmq"))]
+    async fn try_subscribe(
+        &mut self,
+        _thread: &AppThread,
+        _handlers: &mut Vec<Option<Result<tokio::task::JoinHandle<()>>>>,
+    ) {
+    }
+
+    /// A node that restarted between two polls never looked offline, its uptime going
+    /// down tells. Either way it is starting until it was up for [`STARTING_UPTIME`].
+    async fn get_uptime(&mut self) -> Result<()> {
+        let uptime = BitcoinCore::rpc(&self.rpc_client, |rpc| rpc.uptime()).await?;
+        let restarted = self.uptime.is_some_and(|previous| uptime < previous);
+        self.uptime = Some(uptime);
+
+        let mut state = self.state.lock().unwrap();
+        if uptime < STARTING_UPTIME && state.status == NodeStatus::Online {
+            state.status = NodeStatus::Starting;
+            state.starting = Some(match restarted {
+                true => "Restarted, finding peers…".to_string(),
+                false => "Finding peers…".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Everything the panels show, or only getblockchaininfo in lightweight mode.
+    async fn poll(&mut self) {
+        let blockchain_info = self.get_blockchain_info().await;
+
+        if blockchain_info.is_ok() {
+            let _ = self.get_uptime().await;
+        }
+
+        if self.lightweight {
+            return;
+        }
+
+        if let Ok(blockchain_info) = blockchain_info {
+            let _ = self
+                .get_block_times(blockchain_info.best_block_hash, blockchain_info.blocks)
+                .await;
+            let tip_time = self.state.lock().unwrap().last_hash_time;
+            let _ = self.get_epochs(blockchain_info.blocks, tip_time).await;
+        }
+        let _ = self.get_peers().await;
+        let _ = self.get_implementation().await;
+    }
 
-        None
+    async fn get_block_time(
+        rpc_client: &Arc<bitcoincore_rpc::Client>,
+        hash: BlockHash,
+    ) -> Option<i64> {
+        BitcoinCore::rpc(rpc_client, move |rpc| rpc.get_block_header_info(&hash))
+            .await
+            .ok()
+            .map(|header| header.time as i64)
     }
 
+    #[cfg(feature = "zmq")]
     fn set_service_status(state: &Arc<Mutex<NodeState>>, service: &str, status: NodeStatus) {
         *state
             .lock()
@@ -160,31 +586,26 @@ impl BitcoinCore {
 #[async_trait]
 impl NodeProvider for BitcoinCore {
     fn new(config: &AppConfig) -> Self {
-        let rpc = bitcoincore_rpc::Client::new(
-            vec![
-                config.bitcoin_core.host.as_str(),
-                config.bitcoin_core.rpc_port.as_str(),
+        let rpc = bitcoin_core_client(config);
+
+        // Topics without a port are not watched, ZMQ can't go through a proxy
+        #[cfg(feature = "zmq")]
+        let zmq_topics: Vec<ZmqTopic> = match config.bitcoin_core.host.as_str() {
+            _ if config.bitcoin_core.lightweight => vec![],
+            "" => vec![],
+            host if is_onion(host) => vec![],
+            host => [
+                (ZMQ_BLOCKS, &config.bitcoin_core.zmq_port),
+                ("ZMQ-tx", &config.bitcoin_core.zmq_rawtx_port),
+                ("ZMQ-sequence", &config.bitcoin_core.zmq_sequence_port),
             ]
-            .join(":")
-            .as_str(),
-            bitcoincore_rpc::Auth::UserPass(
-                config.bitcoin_core.rpc_user.to_string(),
-                config.bitcoin_core.rpc_password.to_string(),
-            ),
-        )
-        .unwrap();
-
-        let zmq_url: Option<String> = match config.bitcoin_core.host.as_str() {
-            "" => None,
-            _ => Some(
-                vec![
-                    "tcp://",
-                    &config.bitcoin_core.host,
-                    ":",
-                    &config.bitcoin_core.zmq_port,
-                ]
-                .join(""),
-            ),
+            .into_iter()
+            .filter(|(_, port)| !port.is_empty())
+            .map(|(service, port)| ZmqTopic {
+                service,
+                url: ["tcp://", &host_port(host, port)].join(""),
+            })
+            .collect(),
         };
 
         let state = NodeState::new();
@@ -192,55 +613,69 @@ impl NodeProvider for BitcoinCore {
         {
             let mut locked_state = state.lock().unwrap();
 
+            locked_state.name = "Bitcoin Core".to_string();
             locked_state
                 .services
                 .insert("RPC".to_string(), NodeStatus::Offline);
 
-            locked_state
-                .services
-                .insert("ZMQ".to_string(), NodeStatus::Offline);
+            #[cfg(feature = "zmq")]
+            for topic in zmq_topics.iter() {
+                locked_state
+                    .services
+                    .insert(topic.service.to_string(), NodeStatus::Offline);
+            }
         }
 
         Self {
             rpc_client: Arc::new(rpc),
-            zmq_url,
+            #[cfg(feature = "zmq")]
+            zmq_topics,
+            block_times_floor: None,
+            epochs_count: config.difficulty.epochs,
+            sync_tolerance: config.node.sync_tolerance_blocks,
+            lightweight: config.bitcoin_core.lightweight,
+            mine_address: config.bitcoin_core.mine_address.clone(),
+            uptime: None,
             state,
         }
     }
 
-    async fn init(&mut self, thread: AppThread) -> Result<()> {
-        let check_interval = time::Duration::from_millis(15 * 1000);
+    async fn init(
+        &mut self,
+        thread: AppThread,
+        mut commands: UnboundedReceiver<NodeCommand>,
+    ) -> Result<()> {
+        let check_interval = match self.lightweight {
+            true => LIGHTWEIGHT_CHECK_INTERVAL,
+            false => CHECK_INTERVAL,
+        };
 
-        let _ = self.get_blockchain_info().await;
+        self.poll().await;
 
-        let mut sub_handlers = Box::new(self.try_subscribe(&thread).await);
+        let mut sub_handlers = vec![];
 
         loop {
             if thread.token.is_cancelled() {
                 break;
             }
 
-            match *sub_handlers {
-                Some(Ok(ref handler)) => {
-                    if handler.is_finished() {
-                        sub_handlers = Box::new(self.try_subscribe(&thread).await);
-                    }
-                }
-                Some(Err(_)) => {
-                    sub_handlers = Box::new(self.try_subscribe(&thread).await);
-                }
-                _ => {}
-            }
+            self.try_subscribe(&thread, &mut sub_handlers).await;
 
-            let _ = self.get_blockchain_info().await;
+            self.poll().await;
 
-            tokio::time::sleep(check_interval).await;
+            tokio::select! {
+                () = thread.token.cancelled() => break,
+                () = tokio::time::sleep(bandwidth::stretch(check_interval)) => {}
+                Some(command) = commands.recv() => {
+                    let _ = self.execute(command).await;
+                }
+            }
         }
 
         Ok(())
     }
 
     fn get_state(&self) -> Arc<Mutex<NodeState>> {
-        return self.state.clone();
+        self.state.clone()
     }
 }
@@ -0,0 +1,169 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::time::{self, Instant};
+
+use super::custom_node_client;
+use crate::{
+    app::AppThread,
+    bandwidth::{self, Source, HTTP_OVERHEAD},
+    config::{AppConfig, CustomNodeSettings},
+    health::SourceHealth,
+    node::{NodeCommand, NodeProvider, NodeState, NodeStatus},
+    throughput::{self, Feed},
+};
+
+/// Node backed by any JSON HTTP endpoint, mapped through the `custom_node` settings.
+#[derive(Clone)]
+pub struct CustomNode {
+    settings: CustomNodeSettings,
+    sync_tolerance: u64,
+    client: reqwest::Client,
+    state: Arc<Mutex<NodeState>>,
+}
+
+impl CustomNode {
+    async fn fetch(&mut self) -> Result<Value> {
+        let mut request = self.client.get(&self.settings.url);
+
+        for (name, value) in self.settings.headers.iter() {
+            request = request.header(name, value);
+        }
+
+        let body = request.send().await?.error_for_status()?.bytes().await?;
+        bandwidth::record(Source::Node, body.len() + HTTP_OVERHEAD);
+
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    async fn update(&mut self) -> Result<()> {
+        let body = match self.fetch().await {
+            Ok(body) => body,
+            Err(e) => {
+                let mut state = self.state.lock().unwrap();
+                state.status = NodeStatus::Offline;
+                state.health.failing = true;
+                state.failed_polls += 1;
+                state
+                    .services
+                    .insert("HTTP".to_string(), NodeStatus::Offline);
+                return Err(e);
+            }
+        };
+
+        throughput::record(Feed::NodePolls);
+
+        let height = get_path(&body, &self.settings.height_path).and_then(value_to_u64);
+        let headers = get_path(&body, &self.settings.headers_path).and_then(value_to_u64);
+        let label = get_path(&body, &self.settings.label_path).map(value_to_string);
+
+        let online = match get_path(&body, &self.settings.status_path) {
+            Some(status) => value_to_string(status) == self.settings.online_value,
+            None => self.settings.status_path.is_empty(),
+        };
+
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(height) = height {
+            if state.height > 0 && height > state.height {
+                state.last_hash_instant = Some(Instant::now());
+            }
+            state.height = height;
+        }
+
+        state.headers = headers.unwrap_or(state.height);
+        state.label = label;
+        state.status = match online {
+            false => NodeStatus::Offline,
+            true => NodeStatus::from_sync(state.height, state.headers, self.sync_tolerance),
+        };
+        state.health = SourceHealth::fresh();
+        state
+            .services
+            .insert("HTTP".to_string(), NodeStatus::Online);
+
+        Ok(())
+    }
+}
+
+/// Looks up a dot separated path like `result.blocks` or `backends.0.height`.
+pub fn get_path<'a>(body: &'a Value, path: &str) -> Option<&'a Value> {
+    if path.is_empty() {
+        return None;
+    }
+
+    body.pointer(&["/", &path.replace('.', "/")].join(""))
+}
+
+pub fn value_to_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(number) => number.as_u64(),
+        Value::String(string) => string.parse().ok(),
+        _ => None,
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        _ => value.to_string(),
+    }
+}
+
+#[async_trait]
+impl NodeProvider for CustomNode {
+    fn new(config: &AppConfig) -> Self {
+        let state = NodeState::new();
+
+        {
+            let mut locked_state = state.lock().unwrap();
+            locked_state.name = config.custom_node.name.clone();
+            locked_state
+                .services
+                .insert("HTTP".to_string(), NodeStatus::Offline);
+        }
+
+        let client = custom_node_client(config);
+
+        Self {
+            settings: config.custom_node.clone(),
+            sync_tolerance: config.node.sync_tolerance_blocks,
+            client,
+            state,
+        }
+    }
+
+    async fn init(
+        &mut self,
+        thread: AppThread,
+        mut commands: UnboundedReceiver<NodeCommand>,
+    ) -> Result<()> {
+        let check_interval = time::Duration::from_secs(self.settings.poll_interval);
+
+        loop {
+            if thread.token.is_cancelled() {
+                break;
+            }
+
+            tokio::select! {
+                () = thread.token.cancelled() => break,
+                _ = self.update() => {}
+            }
+
+            tokio::select! {
+                () = thread.token.cancelled() => break,
+                () = tokio::time::sleep(bandwidth::stretch(check_interval)) => {}
+                // There are no actions for a custom node, just drain them
+                Some(_) = commands.recv() => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_state(&self) -> Arc<Mutex<NodeState>> {
+        self.state.clone()
+    }
+}
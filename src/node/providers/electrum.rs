@@ -0,0 +1,263 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use bitcoincore_rpc::RpcApi;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::time::{self, Duration, Instant};
+use tokio_socks::tcp::Socks5Stream;
+
+use super::{bitcoin_core_client, host_port, is_onion};
+use crate::{
+    app::AppThread,
+    bandwidth::{self, Source},
+    config::AppConfig,
+    health::SourceHealth,
+    node::{NodeCommand, NodeProvider, NodeState, NodeStatus},
+    throughput::{self, Feed},
+};
+
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Longest wait for a request to be sent or answered, a stalled server is offline.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An index behind Core that did not move for this long is taken as stalled.
+const STALL_AFTER: Duration = Duration::from_secs(10 * 60);
+
+/// Electrum server (electrs, Fulcrum) index compared against its paired Bitcoin Core.
+#[derive(Clone)]
+pub struct Electrum {
+    host: String,
+    port: u16,
+    proxy: Option<String>,
+    rpc_client: Arc<bitcoincore_rpc::Client>,
+    sync_tolerance: u64,
+    /// Index height of the last answer and since when it is behind at that height.
+    progress: Option<(u64, Instant)>,
+    /// Blocks the index was behind Core on the last answer.
+    last_lag: u64,
+    state: Arc<Mutex<NodeState>>,
+}
+
+struct ElectrumInfo {
+    version: String,
+    height: u64,
+}
+
+impl Electrum {
+    pub async fn request(
+        reader: &mut BufReader<TcpStream>,
+        id: u64,
+        method: &str,
+        params: Value,
+    ) -> Result<Value> {
+        let request = format!(
+            "{}\n",
+            json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+        );
+
+        time::timeout(
+            REQUEST_TIMEOUT,
+            reader.get_mut().write_all(request.as_bytes()),
+        )
+        .await??;
+
+        let mut line = String::new();
+        time::timeout(REQUEST_TIMEOUT, reader.read_line(&mut line)).await??;
+
+        bandwidth::record(Source::Node, request.len() + line.len());
+
+        let mut response: Value = serde_json::from_str(&line)?;
+
+        if let Some(error) = response.get("error").filter(|error| !error.is_null()) {
+            return Err(anyhow::Error::msg(error.to_string()));
+        }
+
+        Ok(response["result"].take())
+    }
+
+    async fn get_electrum_info(&self) -> Result<ElectrumInfo> {
+        let stream = match &self.proxy {
+            // Onion services take a while to reach
+            Some(proxy) => time::timeout(
+                Duration::from_millis(30000),
+                Socks5Stream::connect(proxy.as_str(), (self.host.as_str(), self.port)),
+            )
+            .await??
+            .into_inner(),
+            None => {
+                time::timeout(
+                    Duration::from_millis(5000),
+                    TcpStream::connect(host_port(&self.host, &self.port.to_string())),
+                )
+                .await??
+            }
+        };
+        let mut reader = BufReader::new(stream);
+
+        let version =
+            Electrum::request(&mut reader, 0, "server.version", json!(["btcmon", "1.4"])).await?;
+        let tip =
+            Electrum::request(&mut reader, 1, "blockchain.headers.subscribe", json!([])).await?;
+
+        Ok(ElectrumInfo {
+            version: version[0].as_str().unwrap_or("Electrum").to_string(),
+            height: tip["height"]
+                .as_u64()
+                .ok_or(anyhow::Error::msg("Missing tip height"))?,
+        })
+    }
+
+    async fn update(&mut self) {
+        let electrum = self.get_electrum_info().await;
+        // Blocking RPC off the async workers, like the Bitcoin Core provider does
+        let rpc_client = self.rpc_client.clone();
+        let core_height = tokio::task::spawn_blocking(move || rpc_client.get_blockchain_info())
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|info| Ok(info?.blocks));
+
+        let mut state = self.state.lock().unwrap();
+
+        state.services.insert(
+            "RPC".to_string(),
+            match core_height {
+                Ok(_) => NodeStatus::Online,
+                Err(_) => NodeStatus::Offline,
+            },
+        );
+
+        let Ok(electrum) = electrum else {
+            state.health.failing = true;
+            state.failed_polls += 1;
+            state
+                .services
+                .insert("Electrum".to_string(), NodeStatus::Offline);
+
+            // electrs and Fulcrum only serve once caught up, and electrs compacts its
+            // database right after, so an index last seen behind is most likely busy
+            match core_height.is_ok() && self.last_lag > self.sync_tolerance {
+                true => {
+                    state.status = NodeStatus::Starting;
+                    state.starting = Some(format!(
+                        "Indexing or compacting, was {} blocks behind",
+                        self.last_lag
+                    ));
+                }
+                false => state.status = NodeStatus::Offline,
+            }
+            return;
+        };
+
+        throughput::record(Feed::NodePolls);
+
+        state
+            .services
+            .insert("Electrum".to_string(), NodeStatus::Online);
+
+        if state.height > 0 && electrum.height > state.height {
+            state.last_hash_instant = Some(Instant::now());
+        }
+
+        // Without Core there is nothing to compare against, trust the index
+        let core_height = core_height.unwrap_or(electrum.height);
+        let lag = core_height.saturating_sub(electrum.height);
+
+        // Counted from when it fell behind, an index in sync waits for blocks
+        let moved_at = match self.progress {
+            Some((height, at)) if height == electrum.height && self.last_lag > 0 => at,
+            _ => Instant::now(),
+        };
+        self.progress = Some((electrum.height, moved_at));
+        self.last_lag = lag;
+
+        state.height = electrum.height;
+        state.headers = core_height;
+        state.status = NodeStatus::from_sync(electrum.height, core_height, self.sync_tolerance);
+        state.starting = None;
+        state.health = SourceHealth::fresh();
+        state.label = Some(match lag {
+            0 => format!("{} · index in sync", electrum.version),
+            _ if moved_at.elapsed() >= STALL_AFTER => format!(
+                "{} · index stalled {} blocks behind for {} min",
+                electrum.version,
+                lag,
+                moved_at.elapsed().as_secs() / 60
+            ),
+            _ => format!("{} · index {} blocks behind", electrum.version, lag),
+        });
+    }
+}
+
+#[async_trait]
+impl NodeProvider for Electrum {
+    fn new(config: &AppConfig) -> Self {
+        let rpc = bitcoin_core_client(config);
+
+        let state = NodeState::new();
+
+        {
+            let mut locked_state = state.lock().unwrap();
+
+            locked_state.name = "Electrum".to_string();
+            locked_state
+                .services
+                .insert("Electrum".to_string(), NodeStatus::Offline);
+            locked_state
+                .services
+                .insert("RPC".to_string(), NodeStatus::Offline);
+        }
+
+        Self {
+            host: config
+                .electrum
+                .host
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_string(),
+            port: config.electrum.port.parse().unwrap_or(50001),
+            proxy: Some(config.proxy.clone())
+                .filter(|proxy| !proxy.is_empty() && is_onion(&config.electrum.host)),
+            rpc_client: Arc::new(rpc),
+            sync_tolerance: config.node.sync_tolerance_blocks,
+            progress: None,
+            last_lag: 0,
+            state,
+        }
+    }
+
+    async fn init(
+        &mut self,
+        thread: AppThread,
+        mut commands: UnboundedReceiver<NodeCommand>,
+    ) -> Result<()> {
+        let check_interval = CHECK_INTERVAL;
+
+        loop {
+            if thread.token.is_cancelled() {
+                break;
+            }
+
+            tokio::select! {
+                () = thread.token.cancelled() => break,
+                () = self.update() => {}
+            }
+
+            tokio::select! {
+                () = thread.token.cancelled() => break,
+                () = tokio::time::sleep(bandwidth::stretch(check_interval)) => {}
+                // There are no actions for an Electrum server, just drain them
+                Some(_) = commands.recv() => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_state(&self) -> Arc<Mutex<NodeState>> {
+        self.state.clone()
+    }
+}
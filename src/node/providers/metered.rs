@@ -0,0 +1,36 @@
+use jsonrpc::{Request, Response, Transport};
+use std::fmt;
+
+use crate::bandwidth::{self, Source, HTTP_OVERHEAD};
+
+/// Counts the JSON-RPC traffic going through another transport, sizes are those of the
+/// JSON bodies plus an estimate for the headers.
+pub struct MeteredTransport<T>(pub T);
+
+fn body_size(body: &impl serde::Serialize) -> usize {
+    serde_json::to_vec(body).map_or(0, |body| body.len())
+}
+
+impl<T: Transport> Transport for MeteredTransport<T> {
+    fn send_request(&self, request: Request) -> Result<Response, jsonrpc::Error> {
+        let sent = body_size(&request);
+        let response = self.0.send_request(request);
+        let received = response.as_ref().map_or(0, body_size);
+
+        bandwidth::record(Source::Node, sent + received + HTTP_OVERHEAD);
+        response
+    }
+
+    fn send_batch(&self, requests: &[Request]) -> Result<Vec<Response>, jsonrpc::Error> {
+        let sent = body_size(&requests);
+        let responses = self.0.send_batch(requests);
+        let received = responses.as_ref().map_or(0, body_size);
+
+        bandwidth::record(Source::Node, sent + received + HTTP_OVERHEAD);
+        responses
+    }
+
+    fn fmt_target(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_target(f)
+    }
+}
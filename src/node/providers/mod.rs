@@ -1 +1,122 @@
 pub mod bitcoin_core;
+pub mod custom;
+pub mod electrum;
+pub mod metered;
+#[cfg(unix)]
+pub mod shared;
+pub mod socks;
+
+use crate::config::AppConfig;
+use crate::node::NodeProvider;
+use bitcoin_core::BitcoinCore;
+use custom::CustomNode;
+use electrum::Electrum;
+use jsonrpc::simple_http::SimpleHttpTransport;
+use metered::MeteredTransport;
+use socks::SocksHttpTransport;
+use std::time::Duration;
+
+/// HTTP client for the custom node, through the proxy for `.onion` URLs.
+pub fn custom_node_client(config: &AppConfig) -> reqwest::Client {
+    http_client(config, &config.custom_node.url)
+}
+
+/// HTTP client for `url`, through the proxy when it is a `.onion` one.
+pub fn http_client(config: &AppConfig, url: &str) -> reqwest::Client {
+    let onion = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(is_onion))
+        .unwrap_or(false);
+
+    // A proxy that doesn't parse is warned about at startup
+    let proxy = config
+        .onion_proxy()
+        .filter(|_| onion && !config.proxy.is_empty());
+
+    proxy
+        .and_then(|proxy| reqwest::Client::builder().proxy(proxy).build().ok())
+        .unwrap_or_default()
+}
+
+/// Provider named by `node.provider`, Bitcoin Core unless another one is.
+pub fn from_config(config: &AppConfig) -> Box<dyn NodeProvider + Send> {
+    match config.node.provider.as_str() {
+        "electrum" => Box::new(Electrum::new(config)),
+        "custom" => Box::new(CustomNode::new(config)),
+        _ => Box::new(BitcoinCore::new(config)),
+    }
+}
+
+/// Time between polls of the `node.provider` in use.
+pub fn poll_interval(config: &AppConfig) -> Duration {
+    match config.node.provider.as_str() {
+        "electrum" => electrum::CHECK_INTERVAL,
+        "custom" => Duration::from_secs(config.custom_node.poll_interval),
+        _ if config.bitcoin_core.lightweight => bitcoin_core::LIGHTWEIGHT_CHECK_INTERVAL,
+        _ => bitcoin_core::CHECK_INTERVAL,
+    }
+}
+
+/// Address of the node being watched, as the provider reaches it.
+pub fn target(config: &AppConfig) -> String {
+    match config.node.provider.as_str() {
+        "electrum" => host_port(&config.electrum.host, &config.electrum.port),
+        "custom" => config.custom_node.url.clone(),
+        _ => host_port(&config.bitcoin_core.host, &config.bitcoin_core.rpc_port),
+    }
+}
+
+/// Like [`from_config`], shared with other instances watching the same node when
+/// `node.share` is on.
+pub fn shared_from_config(config: &AppConfig) -> Box<dyn NodeProvider + Send> {
+    #[cfg(unix)]
+    if config.node.share {
+        return Box::new(shared::SharedNode::new(config));
+    }
+
+    from_config(config)
+}
+
+/// `host:port`, with IPv6 literals bracketed whether or not the config did it.
+pub fn host_port(host: &str, port: &str) -> String {
+    match host.contains(':') && !host.starts_with('[') {
+        true => format!("[{}]:{}", host, port),
+        false => format!("{}:{}", host, port),
+    }
+}
+
+/// Tor hidden services have to be resolved by the proxy, never locally.
+pub fn is_onion(host: &str) -> bool {
+    host.trim_end_matches('.').ends_with(".onion")
+}
+
+/// RPC client for the configured Bitcoin Core, through the proxy for `.onion` hosts.
+pub fn bitcoin_core_client(config: &AppConfig) -> bitcoincore_rpc::Client {
+    let settings = &config.bitcoin_core;
+
+    if is_onion(&settings.host) && !config.proxy.is_empty() {
+        let url = format!("http://{}/", host_port(&settings.host, &settings.rpc_port));
+        let transport = SocksHttpTransport {
+            client: http_client(config, &url),
+            url,
+            auth: Some((settings.rpc_user.clone(), settings.rpc_password.clone())),
+        };
+
+        return bitcoincore_rpc::Client::from_jsonrpc(jsonrpc::Client::with_transport(
+            MeteredTransport(transport),
+        ));
+    }
+
+    let transport = SimpleHttpTransport::builder()
+        .url(&host_port(&settings.host, &settings.rpc_port))
+        .unwrap()
+        .auth(
+            settings.rpc_user.clone(),
+            Some(settings.rpc_password.clone()),
+        )
+        .build();
+
+    bitcoincore_rpc::Client::from_jsonrpc(jsonrpc::Client::with_transport(MeteredTransport(
+        transport,
+    )))
+}
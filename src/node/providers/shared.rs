@@ -0,0 +1,182 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::time::{sleep, Duration, Instant};
+
+use crate::app::AppThread;
+use crate::config::AppConfig;
+use crate::event::NodeEvent;
+use crate::node::{NodeCommand, NodeProvider, NodeState, NodeStatus};
+use crate::paths;
+
+use super::target;
+
+const PUBLISH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Answer to a control action asked of a follower.
+const FOLLOWER_REFUSAL: &str = "control actions run in the instance that owns the node socket";
+
+/// Several instances watching the same node share one poller. The first one binds a
+/// unix socket and publishes its state there, the others follow it and one of them
+/// takes over when it goes away.
+pub struct SharedNode {
+    inner: Box<dyn NodeProvider + Send>,
+    /// None without a private runtime directory, the node is polled alone then.
+    path: Option<PathBuf>,
+}
+
+impl SharedNode {
+    /// One socket per watched node, in the runtime directory.
+    fn socket_path(config: &AppConfig) -> Option<PathBuf> {
+        let name: String = format!("{}-{}", config.node.provider, target(config))
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+
+        paths::runtime_dir().map(|dir| dir.join(format!("{}.sock", name)))
+    }
+}
+
+/// Mirrors the published state until the publishing instance goes away.
+async fn follow(stream: UnixStream, state: Arc<Mutex<NodeState>>) {
+    let mut lines = BufReader::new(stream).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        let Ok(published) = serde_json::from_str::<NodeState>(&line) else {
+            continue;
+        };
+
+        let mut state = state.lock().unwrap();
+
+        // Instants do not cross processes, a new hash is a new block from here too
+        let last_hash_instant = match state.last_hash.is_empty() {
+            false if state.last_hash != published.last_hash => Some(Instant::now()),
+            _ => state.last_hash_instant,
+        };
+
+        *state = NodeState {
+            last_hash_instant,
+            ..published
+        };
+    }
+
+    state.lock().unwrap().status = NodeStatus::Offline;
+}
+
+/// Tells the app a control action asked of a follower was dropped.
+fn refuse_command(thread: &AppThread) {
+    let _ = thread
+        .sender
+        .send(NodeEvent::CommandRefused(FOLLOWER_REFUSAL.to_string()).into());
+}
+
+async fn publish(listener: UnixListener, state: Arc<Mutex<NodeState>>, thread: AppThread) {
+    while let Ok((mut stream, _)) = listener.accept().await {
+        let state = state.clone();
+        let token = thread.token.clone();
+
+        thread.tracker.spawn(async move {
+            while !token.is_cancelled() {
+                let Ok(line) = serde_json::to_string(&*state.lock().unwrap()) else {
+                    break;
+                };
+
+                if stream
+                    .write_all(format!("{}\n", line).as_bytes())
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+
+                sleep(PUBLISH_INTERVAL).await;
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl NodeProvider for SharedNode {
+    fn new(config: &AppConfig) -> Self {
+        Self {
+            inner: super::from_config(config),
+            path: Self::socket_path(config),
+        }
+    }
+
+    async fn init(
+        &mut self,
+        thread: AppThread,
+        mut commands: UnboundedReceiver<NodeCommand>,
+    ) -> Result<()> {
+        let Some(path) = self.path.clone() else {
+            return self.inner.init(thread, commands).await;
+        };
+
+        let mut followed = false;
+
+        loop {
+            if let Ok(stream) = UnixStream::connect(&path).await {
+                followed = true;
+                let following = follow(stream, self.inner.get_state());
+                tokio::pin!(following);
+
+                // The node belongs to the publishing instance, actions are not run here
+                loop {
+                    tokio::select! {
+                        () = &mut following => break,
+                        Some(_) = commands.recv() => refuse_command(&thread),
+                    }
+                }
+
+                // Spread the followers out so only one of them takes over
+                sleep(Duration::from_millis(std::process::id() as u64 % 500)).await;
+                continue;
+            }
+
+            // Held while publishing, another follower taking over has to wait for it
+            let Some(_lock) = paths::lock_socket(&path) else {
+                sleep(PUBLISH_INTERVAL).await;
+                continue;
+            };
+
+            // Someone may have taken over between the connect and the lock
+            if UnixStream::connect(&path).await.is_ok() {
+                continue;
+            }
+
+            // Nobody answers on the socket, it is left over from an instance that died
+            let _ = fs::remove_file(&path);
+
+            let Ok(listener) = UnixListener::bind(&path) else {
+                sleep(PUBLISH_INTERVAL).await;
+                continue;
+            };
+            paths::restrict_socket(&path);
+
+            // Asked for while following, they are stale by now
+            while followed && commands.try_recv().is_ok() {
+                refuse_command(&thread);
+            }
+
+            let publisher = publish(listener, self.inner.get_state(), thread.clone());
+
+            let result = tokio::select! {
+                result = self.inner.init(thread, commands) => result,
+                () = publisher => Ok(()),
+            };
+
+            let _ = fs::remove_file(&path);
+            return result;
+        }
+    }
+
+    fn get_state(&self) -> Arc<Mutex<NodeState>> {
+        self.inner.get_state()
+    }
+}
@@ -0,0 +1,68 @@
+use jsonrpc::{Request, Response};
+use reqwest::StatusCode;
+use std::fmt;
+use std::time::Duration;
+
+/// JSON-RPC over HTTP through the SOCKS5 proxy of `client`, which resolves the host
+/// so `.onion` addresses work.
+///
+/// The RPC client is synchronous, so requests block on the runtime of the calling
+/// thread, they have to be made from `spawn_blocking` like every other RPC call.
+pub struct SocksHttpTransport {
+    pub client: reqwest::Client,
+    pub url: String,
+    pub auth: Option<(String, String)>,
+}
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+impl SocksHttpTransport {
+    async fn post(&self, body: Vec<u8>) -> Result<Vec<u8>, reqwest::Error> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .timeout(TIMEOUT)
+            .body(body);
+
+        if let Some((user, password)) = &self.auth {
+            request = request.basic_auth(user, Some(password));
+        }
+
+        let response = request.send().await?;
+
+        // Core answers RPC errors with a 500 and a JSON body, auth failures have none
+        match response.status() {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                Err(response.error_for_status().unwrap_err())
+            }
+            _ => Ok(response.bytes().await?.to_vec()),
+        }
+    }
+
+    fn send<R: for<'a> serde::de::Deserialize<'a>>(
+        &self,
+        body: &impl serde::Serialize,
+    ) -> Result<R, jsonrpc::Error> {
+        let body = serde_json::to_vec(body)?;
+        let response = tokio::runtime::Handle::current()
+            .block_on(self.post(body))
+            .map_err(|e| jsonrpc::Error::Transport(Box::new(e)))?;
+
+        Ok(serde_json::from_slice(&response)?)
+    }
+}
+
+impl jsonrpc::Transport for SocksHttpTransport {
+    fn send_request(&self, request: Request) -> Result<Response, jsonrpc::Error> {
+        self.send(&request)
+    }
+
+    fn send_batch(&self, requests: &[Request]) -> Result<Vec<Response>, jsonrpc::Error> {
+        self.send(&requests)
+    }
+
+    fn fmt_target(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} via proxy", self.url)
+    }
+}
@@ -0,0 +1,193 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::Style,
+    widgets::{
+        Block, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Table, Widget,
+    },
+};
+
+/// Scroll position and selection of a [`VirtualList`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VirtualListState {
+    offset: usize,
+    selected: Option<usize>,
+}
+
+impl VirtualListState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn select(&mut self, index: Option<usize>) {
+        self.selected = index;
+    }
+
+    pub fn next(&mut self, len: usize) {
+        if len == 0 {
+            self.selected = None;
+            return;
+        }
+
+        self.selected = Some(match self.selected {
+            Some(i) => (i + 1).min(len - 1),
+            None => 0,
+        });
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = Some(self.selected.map_or(0, |i| i.saturating_sub(1)));
+    }
+
+    pub fn first(&mut self) {
+        self.selected = Some(0);
+    }
+
+    pub fn last(&mut self, len: usize) {
+        self.selected = len.checked_sub(1);
+    }
+
+    /// Keeps the selection inside the list and visible in the viewport.
+    fn fit(&mut self, len: usize, viewport: usize) {
+        if let Some(selected) = self.selected {
+            let selected = selected.min(len.saturating_sub(1));
+            self.selected = Some(selected);
+
+            if selected < self.offset {
+                self.offset = selected;
+            } else if viewport > 0 && selected >= self.offset + viewport {
+                self.offset = selected + 1 - viewport;
+            }
+        }
+
+        self.offset = self.offset.min(len.saturating_sub(viewport));
+    }
+}
+
+/// Table that only builds the rows that are currently visible.
+///
+/// Rows are produced on demand by index, so lists with hundreds of
+/// entries cost the same per frame as the ones that fit on screen.
+pub struct VirtualList<'a, F>
+where
+    F: Fn(usize) -> Row<'a>,
+{
+    len: usize,
+    row: F,
+    widths: Vec<Constraint>,
+    header: Option<Row<'a>>,
+    block: Option<Block<'a>>,
+    style: Style,
+    highlight_style: Style,
+}
+
+impl<'a, F> VirtualList<'a, F>
+where
+    F: Fn(usize) -> Row<'a>,
+{
+    pub fn new<C>(len: usize, widths: C, row: F) -> Self
+    where
+        C: IntoIterator,
+        C::Item: Into<Constraint>,
+    {
+        Self {
+            len,
+            row,
+            widths: widths.into_iter().map(Into::into).collect(),
+            header: None,
+            block: None,
+            style: Style::default(),
+            highlight_style: Style::default(),
+        }
+    }
+
+    pub fn header(mut self, header: Row<'a>) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn highlight_style(mut self, highlight_style: Style) -> Self {
+        self.highlight_style = highlight_style;
+        self
+    }
+}
+
+impl<'a, F> StatefulWidget for VirtualList<'a, F>
+where
+    F: Fn(usize) -> Row<'a>,
+{
+    type State = VirtualListState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let inner = match self.block {
+            Some(block) => {
+                let inner = block.inner(area);
+                block.render(area, buf);
+                inner
+            }
+            None => area,
+        };
+
+        if inner.is_empty() {
+            return;
+        }
+
+        let header_height = if self.header.is_some() { 1 } else { 0 };
+        let viewport = inner.height.saturating_sub(header_height) as usize;
+
+        state.fit(self.len, viewport);
+
+        let end = (state.offset + viewport).min(self.len);
+        let rows = (state.offset..end).map(|i| {
+            let row = (self.row)(i);
+            match state.selected {
+                Some(selected) if selected == i => row.style(self.highlight_style),
+                _ => row,
+            }
+        });
+
+        let scrollable = self.len > viewport;
+        let table_area = Rect {
+            width: inner.width - u16::from(scrollable),
+            ..inner
+        };
+
+        let mut table = Table::new(rows, self.widths).style(self.style);
+
+        if let Some(header) = self.header {
+            table = table.header(header);
+        }
+
+        Widget::render(table, table_area, buf);
+
+        if scrollable {
+            let mut scrollbar_state = ScrollbarState::new(self.len - viewport)
+                .viewport_content_length(viewport)
+                .position(state.offset);
+
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None)
+                .style(self.style)
+                .render(inner, buf, &mut scrollbar_state);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use anyhow::Result;
+use serde_json::json;
+use std::str::FromStr;
+use tokio::sync::mpsc;
+
+use crate::app::AppThread;
+use crate::badges::Badge;
+use crate::config::AppConfig;
+use crate::event::{Event, SystemEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PushService {
+    Ntfy,
+    Gotify,
+}
+
+impl FromStr for PushService {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<PushService> {
+        match input {
+            "ntfy" => Ok(PushService::Ntfy),
+            "gotify" => Ok(PushService::Gotify),
+            _ => Err(anyhow::Error::msg("Push service not supported")),
+        }
+    }
+}
+
+/// Push endpoint of a self-hosted ntfy or Gotify server, or ntfy.sh.
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    pub service: PushService,
+    pub url: String,
+    pub topic: String,
+    pub token: String,
+}
+
+impl Notifier {
+    /// `None` while no URL is configured.
+    pub fn from_config(config: &AppConfig) -> Option<Notifier> {
+        let settings = &config.notify;
+
+        if settings.url.is_empty() {
+            return None;
+        }
+
+        Some(Notifier {
+            service: PushService::from_str(&settings.service).ok()?,
+            url: settings.url.trim_end_matches('/').to_string(),
+            topic: settings.topic.clone(),
+            token: settings.token.clone(),
+        })
+    }
+
+    pub async fn send(&self, title: &str, message: &str) -> Result<()> {
+        let client = reqwest::Client::builder().build()?;
+
+        let request = match self.service {
+            PushService::Ntfy => {
+                let request = client
+                    .post(format!("{}/{}", self.url, self.topic))
+                    .header("Title", title)
+                    .body(message.to_string());
+
+                match self.token.is_empty() {
+                    true => request,
+                    false => request.bearer_auth(&self.token),
+                }
+            }
+            PushService::Gotify => client
+                .post(format!("{}/message", self.url))
+                .header("X-Gotify-Key", &self.token)
+                .json(&json!({ "title": title, "message": message, "priority": 5 })),
+        };
+
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Sends in the background, a push that fails is not worth interrupting anything for.
+pub fn spawn_notification(thread: AppThread, notifier: Notifier, title: String, message: String) {
+    thread.tracker.spawn(async move {
+        tokio::select! {
+            () = thread.token.cancelled() => {}
+            _ = notifier.send(&title, &message) => {}
+        }
+    });
+}
+
+/// Synthetic push for a badge, or a generic one, to check the plumbing end to end.
+pub fn test_message(badge: Option<&Badge>) -> (String, String) {
+    match badge {
+        Some(badge) => (
+            badge.label.clone(),
+            format!("Test notification, sent when {}", badge.when),
+        ),
+        None => (
+            "btcmon".to_string(),
+            "Test notification, pushes are working".to_string(),
+        ),
+    }
+}
+
+/// Like [`spawn_notification`], reporting back whether the push went through.
+pub fn spawn_test_notification(thread: AppThread, notifier: Notifier) {
+    thread.tracker.spawn(async move {
+        let (title, message) = test_message(None);
+
+        tokio::select! {
+            () = thread.token.cancelled() => {}
+            result = notifier.send(&title, &message) => {
+                let _ = thread.sender.send(SystemEvent::TestNotification(result.is_ok()).into());
+            }
+        }
+    });
+}
+
+/// Sends the pushes the app publishes on the bus until it shuts down, `events` being
+/// a subscription to the system topic.
+pub fn spawn_notifier(
+    thread: AppThread,
+    notifier: Notifier,
+    mut events: mpsc::UnboundedReceiver<Event>,
+) {
+    thread.tracker.clone().spawn(async move {
+        loop {
+            let event = tokio::select! {
+                () = thread.token.cancelled() => return,
+                event = events.recv() => event,
+            };
+
+            match event {
+                Some(Event::System(SystemEvent::Push(title, message))) => {
+                    spawn_notification(thread.clone(), notifier.clone(), title, message)
+                }
+                Some(Event::System(SystemEvent::TestPush)) => {
+                    spawn_test_notification(thread.clone(), notifier.clone())
+                }
+                Some(_) => {}
+                None => return,
+            }
+        }
+    });
+}
@@ -0,0 +1,152 @@
+// Where btcmon reads and writes, in one place so packages and sandboxes know what to allow:
+//
+// - config: read only, btcmon never writes there
+// - state: values kept across restarts, like the all time high and the notes
+// - cache: data that can be fetched again, like the last snapshot, safe to delete
+// - runtime: sockets, gone after a reboot
+//
+// Each one follows the directory systemd hands out with `ConfigurationDirectory=`,
+// `StateDirectory=`, `CacheDirectory=` and `RuntimeDirectory=`, so a unit can run with
+// `ProtectHome=` and nothing to write outside of them.
+
+use std::env;
+#[cfg(unix)]
+use std::fs::{self, File};
+#[cfg(unix)]
+use std::path::Path;
+use std::path::PathBuf;
+
+const APP_DIR: &str = "btcmon";
+
+/// First directory of a systemd directory variable, which may list several.
+fn systemd_dir(variable: &str) -> Option<PathBuf> {
+    env::var_os(variable)
+        .and_then(|value| env::split_paths(&value).next())
+        .filter(|dir| !dir.as_os_str().is_empty())
+}
+
+/// Config files looked up when none is given, later ones take precedence.
+pub fn config_files() -> Vec<PathBuf> {
+    let mut files = vec![];
+
+    #[cfg(unix)]
+    files.push(PathBuf::from("/etc/btcmon/btcmon.toml"));
+
+    if let Some(config_dir) = dirs::config_dir() {
+        files.push(config_dir.join(APP_DIR).join("btcmon.toml"));
+    }
+
+    if let Some(home_dir) = dirs::home_dir() {
+        files.push(home_dir.join(".btcmon").join("btcmon.toml"));
+    }
+
+    if let Some(file) = systemd_dir("CONFIGURATION_DIRECTORY").map(|dir| dir.join("btcmon.toml")) {
+        // Usually /etc/btcmon, already read first
+        files.retain(|other| *other != file);
+        files.push(file);
+    }
+
+    files
+}
+
+/// `$XDG_STATE_HOME/btcmon`, the local data directory where there is no such thing
+/// (macOS, Windows).
+pub fn state_dir() -> Option<PathBuf> {
+    systemd_dir("STATE_DIRECTORY").or_else(|| {
+        dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|dir| dir.join(APP_DIR))
+    })
+}
+
+/// `$XDG_CACHE_HOME/btcmon`.
+pub fn cache_dir() -> Option<PathBuf> {
+    systemd_dir("CACHE_DIRECTORY").or_else(|| dirs::cache_dir().map(|dir| dir.join(APP_DIR)))
+}
+
+/// `$XDG_RUNTIME_DIR/btcmon`, or a private `btcmon-<uid>` in the temporary directory
+/// where there is none. `None` when that one can't be trusted.
+pub fn runtime_dir() -> Option<PathBuf> {
+    systemd_dir("RUNTIME_DIRECTORY")
+        .or_else(|| dirs::runtime_dir().map(|dir| dir.join(APP_DIR)))
+        .or_else(private_temp_dir)
+}
+
+/// The temporary directory is shared with every user, who could plant sockets there to
+/// feed a spoofed node state or watch the dashboard. Only a directory of our own that
+/// nobody else can enter is used.
+#[cfg(unix)]
+fn private_temp_dir() -> Option<PathBuf> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    // SAFETY: getuid has no preconditions and can't fail
+    let uid = unsafe { libc::getuid() };
+    let dir = env::temp_dir().join(format!("{}-{}", APP_DIR, uid));
+    let _ = fs::DirBuilder::new().mode(0o700).create(&dir);
+
+    // Not following links, someone else may have made it one
+    let metadata = fs::symlink_metadata(&dir).ok()?;
+    let private = metadata.is_dir() && metadata.uid() == uid && metadata.mode() & 0o077 == 0;
+
+    private.then_some(dir)
+}
+
+/// Windows gives every user a temporary directory of their own.
+#[cfg(not(unix))]
+fn private_temp_dir() -> Option<PathBuf> {
+    Some(env::temp_dir().join(APP_DIR))
+}
+
+/// Takes the lock file next to `socket`, held until the file is dropped. Whoever holds it
+/// owns the socket, so only one instance at a time removes a stale one and binds it.
+#[cfg(unix)]
+pub fn lock_socket(socket: &Path) -> Option<File> {
+    use std::os::unix::io::AsRawFd;
+
+    let mut name = socket.file_name()?.to_os_string();
+    name.push(".lock");
+
+    if let Some(dir) = socket.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+
+    let file = File::create(socket.with_file_name(name)).ok()?;
+
+    // SAFETY: the descriptor is open for as long as `file` lives
+    let locked = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0;
+
+    locked.then_some(file)
+}
+
+/// Lets only the owner connect to `socket`.
+#[cfg(unix)]
+pub fn restrict_socket(socket: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let _ = fs::set_permissions(socket, fs::Permissions::from_mode(0o600));
+}
+
+/// The layout in use, for `btcmon paths`.
+pub fn describe() -> Vec<(&'static str, String)> {
+    let display = |dir: Option<PathBuf>| match dir {
+        Some(dir) => dir.display().to_string(),
+        None => "none, nothing is kept".to_string(),
+    };
+
+    let config_files: Vec<String> = config_files()
+        .iter()
+        .map(|file| file.display().to_string())
+        .collect();
+
+    vec![
+        ("config", config_files.join(", ")),
+        ("state", display(state_dir())),
+        ("cache", display(cache_dir())),
+        (
+            "runtime",
+            runtime_dir().map_or("none, nothing is shared".to_string(), |dir| {
+                dir.display().to_string()
+            }),
+        ),
+    ]
+}
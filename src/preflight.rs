@@ -0,0 +1,297 @@
+use anyhow::Result;
+use bitcoincore_rpc::RpcApi;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fmt;
+use tokio::io::BufReader;
+use tokio::net::{lookup_host, TcpStream};
+use tokio::time::{timeout, Duration};
+use tokio_socks::tcp::Socks5Stream;
+
+use crate::app::AppThread;
+use crate::config::AppConfig;
+use crate::event::NodeEvent;
+use crate::node::providers::custom::{get_path, value_to_u64};
+use crate::node::providers::electrum::Electrum;
+use crate::node::providers::{bitcoin_core_client, custom_node_client, host_port, is_onion};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Onion services take a while to reach.
+const PROXY_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Outcome of one step of a connection test, with what was found or what went wrong.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Check {
+    pub name: String,
+    pub result: Result<String, String>,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.result {
+            Ok(detail) => write!(f, "ok    {:<16}{}", self.name, detail),
+            Err(error) => write!(f, "FAIL  {:<16}{}", self.name, error),
+        }
+    }
+}
+
+/// Steps run in order, a failing one ends its chain since the next ones depend on it.
+struct Checks(Vec<Check>);
+
+impl Checks {
+    /// Records the step and tells whether it passed.
+    fn push(&mut self, name: &str, result: Result<String>) -> bool {
+        let passed = result.is_ok();
+
+        self.0.push(Check {
+            name: name.to_string(),
+            result: result.map_err(|e| e.to_string()),
+        });
+
+        passed
+    }
+}
+
+/// Node names accepted by `btcmon test-node`, the configured provider when none is given.
+pub const NODES: [&str; 3] = ["bitcoin_core", "electrum", "custom"];
+
+/// Runs the checks for one node: name resolution, TCP, then the protocol and its auth.
+pub async fn test_node(config: &AppConfig, node: &str) -> Vec<Check> {
+    let mut checks = Checks(vec![]);
+
+    match node {
+        "electrum" => {
+            let host = config.electrum.host.trim_matches(['[', ']']);
+            let port = config.electrum.port.parse().unwrap_or(50001);
+
+            if let Some(stream) = connect(config, &mut checks, host, port).await {
+                let mut reader = BufReader::new(stream);
+                let version =
+                    Electrum::request(&mut reader, 0, "server.version", json!(["btcmon", "1.4"]))
+                        .await
+                        .map(|version| version[0].as_str().unwrap_or("unknown").to_string());
+
+                if checks.push("Electrum", version) {
+                    let tip = Electrum::request(
+                        &mut reader,
+                        1,
+                        "blockchain.headers.subscribe",
+                        json!([]),
+                    )
+                    .await
+                    .map(|tip| format!("tip at height {}", tip["height"]));
+                    checks.push("Headers", tip);
+                }
+            }
+
+            // The index is compared against Core, so its RPC is part of the setup
+            test_bitcoin_core_rpc(config, &mut checks).await;
+        }
+        "custom" => test_custom_node(config, &mut checks).await,
+        _ => {
+            let host = config.bitcoin_core.host.trim_matches(['[', ']']);
+            let port = config.bitcoin_core.rpc_port.parse().unwrap_or(8332);
+
+            if connect(config, &mut checks, host, port).await.is_some() {
+                test_bitcoin_core_rpc(config, &mut checks).await;
+            }
+        }
+    }
+
+    checks.0
+}
+
+/// Resolves the host and opens a TCP connection, or leaves both to the proxy for onions.
+async fn connect(
+    config: &AppConfig,
+    checks: &mut Checks,
+    host: &str,
+    port: u16,
+) -> Option<TcpStream> {
+    if is_onion(host) {
+        if config.proxy.is_empty() {
+            checks.push(
+                "DNS",
+                Err(anyhow::Error::msg("onion hosts need a proxy, e.g. Tor")),
+            );
+            return None;
+        }
+
+        checks.push("DNS", Ok(format!("resolved by the proxy {}", config.proxy)));
+
+        let stream = timeout(
+            PROXY_CONNECT_TIMEOUT,
+            Socks5Stream::connect(config.proxy.as_str(), (host, port)),
+        )
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|stream| Ok(stream?.into_inner()));
+
+        return match stream {
+            Ok(stream) => {
+                checks.push("TCP", Ok(format!("connected through {}", config.proxy)));
+                Some(stream)
+            }
+            Err(e) => {
+                checks.push("TCP", Err(e));
+                None
+            }
+        };
+    }
+
+    let address = host_port(host, &port.to_string());
+    let resolved = lookup_host(&address)
+        .await
+        .map(|addresses| addresses.collect::<Vec<_>>());
+
+    let addresses = match resolved {
+        Ok(addresses) if !addresses.is_empty() => addresses,
+        Ok(_) => {
+            checks.push("DNS", Err(anyhow::Error::msg("no addresses")));
+            return None;
+        }
+        Err(e) => {
+            checks.push("DNS", Err(e.into()));
+            return None;
+        }
+    };
+
+    let listed: Vec<String> = addresses.iter().map(|a| a.ip().to_string()).collect();
+    checks.push("DNS", Ok(format!("{} -> {}", host, listed.join(", "))));
+
+    match timeout(CONNECT_TIMEOUT, TcpStream::connect(&addresses[..])).await {
+        Ok(Ok(stream)) => {
+            let peer = stream
+                .peer_addr()
+                .map(|peer| peer.to_string())
+                .unwrap_or(address);
+            checks.push("TCP", Ok(format!("connected to {}", peer)));
+            Some(stream)
+        }
+        Ok(Err(e)) => {
+            checks.push("TCP", Err(e.into()));
+            None
+        }
+        Err(_) => {
+            checks.push("TCP", Err(anyhow::Error::msg("timed out")));
+            None
+        }
+    }
+}
+
+async fn test_bitcoin_core_rpc(config: &AppConfig, checks: &mut Checks) {
+    let rpc_client = bitcoin_core_client(config);
+    let result = tokio::task::spawn_blocking(move || {
+        let uptime = rpc_client.uptime();
+        let info = rpc_client.get_blockchain_info();
+        (uptime, info)
+    })
+    .await;
+
+    let Ok((uptime, info)) = result else {
+        checks.push("RPC auth", Err(anyhow::Error::msg("RPC call panicked")));
+        return;
+    };
+
+    let auth = match uptime {
+        Ok(uptime) => Ok(format!("logged in, node up for {}h", uptime / 3600)),
+        Err(e) if e.to_string().contains("401") => Err(anyhow::Error::msg(
+            "rejected (401), check rpc_user and rpc_password",
+        )),
+        Err(e) => Err(e.into()),
+    };
+
+    if checks.push("RPC auth", auth) {
+        checks.push(
+            "RPC call",
+            info.map(|info| {
+                format!(
+                    "getblockchaininfo: {} at height {}",
+                    info.chain, info.blocks
+                )
+            })
+            .map_err(anyhow::Error::from),
+        );
+    }
+}
+
+async fn test_custom_node(config: &AppConfig, checks: &mut Checks) {
+    let settings = &config.custom_node;
+
+    let url = match reqwest::Url::parse(&settings.url) {
+        Ok(url) => url,
+        Err(e) => {
+            checks.push("URL", Err(e.into()));
+            return;
+        }
+    };
+
+    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        checks.push("URL", Err(anyhow::Error::msg("missing host")));
+        return;
+    };
+
+    if connect(config, checks, host, port).await.is_none() {
+        return;
+    }
+
+    let mut request = custom_node_client(config).get(url.clone());
+    for (name, value) in settings.headers.iter() {
+        request = request.header(name, value);
+    }
+
+    // Certificates are checked as part of the request for https URLs
+    let name = match url.scheme() {
+        "https" => "HTTPS",
+        _ => "HTTP",
+    };
+
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            checks.push(name, Err(e.into()));
+            return;
+        }
+    };
+
+    let status = response.status();
+    if !checks.push(
+        name,
+        match status.is_success() {
+            true => Ok(status.to_string()),
+            false => Err(anyhow::Error::msg(format!(
+                "{}, check the headers for auth",
+                status
+            ))),
+        },
+    ) {
+        return;
+    }
+
+    let body = response.json::<serde_json::Value>().await;
+    let height = body
+        .map_err(anyhow::Error::from)
+        .and_then(|body| {
+            get_path(&body, &settings.height_path)
+                .and_then(value_to_u64)
+                .ok_or(anyhow::Error::msg(format!(
+                    "no height at height_path {:?}",
+                    settings.height_path
+                )))
+        })
+        .map(|height| format!("height {}", height));
+
+    checks.push("Response", height);
+}
+
+/// Tests the configured node in the background and sends the results to the interface.
+pub fn spawn_test_node(thread: AppThread, config: AppConfig) {
+    thread.tracker.spawn(async move {
+        tokio::select! {
+            () = thread.token.cancelled() => {}
+            checks = test_node(&config, &config.node.provider) => {
+                let _ = thread.sender.send(NodeEvent::Preflight(checks).into());
+            }
+        }
+    });
+}
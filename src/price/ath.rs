@@ -0,0 +1,67 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::{
+    app::AppThread,
+    bandwidth::{self, Source, HTTP_OVERHEAD},
+    event::MarketEvent,
+};
+
+use super::PriceCurrency;
+
+/// `price.ath_source` values the all time high can be seeded from.
+pub const ATH_SOURCES: [&str; 1] = ["coingecko"];
+
+#[derive(Debug, Deserialize)]
+struct CoinGeckoCoin {
+    market_data: CoinGeckoMarketData,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinGeckoMarketData {
+    /// Keyed by lowercase currency code.
+    ath: HashMap<String, f64>,
+}
+
+/// All time highs of bitcoin as CoinGecko tracks them across exchanges, keyed by
+/// lowercase currency code.
+pub async fn fetch_coingecko_ath() -> Result<HashMap<String, f64>> {
+    let bytes = reqwest::Client::new()
+        .get("https://api.coingecko.com/api/v3/coins/bitcoin?localization=false&tickers=false&community_data=false&developer_data=false")
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    bandwidth::record(Source::Price, bytes.len() + HTTP_OVERHEAD);
+
+    Ok(serde_json::from_slice::<CoinGeckoCoin>(&bytes)?
+        .market_data
+        .ath)
+}
+
+/// Asks for the all time highs once, at startup, and seeds every currency from the same
+/// answer. The app keeps one only when it is above the one it already has, failures and
+/// currencies CoinGecko doesn't track leave that one alone.
+pub fn spawn_ath_fetch(thread: AppThread, currencies: Vec<PriceCurrency>) {
+    thread.tracker.spawn(async move {
+        let highs = tokio::select! {
+            () = thread.token.cancelled() => return,
+            highs = fetch_coingecko_ath() => highs,
+        };
+
+        let Ok(highs) = highs else {
+            return;
+        };
+
+        for currency in currencies {
+            if let Some(ath) = highs.get(&currency.code().to_lowercase()) {
+                let _ = thread
+                    .sender
+                    .send(MarketEvent::AthSeed(currency, *ath).into());
+            }
+        }
+    });
+}
@@ -1,62 +1,211 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::fmt;
 use std::str::FromStr;
 use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant};
 use tokio_util::sync::CancellationToken;
 
-use crate::{app::AppThread, event::Event};
+use crate::{
+    app::AppThread,
+    bandwidth,
+    config::AppConfig,
+    event::{Event, MarketEvent},
+    health::SourceHealth,
+    throughput::{self, Feed},
+};
 
+pub mod ath;
 pub mod providers;
+pub mod stream;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum PriceCurrency {
-    USD,
-    EUR,
+pub const CHECK_INTERVAL: Duration = Duration::from_millis(30 * 1000);
+
+/// Consecutive failures after which the next provider of `price.providers` takes over.
+const FAILOVER_AFTER: u32 = 3;
+
+/// ISO 4217 code of the currency the price is shown in, e.g. `USD` or `JPY`. Any code
+/// is accepted, whether there is a price in it is up to the provider.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PriceCurrency([u8; 3]);
+
+impl PriceCurrency {
+    pub const USD: PriceCurrency = PriceCurrency(*b"USD");
+    pub const EUR: PriceCurrency = PriceCurrency(*b"EUR");
+    pub const GBP: PriceCurrency = PriceCurrency(*b"GBP");
 }
 
 impl FromStr for PriceCurrency {
     type Err = anyhow::Error;
     fn from_str(input: &str) -> Result<PriceCurrency> {
-        match input {
-            "USD" => Ok(PriceCurrency::USD),
-            "EUR" => Ok(PriceCurrency::EUR),
-            _ => Err(anyhow::Error::msg("Currency not allowed")),
+        match <[u8; 3]>::try_from(input.trim().to_ascii_uppercase().as_bytes()) {
+            Ok(code) if code.iter().all(u8::is_ascii_uppercase) => Ok(PriceCurrency(code)),
+            _ => Err(anyhow::Error::msg("Currency is not a three letter code")),
+        }
+    }
+}
+
+impl TryFrom<String> for PriceCurrency {
+    type Error = anyhow::Error;
+    fn try_from(code: String) -> Result<PriceCurrency> {
+        PriceCurrency::from_str(&code)
+    }
+}
+
+impl From<PriceCurrency> for String {
+    fn from(currency: PriceCurrency) -> String {
+        currency.code().to_string()
+    }
+}
+
+impl PriceCurrency {
+    /// Three uppercase ASCII letters, checked when parsed.
+    pub fn code(&self) -> &str {
+        std::str::from_utf8(&self.0).unwrap_or_default()
+    }
+
+    /// Usual sign of the currency, the code itself for those without a well known one.
+    pub fn symbol(&self) -> &str {
+        match self.code() {
+            "USD" | "ARS" | "COP" | "CUP" | "MXN" => "$",
+            "EUR" => "€",
+            "GBP" => "£",
+            "JPY" => "¥",
+            "AUD" => "A$",
+            "CAD" => "C$",
+            "BRL" => "R$",
+            "NGN" => "₦",
+            "VES" => "Bs.",
+            code => code,
         }
     }
+
+    /// Whether the provider named by `price.provider` has a price in the currency.
+    pub fn listed_by(&self, provider: &str) -> bool {
+        let listed: &[&str] = match provider {
+            // Street rates of most currencies, the others only list the major ones
+            "yadio" => return true,
+            "coinbase" => &["USD", "EUR", "GBP"],
+            "binance" => &["USD", "EUR", "JPY", "BRL"],
+            _ => &["USD", "EUR", "GBP", "CAD", "CHF", "AUD", "JPY"],
+        };
+
+        listed.contains(&self.code())
+    }
 }
 
 impl fmt::Display for PriceCurrency {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        write!(f, "{}", self.code())
+    }
+}
+
+impl fmt::Debug for PriceCurrency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
     }
 }
 
 #[derive(Debug)]
 pub struct PriceResult {
     pub price_in_currency: String,
+    /// BTC traded in the last 24 hours, for exchanges that tell.
+    pub volume_24h: Option<f64>,
 }
 
 #[async_trait]
 pub trait PriceProvider {
-    fn new() -> Self;
+    fn new(config: &AppConfig) -> Self
+    where
+        Self: Sized;
     async fn fetch_current_price(
         &mut self,
         currency: &PriceCurrency,
     ) -> Result<PriceResult, Box<dyn std::error::Error>>;
 }
 
+/// A provider with the name it is configured by.
+pub type NamedProvider = (String, Box<dyn PriceProvider + Send>);
+
+/// Level the price is watched for, dropped once crossed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PriceAlert {
+    Above(f64),
+    Below(f64),
+}
+
+impl PriceAlert {
+    pub fn level(&self) -> f64 {
+        match self {
+            PriceAlert::Above(level) | PriceAlert::Below(level) => *level,
+        }
+    }
+
+    /// Whether the price went past the level since `previous`. Nothing is crossed before
+    /// there is a previous price, a level already passed at startup is not news.
+    pub fn crossed(&self, previous: Option<f64>, price: f64) -> bool {
+        let Some(previous) = previous else {
+            return false;
+        };
+
+        match self {
+            PriceAlert::Above(level) => previous < *level && price >= *level,
+            PriceAlert::Below(level) => previous > *level && price <= *level,
+        }
+    }
+}
+
+/// Requests from the app to the price checker.
 #[derive(Debug, Clone, Copy)]
+pub enum PriceCommand {
+    /// Poll the next configured provider from now on.
+    NextProvider,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceState {
+    /// Name of the provider the price came from.
+    #[serde(default)]
+    pub provider: String,
     pub currency: PriceCurrency,
     pub last_price_in_currency: Option<f64>,
+    /// BTC traded in the last 24 hours, summed over the providers when averaging.
+    #[serde(default)]
+    pub volume_24h: Option<f64>,
+    /// Highest price seen, persisted across restarts.
+    pub ath: Option<f64>,
+    #[serde(skip)]
+    pub ath_reached_at: Option<Instant>,
+    #[serde(skip, default = "SourceHealth::fresh")]
+    pub health: SourceHealth,
+    /// Previous prices, oldest first, kept in memory by the app.
+    #[serde(skip)]
+    pub history: VecDeque<f64>,
+    /// Unix time of each price in the history.
+    #[serde(skip)]
+    pub history_times: VecDeque<i64>,
+    /// When the last price went into the history, streamed ones come faster than polls.
+    #[serde(skip)]
+    pub sampled_at: Option<Instant>,
 }
 
 impl Default for PriceState {
     fn default() -> Self {
         Self {
+            provider: String::new(),
             currency: PriceCurrency::USD,
             last_price_in_currency: None,
+            volume_24h: None,
+            ath: None,
+            ath_reached_at: None,
+            health: SourceHealth::default(),
+            history: VecDeque::new(),
+            history_times: VecDeque::new(),
+            sampled_at: None,
         }
     }
 }
@@ -65,6 +214,23 @@ impl PriceState {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Average of the last `samples` prices, none until the history is that long.
+    pub fn moving_average(&self, samples: usize) -> Option<f64> {
+        if samples == 0 || self.history.len() < samples {
+            return None;
+        }
+
+        Some(self.history.iter().rev().take(samples).sum::<f64>() / samples as f64)
+    }
+
+    /// Relative distance to the all time high, `-0.123` is 12.3% below it.
+    pub fn ath_distance(&self) -> Option<f64> {
+        match (self.last_price_in_currency, self.ath) {
+            (Some(price), Some(ath)) if ath > 0.0 => Some(price / ath - 1.0),
+            _ => None,
+        }
+    }
 }
 
 pub struct Price<TProvider: PriceProvider> {
@@ -73,61 +239,289 @@ pub struct Price<TProvider: PriceProvider> {
 }
 
 impl<TProvider: PriceProvider> Price<TProvider> {
-    pub fn new() -> Self {
-        Self::default()
-    }
-}
-
-impl<TProvider: PriceProvider> Default for Price<TProvider> {
-    fn default() -> Self {
+    pub fn new(config: &AppConfig) -> Self {
         Self {
-            provider: TProvider::new(),
+            provider: TProvider::new(config),
             last_price_in_currency: None,
         }
     }
 }
 
-pub fn spawn_price_checker<T: PriceProvider>(thread: AppThread, currency: PriceCurrency)
-where
-    T: Send,
-{
+/// Polls one provider at a time, moving on to the next one when the app asks to or once
+/// the active one failed [`FAILOVER_AFTER`] times in a row. With `vwap` every provider
+/// is polled each time and their prices are averaged by volume instead. With a
+/// `stream_url` the price is streamed from there, and only polled while the socket is
+/// down.
+pub fn spawn_price_checker(
+    thread: AppThread,
+    currency: PriceCurrency,
+    providers: Vec<NamedProvider>,
+    commands: mpsc::UnboundedReceiver<PriceCommand>,
+    vwap: bool,
+    stream_url: Option<String>,
+) {
     thread.tracker.spawn(async move {
         tokio::select! {
             () = thread.token.cancelled() => {}
-            () = price_checker::<T>(currency, thread.sender, thread.token.clone()) => {}
+            () = price_source(currency, thread.sender, thread.token.clone(), providers, commands, vwap, stream_url) => {}
         }
     });
 }
 
-async fn price_checker<T: PriceProvider>(
+async fn price_source(
     currency: PriceCurrency,
     sender: mpsc::UnboundedSender<Event>,
     token: CancellationToken,
+    mut providers: Vec<NamedProvider>,
+    mut commands: mpsc::UnboundedReceiver<PriceCommand>,
+    vwap: bool,
+    stream_url: Option<String>,
+) {
+    let Some(url) = stream_url else {
+        price_checker(
+            currency,
+            &sender,
+            &token,
+            &mut providers,
+            &mut commands,
+            vwap,
+        )
+        .await;
+        return;
+    };
+
+    while !token.is_cancelled() {
+        stream::coinbase_ticker(&url, currency, &sender).await;
+        let _ = sender
+            .send(MarketEvent::PriceError(currency, stream::STREAM_PROVIDER.to_string()).into());
+
+        // Switches asked for while streaming are stale by now
+        while commands.try_recv().is_ok() {}
+
+        let polling = price_checker(
+            currency,
+            &sender,
+            &token,
+            &mut providers,
+            &mut commands,
+            vwap,
+        );
+        let _ = tokio::time::timeout(stream::RETRY_AFTER, polling).await;
+    }
+}
+
+async fn price_checker(
+    currency: PriceCurrency,
+    sender: &mpsc::UnboundedSender<Event>,
+    token: &CancellationToken,
+    providers: &mut [NamedProvider],
+    commands: &mut mpsc::UnboundedReceiver<PriceCommand>,
+    vwap: bool,
 ) {
-    let mut provider = T::new();
-    let interval = tokio::time::Duration::from_millis(30 * 1000);
+    let interval = CHECK_INTERVAL;
+    let mut active = 0;
+    let mut failures = 0;
 
-    loop {
+    while !providers.is_empty() {
         if token.is_cancelled() {
             break;
         }
-        tokio::select! {
-            () = token.cancelled() => {}
-            res = provider.fetch_current_price(&currency) => {
-                let _ = match res {
-                    Ok(res) => sender.send(Event::PriceUpdate(PriceState {
-                        currency,
-                        last_price_in_currency: Some(res.price_in_currency.parse::<f64>().unwrap()),
-                    })),
-                    Err(_) => Ok(()),
-                };
 
+        let (name, res) = match vwap {
+            true => tokio::select! {
+                () = token.cancelled() => break,
+                res = fetch_vwap(providers, &currency) => res,
+            },
+            false => {
+                let (name, provider) = &mut providers[active];
+
+                tokio::select! {
+                    () = token.cancelled() => break,
+                    res = provider.fetch_current_price(&currency) => (name.clone(), res.ok()),
+                }
+            }
+        };
+
+        let price = res
+            .as_ref()
+            .and_then(|res| res.price_in_currency.parse::<f64>().ok());
+
+        let _ = match price {
+            Some(price) => {
+                failures = 0;
+                throughput::record(Feed::Price);
+                sender.send(
+                    MarketEvent::PriceUpdate(PriceState {
+                        provider: name,
+                        currency,
+                        last_price_in_currency: Some(price),
+                        volume_24h: res.and_then(|res| res.volume_24h),
+                        health: SourceHealth::fresh(),
+                        ..PriceState::default()
+                    })
+                    .into(),
+                )
+            }
+            None => {
+                failures += 1;
+                sender.send(MarketEvent::PriceError(currency, name).into())
             }
+        };
+
+        // The next provider is asked right away rather than after another interval
+        if failures >= FAILOVER_AFTER && providers.len() > 1 && !vwap {
+            active = (active + 1) % providers.len();
+            failures = 0;
+            continue;
         }
 
+        // A switch is polled right away rather than after the interval
         tokio::select! {
             () = token.cancelled() => {}
-            () = tokio::time::sleep(interval) => {}
+            () = tokio::time::sleep(bandwidth::stretch(interval)) => {}
+            Some(PriceCommand::NextProvider) = commands.recv() => {
+                active = (active + 1) % providers.len();
+                failures = 0;
+            }
+        }
+    }
+}
+
+/// Average of `quotes`, prices with their 24h volume, weighted by the volume, and the
+/// total volume. Quotes without volume, or with none traded, only count when none of
+/// them has any.
+fn volume_weighted(quotes: &[(f64, Option<f64>)]) -> Option<(f64, Option<f64>)> {
+    let weighted: Vec<(f64, f64)> = quotes
+        .iter()
+        .filter_map(|(price, volume)| Some((*price, volume.filter(|volume| *volume > 0.0)?)))
+        .collect();
+    let volume: f64 = weighted.iter().map(|(_, volume)| volume).sum();
+
+    let price = match (weighted.is_empty(), quotes.is_empty()) {
+        (false, _) => {
+            weighted
+                .iter()
+                .map(|(price, volume)| price * volume)
+                .sum::<f64>()
+                / volume
+        }
+        (true, false) => quotes.iter().map(|(price, _)| price).sum::<f64>() / quotes.len() as f64,
+        (true, true) => return None,
+    };
+
+    Some((price, (volume > 0.0).then_some(volume)))
+}
+
+/// Volume weighted average of the prices of every provider that answered, named after
+/// how many of them did, e.g. `vwap 2/3`.
+async fn fetch_vwap(
+    providers: &mut [NamedProvider],
+    currency: &PriceCurrency,
+) -> (String, Option<PriceResult>) {
+    let total = providers.len();
+    // Errors are dropped as they come, they can't be held over the other requests
+    let quotes: Vec<(f64, Option<f64>)> = join_all(
+        providers
+            .iter_mut()
+            .map(|(_, provider)| async { provider.fetch_current_price(currency).await.ok() }),
+    )
+    .await
+    .into_iter()
+    .filter_map(|res| {
+        let res = res?;
+        let price = res.price_in_currency.parse::<f64>().ok()?;
+        Some((price, res.volume_24h))
+    })
+    .collect();
+
+    let name = format!("vwap {}/{}", quotes.len(), total);
+    let result = volume_weighted(&quotes).map(|(price, volume)| PriceResult {
+        price_in_currency: price.to_string(),
+        volume_24h: volume,
+    });
+
+    (name, result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers with a fixed price and volume, or fails without one.
+    struct Quote(Option<(f64, Option<f64>)>);
+
+    #[async_trait]
+    impl PriceProvider for Quote {
+        fn new(_config: &AppConfig) -> Self {
+            Quote(None)
         }
+
+        async fn fetch_current_price(
+            &mut self,
+            _currency: &PriceCurrency,
+        ) -> Result<PriceResult, Box<dyn std::error::Error>> {
+            let (price, volume) = self.0.ok_or("down")?;
+
+            Ok(PriceResult {
+                price_in_currency: price.to_string(),
+                volume_24h: volume,
+            })
+        }
+    }
+
+    #[test]
+    fn weights_prices_by_volume() {
+        let (price, volume) = volume_weighted(&[(100.0, Some(1.0)), (200.0, Some(3.0))]).unwrap();
+
+        assert_eq!(price, 175.0);
+        assert_eq!(volume, Some(4.0));
+    }
+
+    #[test]
+    fn quotes_without_volume_are_left_out_of_the_weights() {
+        let (price, volume) =
+            volume_weighted(&[(100.0, Some(2.0)), (1000.0, None), (500.0, Some(0.0))]).unwrap();
+
+        assert_eq!(price, 100.0);
+        assert_eq!(volume, Some(2.0));
+    }
+
+    #[test]
+    fn no_volume_at_all_is_a_plain_average() {
+        let (price, volume) = volume_weighted(&[(100.0, Some(0.0)), (200.0, None)]).unwrap();
+
+        assert_eq!(price, 150.0);
+        assert_eq!(volume, None);
+
+        assert_eq!(volume_weighted(&[]), None);
+    }
+
+    #[tokio::test]
+    async fn vwap_is_named_after_the_providers_that_answered() {
+        let mut providers: Vec<NamedProvider> = vec![
+            (
+                "kraken".to_string(),
+                Box::new(Quote(Some((100.0, Some(1.0))))),
+            ),
+            ("binance".to_string(), Box::new(Quote(None))),
+            (
+                "coinbase".to_string(),
+                Box::new(Quote(Some((200.0, Some(1.0))))),
+            ),
+        ];
+
+        let (name, result) = fetch_vwap(&mut providers, &PriceCurrency::USD).await;
+        let result = result.unwrap();
+
+        assert_eq!(name, "vwap 2/3");
+        assert_eq!(result.price_in_currency, "150");
+        assert_eq!(result.volume_24h, Some(2.0));
+
+        let mut providers: Vec<NamedProvider> =
+            vec![("binance".to_string(), Box::new(Quote(None)))];
+        let (name, result) = fetch_vwap(&mut providers, &PriceCurrency::USD).await;
+
+        assert_eq!(name, "vwap 0/1");
+        assert!(result.is_none());
     }
 }
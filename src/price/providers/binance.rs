@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::bandwidth::{self, Source, HTTP_OVERHEAD};
+use crate::config::AppConfig;
+use crate::price::{PriceCurrency, PriceProvider, PriceResult};
+
+/// Last price and 24h volume of the Binance spot markets, USDT standing in for USD.
+pub struct PriceBinance;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceTickerResponse {
+    last_price: String,
+    /// In BTC.
+    volume: String,
+}
+
+#[async_trait]
+impl PriceProvider for PriceBinance {
+    fn new(_config: &AppConfig) -> Self {
+        Self
+    }
+
+    async fn fetch_current_price(
+        &mut self,
+        currency: &PriceCurrency,
+    ) -> Result<PriceResult, Box<dyn std::error::Error>> {
+        let quote = match currency.code() {
+            "USD" => "USDT",
+            code => code,
+        };
+
+        let bytes = reqwest::Client::new()
+            .get(format!(
+                "https://api.binance.com/api/v3/ticker/24hr?symbol=BTC{}",
+                quote
+            ))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        bandwidth::record(Source::Price, bytes.len() + HTTP_OVERHEAD);
+
+        let body = serde_json::from_slice::<BinanceTickerResponse>(&bytes)?;
+
+        Ok(PriceResult {
+            price_in_currency: body.last_price,
+            volume_24h: body.volume.parse().ok(),
+        })
+    }
+}
+
+impl Default for PriceBinance {
+    fn default() -> Self {
+        Self
+    }
+}
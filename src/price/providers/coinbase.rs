@@ -1,3 +1,5 @@
+use crate::bandwidth::{self, Source, HTTP_OVERHEAD};
+use crate::config::AppConfig;
 use crate::price::{PriceCurrency, PriceProvider, PriceResult};
 use async_trait::async_trait;
 use serde::Deserialize;
@@ -6,12 +8,14 @@ pub struct PriceCoinbase;
 #[derive(Debug, Deserialize)]
 struct CoinbasePriceResponse {
     price: String,
+    /// In BTC.
+    volume_24h: Option<String>,
 }
 
 #[async_trait]
 impl PriceProvider for PriceCoinbase {
-    fn new() -> Self {
-        Self::default()
+    fn new(_config: &AppConfig) -> Self {
+        Self
     }
 
     async fn fetch_current_price(
@@ -25,7 +29,7 @@ impl PriceProvider for PriceCoinbase {
 
         let request = client
             .get(
-                vec![
+                [
                     "https://api.coinbase.com/api/v3/brokerage/market/products/BTC",
                     &currency.to_string(),
                 ]
@@ -39,16 +43,20 @@ impl PriceProvider for PriceCoinbase {
             return Err(e.into());
         }
 
-        let json = request.unwrap().json::<CoinbasePriceResponse>().await;
+        let bytes = request.unwrap().bytes().await;
 
-        if let Err(e) = json {
+        if let Err(e) = bytes {
             return Err(e.into());
         }
 
-        let body = json.unwrap();
+        let bytes = bytes.unwrap();
+        bandwidth::record(Source::Price, bytes.len() + HTTP_OVERHEAD);
+
+        let body = serde_json::from_slice::<CoinbasePriceResponse>(&bytes)?;
 
         Ok(PriceResult {
             price_in_currency: body.price,
+            volume_24h: body.volume_24h.and_then(|volume| volume.parse().ok()),
         })
     }
 }
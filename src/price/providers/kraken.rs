@@ -0,0 +1,74 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::bandwidth::{self, Source, HTTP_OVERHEAD};
+use crate::config::AppConfig;
+use crate::price::{PriceCurrency, PriceProvider, PriceResult};
+
+/// Last trade and 24h volume of the Kraken XBT markets.
+pub struct PriceKraken;
+
+#[derive(Debug, Deserialize)]
+struct KrakenTickerResponse {
+    error: Vec<String>,
+    /// Keyed by Kraken's own pair name, e.g. `XXBTZUSD`.
+    #[serde(default)]
+    result: HashMap<String, KrakenTicker>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KrakenTicker {
+    /// Last trade, price and lot volume.
+    c: Vec<String>,
+    /// Volume today and over the last 24 hours.
+    v: Vec<String>,
+}
+
+#[async_trait]
+impl PriceProvider for PriceKraken {
+    fn new(_config: &AppConfig) -> Self {
+        Self
+    }
+
+    async fn fetch_current_price(
+        &mut self,
+        currency: &PriceCurrency,
+    ) -> Result<PriceResult, Box<dyn std::error::Error>> {
+        let bytes = reqwest::Client::new()
+            .get(format!(
+                "https://api.kraken.com/0/public/Ticker?pair=XBT{}",
+                currency
+            ))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        bandwidth::record(Source::Price, bytes.len() + HTTP_OVERHEAD);
+
+        let body = serde_json::from_slice::<KrakenTickerResponse>(&bytes)?;
+        if let Some(error) = body.error.first() {
+            return Err(error.clone().into());
+        }
+
+        let ticker = body
+            .result
+            .into_values()
+            .next()
+            .ok_or_else(|| format!("Kraken has no XBT{} market", currency))?;
+        let price = ticker.c.first().ok_or("Kraken sent no last trade")?;
+
+        Ok(PriceResult {
+            price_in_currency: price.clone(),
+            volume_24h: ticker.v.get(1).and_then(|volume| volume.parse().ok()),
+        })
+    }
+}
+
+impl Default for PriceKraken {
+    fn default() -> Self {
+        Self
+    }
+}
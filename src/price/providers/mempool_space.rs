@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::bandwidth::{self, Source, HTTP_OVERHEAD};
+use crate::config::AppConfig;
+use crate::node::providers::http_client;
+use crate::price::{PriceCurrency, PriceProvider, PriceResult};
+
+/// Prices of mempool.space or a self-hosted instance of it, to keep every lookup on
+/// your own infrastructure.
+pub struct PriceMempoolSpace {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+/// Price in each currency the instance converts to, besides a `time` field.
+type PricesResponse = HashMap<String, f64>;
+
+#[async_trait]
+impl PriceProvider for PriceMempoolSpace {
+    /// Instance at `price.mempool_url`, or the one of the fees, through the proxy when
+    /// it is an onion service.
+    fn new(config: &AppConfig) -> Self {
+        let url = match config.price.mempool_url.is_empty() {
+            true => &config.fees.mempool_url,
+            false => &config.price.mempool_url,
+        };
+
+        Self {
+            client: http_client(config, url),
+            base_url: url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn fetch_current_price(
+        &mut self,
+        currency: &PriceCurrency,
+    ) -> Result<PriceResult, Box<dyn std::error::Error>> {
+        let bytes = self
+            .client
+            .get(format!("{}/api/v1/prices", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        bandwidth::record(Source::Price, bytes.len() + HTTP_OVERHEAD);
+
+        let body = serde_json::from_slice::<PricesResponse>(&bytes)?;
+        let price = body.get(currency.code()).copied();
+
+        // Instances without a price feed answer with -1
+        match price {
+            Some(price) if price > 0.0 => Ok(PriceResult {
+                price_in_currency: price.to_string(),
+                volume_24h: None,
+            }),
+            _ => Err(format!("mempool.space has no {} price", currency).into()),
+        }
+    }
+}
@@ -1 +1,49 @@
+pub mod binance;
 pub mod coinbase;
+pub mod kraken;
+pub mod mempool_space;
+pub mod yadio;
+
+use crate::config::AppConfig;
+use crate::price::{NamedProvider, PriceProvider};
+use binance::PriceBinance;
+use coinbase::PriceCoinbase;
+use kraken::PriceKraken;
+use mempool_space::PriceMempoolSpace;
+use yadio::PriceYadio;
+
+type Constructor = fn(&AppConfig) -> Box<dyn PriceProvider + Send>;
+
+/// Every price provider by the name `price.provider` selects it with. A new provider
+/// only needs a line here.
+pub const REGISTRY: [(&str, Constructor); 5] = [
+    ("coinbase", |config| Box::new(PriceCoinbase::new(config))),
+    ("yadio", |config| Box::new(PriceYadio::new(config))),
+    ("mempool_space", |config| {
+        Box::new(PriceMempoolSpace::new(config))
+    }),
+    ("kraken", |config| Box::new(PriceKraken::new(config))),
+    ("binance", |config| Box::new(PriceBinance::new(config))),
+];
+
+/// Provider registered as `name`.
+pub fn from_name(name: &str, config: &AppConfig) -> Option<NamedProvider> {
+    REGISTRY
+        .iter()
+        .find(|(registered, _)| *registered == name)
+        .map(|(registered, new)| (registered.to_string(), new(config)))
+}
+
+/// Providers in `price.providers` or `price.provider`, Coinbase in place of unknown ones.
+pub fn from_config(config: &AppConfig) -> Vec<NamedProvider> {
+    config
+        .price_providers()
+        .iter()
+        .map(|name| {
+            from_name(name, config).unwrap_or_else(|| {
+                let (name, new) = REGISTRY[0];
+                (name.to_string(), new(config))
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,56 @@
+use crate::bandwidth::{self, Source, HTTP_OVERHEAD};
+use crate::config::AppConfig;
+use crate::price::{PriceCurrency, PriceProvider, PriceResult};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Street rates from yadio.io, for currencies with official rates far from what
+/// bitcoin actually trades at, like ARS, VES or NGN.
+pub struct PriceYadio;
+
+#[derive(Debug, Deserialize)]
+struct YadioRatesResponse {
+    /// Price of one bitcoin in every currency Yadio tracks.
+    #[serde(rename = "BTC")]
+    btc: HashMap<String, f64>,
+}
+
+#[async_trait]
+impl PriceProvider for PriceYadio {
+    fn new(_config: &AppConfig) -> Self {
+        Self
+    }
+
+    async fn fetch_current_price(
+        &mut self,
+        currency: &PriceCurrency,
+    ) -> Result<PriceResult, Box<dyn std::error::Error>> {
+        let bytes = reqwest::Client::new()
+            .get("https://api.yadio.io/exrates/BTC")
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        bandwidth::record(Source::Price, bytes.len() + HTTP_OVERHEAD);
+
+        let body = serde_json::from_slice::<YadioRatesResponse>(&bytes)?;
+        let price = body
+            .btc
+            .get(currency.code())
+            .ok_or_else(|| format!("Yadio has no {} rate", currency))?;
+
+        Ok(PriceResult {
+            price_in_currency: price.to_string(),
+            volume_24h: None,
+        })
+    }
+}
+
+impl Default for PriceYadio {
+    fn default() -> Self {
+        Self
+    }
+}
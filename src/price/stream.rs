@@ -0,0 +1,107 @@
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use crate::{
+    bandwidth::{self, Source},
+    event::{Event, MarketEvent},
+    health::SourceHealth,
+    throughput::{self, Feed},
+};
+
+use super::{PriceCurrency, PriceState, CHECK_INTERVAL};
+
+/// Name the streamed price is shown under.
+pub const STREAM_PROVIDER: &str = "coinbase ws";
+
+/// Tickers arrive on every trade, the app gets at most one a second.
+const UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A socket quiet for this long is taken as dropped, even if still open. Heartbeats
+/// arrive every second, so a market without trades does not count as quiet.
+const IDLE_TIMEOUT: Duration = CHECK_INTERVAL;
+
+/// How long the price is polled after the socket drops before connecting again.
+pub const RETRY_AFTER: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Deserialize)]
+struct CoinbaseTicker {
+    #[serde(rename = "type")]
+    kind: String,
+    price: Option<String>,
+    /// In BTC.
+    volume_24h: Option<String>,
+}
+
+/// Follows the `ticker` channel of the Coinbase Exchange feed at `url` until the socket
+/// drops, closes, goes quiet or turns the subscription down. Connecting gives up after
+/// [`IDLE_TIMEOUT`] too, so a stalled handshake falls back to polling.
+pub async fn coinbase_ticker(
+    url: &str,
+    currency: PriceCurrency,
+    sender: &mpsc::UnboundedSender<Event>,
+) {
+    let Ok(Ok((mut socket, _))) = tokio::time::timeout(IDLE_TIMEOUT, connect_async(url)).await
+    else {
+        return;
+    };
+
+    let subscribe = serde_json::json!({
+        "type": "subscribe",
+        "product_ids": [format!("BTC-{}", currency)],
+        "channels": ["ticker", "heartbeat"],
+    });
+    if socket
+        .send(Message::Text(subscribe.to_string()))
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    let mut sent_at: Option<Instant> = None;
+
+    loop {
+        let message = match tokio::time::timeout(IDLE_TIMEOUT, socket.next()).await {
+            Ok(Some(Ok(Message::Text(text)))) => text,
+            // Pings are answered by the socket itself
+            Ok(Some(Ok(Message::Ping(_) | Message::Pong(_)))) => continue,
+            _ => return,
+        };
+        bandwidth::record(Source::Price, message.len());
+
+        let Ok(ticker) = serde_json::from_str::<CoinbaseTicker>(&message) else {
+            continue;
+        };
+
+        match ticker.kind.as_str() {
+            "error" => return,
+            "ticker" => {}
+            _ => continue,
+        }
+
+        if sent_at.is_some_and(|at| at.elapsed() < UPDATE_INTERVAL) {
+            continue;
+        }
+
+        let Some(price) = ticker.price.and_then(|price| price.parse::<f64>().ok()) else {
+            continue;
+        };
+
+        sent_at = Some(Instant::now());
+        throughput::record(Feed::Price);
+        let _ = sender.send(
+            MarketEvent::PriceUpdate(PriceState {
+                provider: STREAM_PROVIDER.to_string(),
+                currency,
+                last_price_in_currency: Some(price),
+                volume_24h: ticker.volume_24h.and_then(|volume| volume.parse().ok()),
+                health: SourceHealth::fresh(),
+                ..PriceState::default()
+            })
+            .into(),
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Timelike};
+
+use crate::config::QuietSettings;
+
+/// Values one cron field matches, as bits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Field {
+    bits: u64,
+    /// Written as `*`, which matters for how day of month and day of week combine.
+    any: bool,
+}
+
+impl Field {
+    /// Accepts `*`, single values, ranges, steps and comma separated lists of those,
+    /// e.g. `*/15`, `1-5` or `0,30`.
+    fn parse(input: &str, min: u32, max: u32) -> Result<Field> {
+        let mut bits = 0;
+
+        for part in input.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => (range, step.parse::<u32>()?),
+                None => (part, 1),
+            };
+            let (start, end) = match (range, range.split_once('-')) {
+                ("*", _) => (min, max),
+                (_, Some((start, end))) => (start.parse()?, end.parse()?),
+                // `5/10` runs from 5 to the end, like most crons
+                (_, None) if part.contains('/') => (range.parse()?, max),
+                (_, None) => (range.parse()?, range.parse()?),
+            };
+
+            if step == 0 || start < min || end > max || start > end {
+                return Err(anyhow::Error::msg(format!("{} out of range", part)));
+            }
+
+            for value in (start..=end).step_by(step as usize) {
+                bits |= 1 << value;
+            }
+        }
+
+        Ok(Field {
+            bits,
+            any: input == "*",
+        })
+    }
+
+    fn contains(&self, value: u32) -> bool {
+        self.bits & (1 << value) != 0
+    }
+}
+
+/// Cron style schedule: minute, hour, day of month, month and day of week.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Schedule {
+    minute: Field,
+    hour: Field,
+    day: Field,
+    month: Field,
+    weekday: Field,
+}
+
+impl Schedule {
+    pub fn parse(input: &str) -> Result<Schedule> {
+        let [minute, hour, day, month, weekday] = input
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .try_into()
+            .map_err(|_| anyhow::Error::msg("Expected five fields like `0 3 * * *`"))?;
+
+        let mut weekday = Field::parse(weekday, 0, 7)?;
+        // Sunday is both 0 and 7
+        if weekday.contains(7) {
+            weekday.bits |= 1;
+        }
+
+        Ok(Schedule {
+            minute: Field::parse(minute, 0, 59)?,
+            hour: Field::parse(hour, 0, 23)?,
+            day: Field::parse(day, 1, 31)?,
+            month: Field::parse(month, 1, 12)?,
+            weekday,
+        })
+    }
+
+    /// Whether the schedule fires in the minute of `datetime`.
+    pub fn matches(&self, datetime: &DateTime<FixedOffset>) -> bool {
+        let day = self.day.contains(datetime.day());
+        let weekday = self
+            .weekday
+            .contains(datetime.weekday().num_days_from_sunday());
+
+        // When both are restricted either one is enough, as in cron
+        let date = match (self.day.any, self.weekday.any) {
+            (true, _) => weekday,
+            (_, true) => day,
+            _ => day || weekday,
+        };
+
+        date && self.minute.contains(datetime.minute())
+            && self.hour.contains(datetime.hour())
+            && self.month.contains(datetime.month())
+    }
+}
+
+/// Recurring stretch during which pushes are held back, e.g. a nightly backup that
+/// stops the node.
+#[derive(Debug, Clone)]
+pub struct QuietWindow {
+    pub schedule: Schedule,
+    pub minutes: u64,
+    /// Badge labels, or `Disk`, it covers, every push when empty.
+    pub alerts: Vec<String>,
+}
+
+impl TryFrom<&QuietSettings> for QuietWindow {
+    type Error = anyhow::Error;
+
+    fn try_from(settings: &QuietSettings) -> Result<QuietWindow> {
+        if settings.minutes == 0 {
+            return Err(anyhow::Error::msg("Needs a length in minutes"));
+        }
+
+        Ok(QuietWindow {
+            schedule: Schedule::parse(&settings.at)?,
+            minutes: settings.minutes,
+            alerts: settings.alerts.clone(),
+        })
+    }
+}
+
+impl QuietWindow {
+    /// Whether the window started within its length before `now`.
+    pub fn is_open(&self, now: &DateTime<FixedOffset>) -> bool {
+        (0..self.minutes as i64).any(|ago| self.schedule.matches(&(*now - Duration::minutes(ago))))
+    }
+
+    pub fn covers(&self, alert: &str) -> bool {
+        self.alerts.is_empty()
+            || self
+                .alerts
+                .iter()
+                .any(|covered| covered.eq_ignore_ascii_case(alert))
+    }
+}
+
+/// Whether a push titled `alert` is held back at `now`.
+pub fn is_quiet(windows: &[QuietWindow], alert: &str, now: &DateTime<FixedOffset>) -> bool {
+    windows
+        .iter()
+        .any(|window| window.covers(alert) && window.is_open(now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(datetime: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(datetime).unwrap()
+    }
+
+    fn window(at: &str, minutes: u64, alerts: &[&str]) -> QuietWindow {
+        QuietWindow {
+            schedule: Schedule::parse(at).unwrap(),
+            minutes,
+            alerts: alerts.iter().map(|alert| alert.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn field_ranges_steps_and_lists() {
+        let field = Field::parse("1-3,10,*/20", 0, 59).unwrap();
+
+        for value in [0, 1, 2, 3, 10, 20, 40] {
+            assert!(field.contains(value), "{}", value);
+        }
+        for value in [4, 9, 11, 50, 59] {
+            assert!(!field.contains(value), "{}", value);
+        }
+        assert!(!field.any);
+
+        let field = Field::parse("10-20/5", 0, 59).unwrap();
+        assert_eq!(field.bits, (1 << 10) | (1 << 15) | (1 << 20));
+
+        // A step from a single value runs to the end of the field
+        let field = Field::parse("5/25", 0, 59).unwrap();
+        assert_eq!(field.bits, (1 << 5) | (1 << 30) | (1 << 55));
+
+        assert!(Field::parse("*", 1, 31).unwrap().any);
+    }
+
+    #[test]
+    fn field_rejects_malformed_values() {
+        for input in ["60", "5-1", "*/0", "x", "1-", "", "0"] {
+            assert!(Field::parse(input, 1, 59).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn schedule_needs_five_fields() {
+        assert!(Schedule::parse("0 3 * *").is_err());
+        assert!(Schedule::parse("0 3 * * * *").is_err());
+        assert!(Schedule::parse("0 24 * * *").is_err());
+        assert!(Schedule::parse("0 3 * 13 *").is_err());
+        assert!(Schedule::parse("0 3 * * 8").is_err());
+        assert!(Schedule::parse("0 3 * * *").is_ok());
+    }
+
+    #[test]
+    fn weekday_seven_is_sunday() {
+        let schedule = Schedule::parse("0 3 * * 7").unwrap();
+
+        // 2024-01-07 was a Sunday
+        assert!(schedule.matches(&at("2024-01-07T03:00:00+00:00")));
+        assert!(!schedule.matches(&at("2024-01-08T03:00:00+00:00")));
+        assert_eq!(schedule, Schedule::parse("0 3 * * 0,7").unwrap());
+    }
+
+    #[test]
+    fn restricted_day_and_weekday_are_ored() {
+        // The 13th, or any Friday
+        let schedule = Schedule::parse("0 0 13 * 5").unwrap();
+
+        assert!(schedule.matches(&at("2024-01-05T00:00:00+00:00")));
+        assert!(schedule.matches(&at("2024-01-13T00:00:00+00:00")));
+        assert!(!schedule.matches(&at("2024-01-06T00:00:00+00:00")));
+
+        // With one of them left as `*` only the other one counts
+        let schedule = Schedule::parse("0 0 13 * *").unwrap();
+        assert!(!schedule.matches(&at("2024-01-05T00:00:00+00:00")));
+        assert!(schedule.matches(&at("2024-01-13T00:00:00+00:00")));
+
+        let schedule = Schedule::parse("0 0 * * 5").unwrap();
+        assert!(schedule.matches(&at("2024-01-05T00:00:00+00:00")));
+        assert!(!schedule.matches(&at("2024-01-13T00:00:00+00:00")));
+    }
+
+    #[test]
+    fn window_crosses_midnight() {
+        let window = window("30 23 * * *", 60, &[]);
+
+        assert!(!window.is_open(&at("2024-01-01T23:29:00+00:00")));
+        assert!(window.is_open(&at("2024-01-01T23:30:00+00:00")));
+        assert!(window.is_open(&at("2024-01-02T00:29:00+00:00")));
+        assert!(!window.is_open(&at("2024-01-02T00:30:00+00:00")));
+    }
+
+    #[test]
+    fn window_on_a_weekday_crosses_into_the_next_day() {
+        // Saturday night until Sunday morning
+        let window = window("0 22 * * 6", 600, &[]);
+
+        assert!(window.is_open(&at("2024-01-07T07:59:00+00:00")));
+        assert!(!window.is_open(&at("2024-01-07T08:00:00+00:00")));
+        assert!(!window.is_open(&at("2024-01-05T23:00:00+00:00")));
+    }
+
+    #[test]
+    fn windows_cover_their_alerts() {
+        let windows = [
+            window("0 3 * * *", 30, &["disk"]),
+            window("0 12 * * *", 10, &[]),
+        ];
+
+        assert!(windows[0].covers("Disk"));
+        assert!(!windows[0].covers("NO PEERS"));
+        assert!(windows[1].covers("NO PEERS"));
+
+        let night = at("2024-01-01T03:10:00+00:00");
+        assert!(is_quiet(&windows, "Disk", &night));
+        assert!(!is_quiet(&windows, "NO PEERS", &night));
+        assert!(is_quiet(
+            &windows,
+            "NO PEERS",
+            &at("2024-01-01T12:05:00+00:00")
+        ));
+        assert!(!is_quiet(
+            &windows,
+            "Disk",
+            &at("2024-01-01T04:00:00+00:00")
+        ));
+    }
+
+    #[test]
+    fn window_needs_a_length() {
+        let settings = QuietSettings {
+            at: "0 3 * * *".to_string(),
+            minutes: 0,
+            alerts: vec![],
+        };
+
+        assert!(QuietWindow::try_from(&settings).is_err());
+    }
+}
@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::time::{self, Duration, Instant};
+
+use crate::app::AppThread;
+use crate::event::{Event, UiEvent};
+use crate::node::NodeState;
+
+/// What a line of a recording holds.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Record {
+    Event(Box<Event>),
+    /// The node is polled outside the event loop, its state is written whenever it changed.
+    Node(Box<NodeState>),
+}
+
+/// One line of a recording.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Entry {
+    /// Milliseconds since the recording started.
+    pub at: u64,
+    pub record: Record,
+}
+
+/// Writes the events going through the loop to a JSON lines file, for bug reports and
+/// demos played back with `--replay`.
+pub struct Recorder {
+    file: File,
+    started: Instant,
+    /// Node state last written, to skip the ticks where nothing changed.
+    last_node: String,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> io::Result<Recorder> {
+        Ok(Recorder {
+            file: File::create(path)?,
+            started: Instant::now(),
+            last_node: String::new(),
+        })
+    }
+
+    /// Ticks are left out, the replay makes its own, and check whether the node changed.
+    pub fn record(&mut self, event: &Event, node: Option<&Arc<Mutex<NodeState>>>) {
+        let record = match (event, node) {
+            (Event::Ui(UiEvent::Tick), Some(node)) => {
+                let node = node.lock().unwrap();
+                let Ok(state) = serde_json::to_string(&*node) else {
+                    return;
+                };
+
+                if state == self.last_node {
+                    return;
+                }
+
+                self.last_node = state;
+                Record::Node(Box::new(node.clone()))
+            }
+            (Event::Ui(UiEvent::Tick), None) => return,
+            (event, _) => Record::Event(Box::new(event.clone())),
+        };
+
+        let entry = Entry {
+            at: self.started.elapsed().as_millis() as u64,
+            record,
+        };
+
+        // A recording that can't be written is not worth stopping the session for
+        if let Ok(line) = serde_json::to_string(&entry) {
+            let _ = writeln!(self.file, "{}", line);
+        }
+    }
+}
+
+/// Records everything published on the bus until the app shuts down.
+pub fn spawn_recorder(
+    thread: AppThread,
+    recorder: Recorder,
+    events: mpsc::UnboundedReceiver<Event>,
+    node: Option<Arc<Mutex<NodeState>>>,
+) {
+    thread.tracker.spawn(async move {
+        tokio::select! {
+            () = thread.token.cancelled() => {}
+            () = recording(recorder, events, node) => {}
+        }
+    });
+}
+
+async fn recording(
+    mut recorder: Recorder,
+    mut events: mpsc::UnboundedReceiver<Event>,
+    node: Option<Arc<Mutex<NodeState>>>,
+) {
+    while let Some(event) = events.recv().await {
+        recorder.record(&event, node.as_ref());
+    }
+}
+
+/// Feeds a recording back at `speed` times the pace it was recorded at, lines that do
+/// not parse are skipped.
+pub fn spawn_replay(thread: AppThread, path: String, speed: f64, node: Arc<Mutex<NodeState>>) {
+    thread.tracker.spawn(async move {
+        tokio::select! {
+            () = thread.token.cancelled() => {}
+            () = replay(thread.sender, &path, speed, node) => {}
+        }
+    });
+}
+
+async fn replay(
+    sender: mpsc::UnboundedSender<Event>,
+    path: &str,
+    speed: f64,
+    node: Arc<Mutex<NodeState>>,
+) {
+    let Ok(contents) = tokio::fs::read_to_string(path).await else {
+        return;
+    };
+    let started = Instant::now();
+    let speed = if speed > 0.0 { speed } else { 1.0 };
+
+    for entry in contents
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Entry>(line).ok())
+    {
+        time::sleep_until(started + Duration::from_millis(entry.at).div_f64(speed)).await;
+
+        match entry.record {
+            Record::Event(event) => {
+                let _ = sender.send(*event);
+            }
+            Record::Node(state) => {
+                let mut node = node.lock().unwrap();
+
+                // As when following a shared node, a new hash is a new block from here
+                let last_hash_instant = match node.last_hash.is_empty() {
+                    false if node.last_hash != state.last_hash => Some(Instant::now()),
+                    _ => node.last_hash_instant,
+                };
+
+                *node = NodeState {
+                    last_hash_instant,
+                    ..*state
+                };
+            }
+        }
+    }
+}
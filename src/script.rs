@@ -0,0 +1,96 @@
+use anyhow::Result;
+use std::path::Path;
+
+#[cfg(feature = "scripting")]
+use rhai::{Dynamic, Engine, Scope, AST};
+#[cfg(feature = "scripting")]
+use serde_json::{Map, Value};
+
+use crate::app::AppState;
+use crate::config::AppConfig;
+#[cfg(feature = "scripting")]
+use crate::stream::StreamField;
+
+/// Operations a script may run per tick, so a runaway loop can't freeze the interface.
+#[cfg(feature = "scripting")]
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// Latest lines returned by the script, and why the last run failed if it did.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptState {
+    pub lines: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// User rhai script behind the custom panel. Every tick its `render(state)` function gets
+/// the status bar fields plus the whole node state and returns the lines to show.
+pub struct Script {
+    #[cfg(feature = "scripting")]
+    engine: Engine,
+    #[cfg(feature = "scripting")]
+    ast: AST,
+}
+
+impl Script {
+    #[cfg(feature = "scripting")]
+    pub fn load(path: &Path) -> Result<Script> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+
+        let ast = engine
+            .compile_file(path.to_path_buf())
+            .map_err(|e| anyhow::Error::msg(e.to_string()))?;
+
+        Ok(Script { engine, ast })
+    }
+
+    #[cfg(not(feature = "scripting"))]
+    pub fn load(_path: &Path) -> Result<Script> {
+        Err(anyhow::Error::msg(
+            "Scripts need btcmon built with --features scripting",
+        ))
+    }
+
+    #[cfg(feature = "scripting")]
+    fn input(config: &AppConfig, state: &AppState) -> Value {
+        let mut input: Map<String, Value> = StreamField::ALL
+            .iter()
+            .map(|field| (field.name().to_string(), field.value(config, state)))
+            .collect();
+
+        let node = state.node.clone().unwrap_or_default();
+        let node = serde_json::to_value(&*node.lock().unwrap()).unwrap_or_default();
+        input.insert("node".to_string(), node);
+
+        Value::Object(input)
+    }
+
+    /// Calls `render`, which may return an array of lines or a single string.
+    #[cfg(feature = "scripting")]
+    pub fn run(&self, config: &AppConfig, state: &AppState) -> Result<Vec<String>> {
+        let input = rhai::serde::to_dynamic(Self::input(config, state))
+            .map_err(|e| anyhow::Error::msg(e.to_string()))?;
+
+        let output: Dynamic = self
+            .engine
+            .call_fn(&mut Scope::new(), &self.ast, "render", (input,))
+            .map_err(|e| anyhow::Error::msg(e.to_string()))?;
+
+        let lines = match output.is_array() {
+            true => output
+                .into_array()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|item| item.to_string())
+                .collect(),
+            false => output.to_string().lines().map(str::to_string).collect(),
+        };
+
+        Ok(lines)
+    }
+
+    #[cfg(not(feature = "scripting"))]
+    pub fn run(&self, _config: &AppConfig, _state: &AppState) -> Result<Vec<String>> {
+        Ok(vec![])
+    }
+}
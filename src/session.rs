@@ -0,0 +1,177 @@
+use chrono::Utc;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tokio::time::Instant;
+
+use crate::format::{format_elapsed, Locale};
+use crate::node::{NodeState, NodeStatus};
+use crate::price::PriceState;
+
+/// What the dashboard last showed, saved on exit to compare with the next start.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastSeen {
+    pub time: i64,
+    pub height: Option<u64>,
+    pub currency: String,
+    pub price: Option<f64>,
+}
+
+impl LastSeen {
+    pub fn capture(node: &NodeState, price: &PriceState) -> Self {
+        Self {
+            time: Utc::now().timestamp(),
+            height: Some(node.height).filter(|height| *height > 0),
+            currency: price.currency.to_string(),
+            price: price.last_price_in_currency,
+        }
+    }
+}
+
+/// Changes between the previous session and the first live values of this one.
+#[derive(Debug, Clone)]
+pub struct SinceLastSeen {
+    pub elapsed: i64,
+    pub blocks: Option<u64>,
+    /// Relative, `0.05` is +5%.
+    pub price_change: Option<f64>,
+}
+
+impl SinceLastSeen {
+    pub fn compare(last_seen: &LastSeen, now: &LastSeen) -> Self {
+        let price_change = match (last_seen.price, now.price) {
+            (Some(before), Some(after)) if before > 0.0 && last_seen.currency == now.currency => {
+                Some(after / before - 1.0)
+            }
+            _ => None,
+        };
+
+        Self {
+            elapsed: now.time - last_seen.time,
+            blocks: match (last_seen.height, now.height) {
+                (Some(before), Some(after)) => Some(after.saturating_sub(before)),
+                _ => None,
+            },
+            price_change,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionState {
+    pub started: Instant,
+    pub blocks_seen: u64,
+    pub price_low: Option<f64>,
+    pub price_high: Option<f64>,
+    pub reconnects: u64,
+    /// Badges that turned on and disk warnings, whether pushed or not.
+    pub alerts: u64,
+    /// Failed polls by source, e.g. `fees`.
+    pub errors: BTreeMap<String, u64>,
+    last_height: Option<u64>,
+    /// `failed_polls` of the node when last observed.
+    node_failed_polls: u64,
+    services: HashMap<String, NodeStatus>,
+    connected_services: HashSet<String>,
+}
+
+impl Default for SessionState {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            blocks_seen: 0,
+            price_low: None,
+            price_high: None,
+            reconnects: 0,
+            alerts: 0,
+            errors: BTreeMap::new(),
+            last_height: None,
+            node_failed_polls: 0,
+            services: HashMap::new(),
+            connected_services: HashSet::new(),
+        }
+    }
+}
+
+impl SessionState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn observe_node(&mut self, node: &NodeState) {
+        if node.status == NodeStatus::Online {
+            if let Some(last_height) = self.last_height {
+                self.blocks_seen += node.height.saturating_sub(last_height);
+            }
+        }
+
+        if node.height > 0 {
+            self.last_height = Some(node.height);
+        }
+
+        // The providers poll on their own, their failures are counted from the state
+        let failed = node.failed_polls.saturating_sub(self.node_failed_polls);
+        if failed > 0 {
+            *self.errors.entry("node".to_string()).or_default() += failed;
+        }
+        self.node_failed_polls = node.failed_polls;
+
+        for (service, status) in node.services.iter() {
+            let previous = self.services.insert(service.clone(), *status);
+
+            if *status != NodeStatus::Online || previous == Some(NodeStatus::Online) {
+                continue;
+            }
+
+            // Only count it as a reconnection if the service was online before
+            if !self.connected_services.insert(service.clone()) {
+                self.reconnects += 1;
+            }
+        }
+    }
+
+    pub fn observe_error(&mut self, source: &str) {
+        *self.errors.entry(source.to_string()).or_default() += 1;
+    }
+
+    pub fn observe_price(&mut self, price: &PriceState) {
+        if let Some(value) = price.last_price_in_currency {
+            self.price_low = Some(self.price_low.map_or(value, |low| low.min(value)));
+            self.price_high = Some(self.price_high.map_or(value, |high| high.max(value)));
+        }
+    }
+
+    /// Sign-off printed once the terminal is restored.
+    pub fn report(&self, locale: &Locale, currency: &str) -> Vec<String> {
+        let price_range = match (self.price_low, self.price_high) {
+            (Some(low), Some(high)) => format!(
+                "{} - {} {}",
+                locale.format_number(low.trunc(), 0),
+                locale.format_number(high.trunc(), 0),
+                currency
+            ),
+            _ => "-".to_string(),
+        };
+
+        let errors = match self.errors.is_empty() {
+            true => "none".to_string(),
+            false => self
+                .errors
+                .iter()
+                .map(|(source, count)| format!("{} {}", source, count))
+                .collect::<Vec<String>>()
+                .join(", "),
+        };
+
+        vec![
+            format!(
+                "btcmon session of {}",
+                format_elapsed(self.started.elapsed().as_secs() as i64)
+            ),
+            format!("  {:<14}{}", "Blocks seen", self.blocks_seen),
+            format!("  {:<14}{}", "Price range", price_range),
+            format!("  {:<14}{}", "Alerts fired", self.alerts),
+            format!("  {:<14}{}", "Reconnects", self.reconnects),
+            format!("  {:<14}{}", "Errors", errors),
+        ]
+    }
+}
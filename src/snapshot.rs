@@ -0,0 +1,74 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tokio::time::Duration;
+
+use crate::fees::FeesState;
+use crate::node::NodeState;
+use crate::paths;
+use crate::price::PriceState;
+
+/// How often the values on screen are written out.
+pub const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Last values shown, restored greyed out on the next start so the dashboard is not
+/// empty while the sources warm up after a crash or reboot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Unix time it was taken.
+    pub taken_at: i64,
+    pub node: Option<NodeState>,
+    pub price: Option<PriceState>,
+    pub fees: Option<FeesState>,
+}
+
+impl Snapshot {
+    pub fn new(
+        node: Option<NodeState>,
+        price: Option<PriceState>,
+        fees: Option<FeesState>,
+    ) -> Self {
+        Self {
+            taken_at: Utc::now().timestamp(),
+            node,
+            price,
+            fees,
+        }
+    }
+
+    /// `snapshot.json` in the cache directory, it can always be fetched again.
+    fn path() -> Option<PathBuf> {
+        paths::cache_dir().map(|dir| dir.join("snapshot.json"))
+    }
+
+    pub fn load() -> Option<Snapshot> {
+        let contents = fs::read_to_string(Snapshot::path()?).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    /// Written next to the previous one and moved over it, a crash halfway through
+    /// leaves the previous snapshot intact.
+    pub fn save(&self) {
+        let Some(path) = Snapshot::path() else {
+            return;
+        };
+        let Ok(contents) = serde_json::to_string(self) else {
+            return;
+        };
+
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+
+        let temp = path.with_extension("json.tmp");
+        if fs::write(&temp, contents).is_ok() {
+            let _ = fs::rename(&temp, &path);
+        }
+    }
+
+    /// Seconds since it was taken.
+    pub fn age(&self) -> i64 {
+        (Utc::now().timestamp() - self.taken_at).max(0)
+    }
+}
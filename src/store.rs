@@ -0,0 +1,61 @@
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::paths;
+
+/// Small key value file for the values that should survive a restart.
+#[derive(Debug, Default)]
+pub struct Store {
+    path: Option<PathBuf>,
+    values: Map<String, Value>,
+}
+
+impl Store {
+    /// Opens `state.json` in the state directory.
+    pub fn open() -> Self {
+        let Some(dir) = paths::state_dir() else {
+            return Self::default();
+        };
+
+        Self::open_path(dir.join("state.json"))
+    }
+
+    pub fn open_path(path: PathBuf) -> Self {
+        let values = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        Self {
+            path: Some(path),
+            values,
+        }
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.values
+            .get(key)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
+    /// Stores the value and writes the file right away, failures only lose persistence.
+    pub fn set<T: Serialize>(&mut self, key: &str, value: T) {
+        let Ok(value) = serde_json::to_value(value) else {
+            return;
+        };
+
+        self.values.insert(key.to_string(), value);
+
+        if let Some(path) = &self.path {
+            if let Some(dir) = path.parent() {
+                let _ = fs::create_dir_all(dir);
+            }
+
+            if let Ok(contents) = serde_json::to_string_pretty(&self.values) {
+                let _ = fs::write(path, contents);
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use std::io::{self, Write};
+use std::str::FromStr;
+use tokio::time::{Duration, Instant};
+
+use crate::app::AppState;
+use crate::badges::Metric;
+use crate::config::AppConfig;
+use crate::node::NodeStatus;
+
+/// Keys a status bar consumer can pick with `--fields`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StreamField {
+    Price,
+    Currency,
+    Height,
+    Behind,
+    Peers,
+    Fee,
+    FeeLow,
+    FeeHigh,
+    NodeOk,
+    /// `node.accent` as configured, for the bar to color the entry with.
+    Accent,
+}
+
+impl FromStr for StreamField {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<StreamField> {
+        match input {
+            "price" => Ok(StreamField::Price),
+            "currency" => Ok(StreamField::Currency),
+            "height" => Ok(StreamField::Height),
+            "behind" => Ok(StreamField::Behind),
+            "peers" => Ok(StreamField::Peers),
+            "fee" => Ok(StreamField::Fee),
+            "fee_low" => Ok(StreamField::FeeLow),
+            "fee_high" => Ok(StreamField::FeeHigh),
+            "node_ok" => Ok(StreamField::NodeOk),
+            "accent" => Ok(StreamField::Accent),
+            _ => Err(anyhow::Error::msg("Unknown stream field")),
+        }
+    }
+}
+
+impl StreamField {
+    pub const ALL: [StreamField; 10] = [
+        StreamField::Price,
+        StreamField::Currency,
+        StreamField::Height,
+        StreamField::Behind,
+        StreamField::Peers,
+        StreamField::Fee,
+        StreamField::FeeLow,
+        StreamField::FeeHigh,
+        StreamField::NodeOk,
+        StreamField::Accent,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StreamField::Price => "price",
+            StreamField::Currency => "currency",
+            StreamField::Height => "height",
+            StreamField::Behind => "behind",
+            StreamField::Peers => "peers",
+            StreamField::Fee => "fee",
+            StreamField::FeeLow => "fee_low",
+            StreamField::FeeHigh => "fee_high",
+            StreamField::NodeOk => "node_ok",
+            StreamField::Accent => "accent",
+        }
+    }
+
+    /// Unknown values are `null`, so consumers can tell them from zero.
+    pub fn value(&self, config: &AppConfig, state: &AppState) -> Value {
+        let node = state.node.clone().unwrap_or_default();
+        let node = node.lock().unwrap();
+
+        let metric = match self {
+            StreamField::Currency => return json!(state.price.currency.to_string()),
+            StreamField::NodeOk => return json!(node.status == NodeStatus::Online),
+            StreamField::Accent => {
+                return match config.node.accent.is_empty() {
+                    true => Value::Null,
+                    false => json!(config.node.accent),
+                }
+            }
+            StreamField::Price => Metric::Price,
+            StreamField::Height => Metric::NodeHeight,
+            StreamField::Behind => Metric::NodeBehind,
+            StreamField::Peers => Metric::NodePeers,
+            StreamField::Fee => Metric::FeesMedium,
+            StreamField::FeeLow => Metric::FeesLow,
+            StreamField::FeeHigh => Metric::FeesHigh,
+        };
+
+        match metric.value(&node, &state.fees, &state.price) {
+            // Counts and heights read better without a trailing `.0`
+            Some(value) if value.fract() == 0.0 && metric != Metric::Price => json!(value as u64),
+            Some(value) => json!(value),
+            None => Value::Null,
+        }
+    }
+}
+
+/// One compact JSON object per line for status bars, e.g. i3status-rust custom blocks.
+pub struct JsonStream {
+    pub config: AppConfig,
+    pub fields: Vec<StreamField>,
+    pub interval: Duration,
+    written_at: Option<Instant>,
+}
+
+impl JsonStream {
+    /// `None` unless `--format json-stream` was asked for; unknown fields are left out.
+    pub fn from_config(config: &AppConfig) -> Option<JsonStream> {
+        if config.format != "json-stream" {
+            return None;
+        }
+
+        Some(JsonStream {
+            config: config.clone(),
+            fields: config
+                .fields
+                .iter()
+                .filter_map(|field| StreamField::from_str(field).ok())
+                .collect(),
+            interval: Duration::from_secs(config.stream_interval.max(1)),
+            written_at: None,
+        })
+    }
+
+    pub fn line(&self, state: &AppState) -> String {
+        let object: Map<String, Value> = self
+            .fields
+            .iter()
+            .map(|field| (field.name().to_string(), field.value(&self.config, state)))
+            .collect();
+
+        Value::Object(object).to_string()
+    }
+
+    /// Writes a line once the interval passed, fails when the consumer went away.
+    pub fn tick(&mut self, state: &AppState) -> io::Result<()> {
+        if self
+            .written_at
+            .is_some_and(|written_at| written_at.elapsed() < self.interval)
+        {
+            return Ok(());
+        }
+
+        let mut stdout = io::stdout().lock();
+        writeln!(stdout, "{}", self.line(state))?;
+        stdout.flush()?;
+
+        self.written_at = Some(Instant::now());
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Kind of update the diagnostics view counts, to tell ZMQ pushes from polling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Feed {
+    /// `hashblock` notifications.
+    ZmqBlocks,
+    /// Any other ZMQ message, raw transactions and sequence numbers.
+    ZmqOther,
+    /// Successful polls of the node over RPC or HTTP.
+    NodePolls,
+    Price,
+    Fees,
+    Host,
+}
+
+impl Feed {
+    pub const ALL: [Feed; 6] = [
+        Feed::ZmqBlocks,
+        Feed::ZmqOther,
+        Feed::NodePolls,
+        Feed::Price,
+        Feed::Fees,
+        Feed::Host,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Feed::ZmqBlocks => "zmq blocks",
+            Feed::ZmqOther => "zmq other",
+            Feed::NodePolls => "node polls",
+            Feed::Price => "price",
+            Feed::Fees => "fees",
+            Feed::Host => "host",
+        }
+    }
+}
+
+/// Updates received since startup. Process wide like the traffic figures, the ZMQ
+/// listeners run far from the app.
+static COUNTS: [AtomicU64; 6] = [
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+];
+
+pub fn record(feed: Feed) {
+    COUNTS[feed as usize].fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn count(feed: Feed) -> u64 {
+    COUNTS[feed as usize].load(Ordering::Relaxed)
+}
+
+/// Every count, in the order of [`Feed::ALL`].
+pub fn counts() -> [u64; 6] {
+    Feed::ALL.map(count)
+}
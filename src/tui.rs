@@ -1,13 +1,20 @@
 use crate::app::{App, AppResult};
 use crate::config::AppConfig;
 use crate::event::EventHandler;
+#[cfg(unix)]
+use crate::mirror::Mirror;
 use crate::ui;
+use crate::ui::banner::CLEAR_IMAGES_SEQUENCE;
+use crossterm::cursor::MoveTo;
 use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::style::Print;
 use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
 use ratatui::backend::Backend;
+use ratatui::layout::Rect;
 use ratatui::Terminal;
 use std::io;
 use std::panic;
+use std::time::Instant;
 
 /// Representation of a terminal user interface.
 ///
@@ -19,12 +26,23 @@ pub struct Tui<B: Backend> {
     terminal: Terminal<B>,
     /// Terminal event handler.
     pub events: EventHandler,
+    /// Where the banner image was last placed.
+    banner_area: Option<Rect>,
+    /// Attached read only clients, when mirroring.
+    #[cfg(unix)]
+    pub mirror: Option<Mirror>,
 }
 
 impl<B: Backend> Tui<B> {
     /// Constructs a new instance of [`Tui`].
     pub fn new(terminal: Terminal<B>, events: EventHandler) -> Self {
-        Self { terminal, events }
+        Self {
+            terminal,
+            events,
+            banner_area: None,
+            #[cfg(unix)]
+            mirror: None,
+        }
     }
 
     /// Initializes the terminal interface.
@@ -52,7 +70,47 @@ impl<B: Backend> Tui<B> {
     /// [`Draw`]: ratatui::Terminal::draw
     /// [`rendering`]: crate::ui::render
     pub fn draw(&mut self, config: &AppConfig, app: &mut App) -> AppResult<()> {
-        self.terminal.draw(|frame| ui::render(config, &app.state, frame))?;
+        if app.state.diagnostics.visible {
+            app.state.diagnostics.channel_depth = self.events.depth();
+            app.state.diagnostics.tasks = app.thread.tracker.len();
+        }
+
+        let started_at = Instant::now();
+
+        #[cfg_attr(not(unix), allow(unused_variables))]
+        let frame = self
+            .terminal
+            .draw(|frame| ui::render(config, &app.state, frame))?;
+
+        #[cfg(unix)]
+        if let Some(mirror) = &self.mirror {
+            mirror.publish(frame.buffer);
+        }
+
+        self.place_banner_image(app)?;
+
+        app.state.diagnostics.record_frame(started_at.elapsed());
+        Ok(())
+    }
+
+    /// Images live outside the cell buffer, so they are only sent again when the slot moves.
+    fn place_banner_image(&mut self, app: &App) -> AppResult<()> {
+        let banner = &app.state.banner;
+        let area = banner.area(self.terminal.size()?);
+
+        if area == self.banner_area {
+            return Ok(());
+        }
+
+        crossterm::execute!(io::stderr(), Print(CLEAR_IMAGES_SEQUENCE))?;
+
+        if let Some((area, sequence)) =
+            area.and_then(|area| banner.image_sequence(area).map(|sequence| (area, sequence)))
+        {
+            crossterm::execute!(io::stderr(), MoveTo(area.x, area.y), Print(sequence))?;
+        }
+
+        self.banner_area = area;
         Ok(())
     }
 
@@ -70,6 +128,9 @@ impl<B: Backend> Tui<B> {
     ///
     /// It disables the raw mode and reverts back the terminal properties.
     pub fn exit(&mut self) -> AppResult<()> {
+        if self.banner_area.is_some() {
+            crossterm::execute!(io::stderr(), Print(CLEAR_IMAGES_SEQUENCE))?;
+        }
         Self::reset()?;
         self.terminal.show_cursor()?;
         Ok(())
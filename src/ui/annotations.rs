@@ -0,0 +1,84 @@
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use tui_popup::{Popup, SizedWrapper};
+
+use crate::annotations::{Annotation, MAX_NOTE_LENGTH};
+use crate::config::AppConfig;
+
+const MAX_LINES: usize = 15;
+
+/// Saved notes, the newest first.
+pub struct AnnotationList<'a> {
+    pub annotations: &'a [Annotation],
+}
+
+impl AnnotationList<'_> {
+    pub fn draw(&self, config: &AppConfig, frame: &mut Frame) {
+        let locale = config.locale();
+
+        let mut lines: Vec<Line> = self
+            .annotations
+            .iter()
+            .rev()
+            .take(MAX_LINES)
+            .map(|annotation| {
+                let height = match annotation.height {
+                    Some(height) => format!("{:>7}", height),
+                    None => format!("{:>7}", "-"),
+                };
+                let time = locale
+                    .to_datetime(annotation.time)
+                    .map(|datetime| locale.format_datetime(&datetime))
+                    .unwrap_or_default();
+
+                Line::from(vec![
+                    Span::styled(height, Style::new().fg(Color::Yellow)),
+                    Span::styled(format!("  {}  ", time), Style::new().fg(Color::DarkGray)),
+                    Span::raw(annotation.note.as_str()),
+                ])
+            })
+            .collect();
+
+        if lines.is_empty() {
+            lines.push(Line::from("No notes yet, press a to add one"));
+        }
+
+        let height = lines.len();
+        let sized_paragraph = SizedWrapper {
+            inner: Paragraph::new(lines),
+            width: MAX_NOTE_LENGTH + 32,
+            height,
+        };
+
+        let popup = Popup::new(" Notes ", sized_paragraph)
+            .style(Style::new().fg(Color::White).bg(Color::Black));
+        frame.render_widget(&popup, frame.size());
+    }
+}
+
+/// Prompt for a new note while it is typed.
+pub fn draw_note_input(frame: &mut Frame, input: &str, height: u64) {
+    let title = match height {
+        0 => " Note ".to_string(),
+        height => format!(" Note for block {} ", height),
+    };
+
+    let sized_paragraph = SizedWrapper {
+        inner: Paragraph::new(vec![
+            Line::from(""),
+            Line::from(format!("{}_", input)),
+            Line::from(""),
+            Line::from("[Enter] save  [Esc] cancel").centered(),
+        ]),
+        width: MAX_NOTE_LENGTH + 2,
+        height: 4,
+    };
+
+    let popup =
+        Popup::new(title, sized_paragraph).style(Style::new().fg(Color::White).bg(Color::Black));
+    frame.render_widget(&popup, frame.size());
+}
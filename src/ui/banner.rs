@@ -0,0 +1,111 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Style, Stylize},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::config::AppConfig;
+
+const IMAGE_WIDTH: u16 = 16;
+const IMAGE_HEIGHT: u16 = 4;
+
+/// Branding slot in the top right corner, for screens showing btcmon in public.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Banner {
+    #[default]
+    None,
+    Text(String),
+    Image(PathBuf),
+}
+
+impl Banner {
+    /// PNG paths become images when the terminal can show them, anything else is text.
+    pub fn from_config(config: &AppConfig) -> Banner {
+        let banner = config.ui.banner.trim();
+        let path = Path::new(banner);
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            _ if banner.is_empty() => Banner::None,
+            Some(extension) if extension.eq_ignore_ascii_case("png") => {
+                // An image the terminal cannot show is left out rather than shown as a path
+                match path.is_file() && supports_kitty_graphics() {
+                    true => Banner::Image(path.to_path_buf()),
+                    false => Banner::None,
+                }
+            }
+            _ => Banner::Text(banner.to_string()),
+        }
+    }
+
+    /// Inside the top panel border, clear of its centered content on most sizes.
+    pub fn area(&self, size: Rect) -> Option<Rect> {
+        let (width, height) = match self {
+            Banner::None => return None,
+            Banner::Text(text) => {
+                let chars = u16::try_from(text.chars().count()).unwrap_or(u16::MAX);
+                (chars.saturating_add(2), 1)
+            }
+            Banner::Image(_) => (IMAGE_WIDTH, IMAGE_HEIGHT),
+        };
+
+        if size.width < width.saturating_mul(3) || size.height < height + 8 {
+            return None;
+        }
+
+        Some(Rect::new(
+            size.width.saturating_sub(width.saturating_add(1)),
+            1,
+            width,
+            height,
+        ))
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        let Some(area) = self.area(frame.size()) else {
+            return;
+        };
+
+        match self {
+            Banner::Text(text) => frame.render_widget(
+                Paragraph::new(format!(" {} ", text))
+                    .style(Style::new().fg(Color::White).bg(Color::Black).bold())
+                    .alignment(Alignment::Right),
+                area,
+            ),
+            // The terminal draws the image on top once the frame is flushed
+            Banner::Image(_) => frame.render_widget(Clear, area),
+            Banner::None => {}
+        }
+    }
+
+    /// Kitty graphics sequence showing the image over `area`, read by the terminal from disk.
+    pub fn image_sequence(&self, area: Rect) -> Option<String> {
+        let Banner::Image(path) = self else {
+            return None;
+        };
+
+        let path = path.canonicalize().ok()?;
+
+        Some(format!(
+            "\x1b_Ga=T,f=100,t=f,c={},r={},C=1,q=2;{}\x1b\\",
+            area.width,
+            area.height,
+            STANDARD.encode(path.to_string_lossy().as_bytes())
+        ))
+    }
+}
+
+/// Removes every image btcmon placed.
+pub const CLEAR_IMAGES_SEQUENCE: &str = "\x1b_Ga=d,d=A,q=2\x1b\\";
+
+/// Terminals known to implement the kitty graphics protocol, the only one drawn, no sixel.
+fn supports_kitty_graphics() -> bool {
+    env::var_os("KITTY_WINDOW_ID").is_some()
+        || env::var("TERM").is_ok_and(|term| term.contains("kitty"))
+        || env::var("TERM_PROGRAM")
+            .is_ok_and(|program| program == "WezTerm" || program == "ghostty")
+}
@@ -0,0 +1,29 @@
+use ratatui::{
+    style::{Color, Style},
+    text::Line,
+    widgets::Paragraph,
+    Frame,
+};
+use tui_popup::{Popup, SizedWrapper};
+
+use crate::config::AppConfig;
+use crate::daily::DayStats;
+
+impl DayStats {
+    pub fn draw(&self, config: &AppConfig, frame: &mut Frame) {
+        let mut lines: Vec<Line> = vec![Line::from(self.date.clone()).centered(), Line::from("")];
+        lines.extend(self.report(&config.locale()).into_iter().map(Line::from));
+        lines.extend([Line::from(""), Line::from("[Esc] close").centered()]);
+
+        let height = lines.len();
+        let sized_paragraph = SizedWrapper {
+            inner: Paragraph::new(lines),
+            width: 48,
+            height,
+        };
+
+        let popup = Popup::new(" End of day ", sized_paragraph)
+            .style(Style::new().fg(Color::White).bg(Color::Black));
+        frame.render_widget(&popup, frame.size());
+    }
+}
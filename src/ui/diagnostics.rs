@@ -0,0 +1,186 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use tokio::time::Duration;
+
+use crate::app::AppState;
+use crate::bandwidth::{self, Source};
+use crate::config::AppConfig;
+use crate::format::format_bytes;
+use crate::health::{DataHealth, SourceHealth};
+use crate::node::NodeState;
+use crate::throughput::{self, Feed};
+
+const WIDTH: u16 = 44;
+
+/// Frame times, event loop load and the state of every source, in the top right corner.
+pub struct DiagnosticsOverlay<'a> {
+    pub state: &'a AppState,
+    pub node: &'a NodeState,
+}
+
+impl DiagnosticsOverlay<'_> {
+    fn millis(duration: Option<Duration>) -> String {
+        match duration {
+            Some(duration) => format!("{:.1} ms", duration.as_secs_f64() * 1000.0),
+            None => "-".to_string(),
+        }
+    }
+
+    fn source(health: &SourceHealth, stale_after: Duration, enabled: bool) -> (String, Color) {
+        if !enabled {
+            return ("disabled".to_string(), Color::DarkGray);
+        }
+
+        let (label, color) = match health.health(stale_after) {
+            DataHealth::Fresh => ("fresh", Color::Green),
+            DataHealth::Stale => ("stale", Color::Yellow),
+            DataHealth::Failing => ("failing", Color::Red),
+        };
+
+        let age = match health.updated_at {
+            Some(updated_at) => format!(", {}s ago", updated_at.elapsed().as_secs()),
+            None => String::new(),
+        };
+
+        (format!("{}{}", label, age), color)
+    }
+
+    pub fn draw(&self, config: &AppConfig, frame: &mut Frame) {
+        let diagnostics = &self.state.diagnostics;
+
+        let mut rows: Vec<(String, String, Color)> = vec![
+            (
+                "Frame".to_string(),
+                Self::millis(diagnostics.last_frame()),
+                Color::White,
+            ),
+            (
+                "Average".to_string(),
+                Self::millis(diagnostics.average_frame()),
+                Color::White,
+            ),
+            (
+                "Slowest".to_string(),
+                Self::millis(diagnostics.slowest_frame()),
+                Color::White,
+            ),
+            (
+                "Events/s".to_string(),
+                diagnostics.events_per_second.to_string(),
+                Color::White,
+            ),
+            (
+                "Queued".to_string(),
+                diagnostics.channel_depth.to_string(),
+                Color::White,
+            ),
+            (
+                "Tasks".to_string(),
+                diagnostics.tasks.to_string(),
+                Color::White,
+            ),
+        ];
+
+        rows.push((
+            "Node".to_string(),
+            format!("{} ({})", self.node.status, config.node.provider),
+            Color::White,
+        ));
+
+        let mut services: Vec<_> = self.node.services.iter().collect();
+        services.sort_by_key(|(name, _)| name.as_str());
+        for (name, status) in services {
+            rows.push((format!("  {}", name), status.to_string(), Color::White));
+        }
+
+        rows.push((
+            "Traffic".to_string(),
+            format!(
+                "{}, {}/h",
+                format_bytes(bandwidth::total()),
+                format_bytes(bandwidth::hourly_rate())
+            ),
+            Color::White,
+        ));
+
+        for source in Source::ALL {
+            rows.push((
+                format!("  {}", source.name()),
+                format_bytes(bandwidth::bytes(source)),
+                Color::White,
+            ));
+        }
+
+        if config.bandwidth.budget_mb > 0 {
+            let (budget, color) = match bandwidth::stretch_factor() {
+                1 => (format!("{} MB/h", config.bandwidth.budget_mb), Color::Green),
+                factor => (format!("over, polling {}x slower", factor), Color::Yellow),
+            };
+            rows.push(("Budget".to_string(), budget, color));
+        }
+
+        rows.push(("Updates/min".to_string(), String::new(), Color::White));
+
+        // A working ZMQ shows blocks here as they arrive, not a poll later
+        for feed in Feed::ALL {
+            let total = throughput::count(feed);
+            let rate = match diagnostics.per_minute(feed) {
+                Some(rate) => format!("{:.1}, {} total", rate, total),
+                None => format!("{} total", total),
+            };
+            rows.push((format!("  {}", feed.name()), rate, Color::White));
+        }
+
+        let (price, color) = Self::source(
+            &self.state.price.health,
+            config.stale_after(crate::price::CHECK_INTERVAL),
+            config.price.enabled,
+        );
+        rows.push(("Price".to_string(), price, color));
+
+        let (fees, color) = Self::source(
+            &self.state.fees.health,
+            config.stale_after(config.fees_interval()),
+            config.fees.enabled,
+        );
+        rows.push(("Fees".to_string(), fees, color));
+
+        let (host, color) = Self::source(
+            &self.state.host.health,
+            config.stale_after(crate::host::CHECK_INTERVAL),
+            config.host.enabled,
+        );
+        rows.push(("Host".to_string(), host, color));
+
+        let lines: Vec<Line> = rows
+            .into_iter()
+            .map(|(name, value, color)| {
+                Line::from(vec![
+                    Span::styled(format!("{:<13}", name), Style::new().fg(Color::DarkGray)),
+                    Span::styled(value, Style::new().fg(color)),
+                ])
+            })
+            .collect();
+
+        let screen = frame.size();
+        let width = WIDTH.min(screen.width);
+        let height = (lines.len() as u16 + 2).min(screen.height);
+        let area = Rect::new(screen.width - width, 0, width, height);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(" Diagnostics [F12] ")
+                    .style(Style::new().fg(Color::White).bg(Color::Black)),
+            ),
+            area,
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use ratatui::{
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use tui_popup::{Popup, SizedWrapper};
+
+use crate::config::AppConfig;
+use crate::node::{NodeState, RETARGET_INTERVAL};
+
+use super::format::format_change;
+
+/// Latest difficulty retargets and where the current epoch is heading.
+pub struct DifficultyEpochs<'a> {
+    pub node: &'a NodeState,
+}
+
+impl DifficultyEpochs<'_> {
+    pub fn draw(&self, config: &AppConfig, frame: &mut Frame) {
+        let locale = config.locale();
+        let remaining = RETARGET_INTERVAL - self.node.height % RETARGET_INTERVAL;
+
+        let estimate = match self.node.difficulty_estimate {
+            Some(change) => vec![
+                Span::raw("Next: "),
+                format_change(&locale, change),
+                Span::raw(format!(" in {} blocks", remaining)),
+            ],
+            None => vec![Span::raw(format!("Next: in {} blocks", remaining))],
+        };
+
+        let mut lines = vec![
+            Line::from(estimate),
+            Line::from(""),
+            Line::from(format!(
+                "{:<9}{:<21}{:>14}  {:>9}",
+                "Height", "Date", "Difficulty", "Change"
+            ))
+            .bold(),
+        ];
+
+        for epoch in self.node.epochs.iter() {
+            let change = match epoch.change {
+                Some(change) => format_change(&locale, change),
+                None => Span::raw("-"),
+            };
+
+            lines.push(Line::from(vec![
+                Span::raw(format!(
+                    "{:<9}{:<21}{:>14}  ",
+                    epoch.height,
+                    locale.format_timestamp(epoch.time),
+                    format!("{} T", locale.format_number(epoch.difficulty / 1e12, 2))
+                )),
+                Span::raw(format!("{:>9}", change.content)).style(change.style),
+            ]));
+        }
+
+        if self.node.epochs.is_empty() {
+            lines.push(Line::from("..."));
+        }
+
+        let sized_paragraph = SizedWrapper {
+            inner: Paragraph::new(lines.clone()),
+            width: 55,
+            height: lines.len(),
+        };
+
+        let popup = Popup::new(" Difficulty epochs ", sized_paragraph)
+            .style(Style::new().fg(Color::White).bg(Color::Black));
+        frame.render_widget(&popup, frame.size());
+    }
+}
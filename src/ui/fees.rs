@@ -1,55 +1,196 @@
 use ratatui::{
-    layout::{Alignment, Rect},
+    layout::{Constraint, Layout, Rect},
     prelude::Stylize,
     style::Style,
     text::Line,
     text::Span,
-    widgets::{Block, BorderType, Padding, Paragraph},
+    widgets::{Padding, Paragraph},
 };
 
+use crate::annotations::{sample_marks, Annotation};
+use crate::config::AppConfig;
 use crate::fees::FeesState;
+use crate::format::Locale;
+use crate::price::PriceState;
 
-use super::Draw;
+use super::widgets::Sparkline;
+use super::{Draw, PanelOptions};
 
-impl Draw for FeesState {
-    fn draw(&self, frame: &mut ratatui::Frame, area: Rect, style: Option<Style>) {
-        let style = style.unwrap_or(Style::new());
+/// Fee rates, with the fiat cost of a typical transaction once the price is known.
+pub struct FeesPanel<'a> {
+    pub fees: &'a FeesState,
+    pub price: Option<&'a PriceState>,
+    /// `mempoolminfee` of the node, when the rates are checked against it.
+    pub min_fee: Option<f64>,
+    /// Notes marked on the sparkline.
+    pub annotations: &'a [Annotation],
+}
 
-        let fee_state = self.result.clone();
-        // fee_state.dedup_by(|a, b| a.fee == b.fee);
+impl Draw for FeesPanel<'_> {
+    fn draw(
+        &self,
+        config: &AppConfig,
+        frame: &mut ratatui::Frame,
+        area: Rect,
+        options: PanelOptions,
+    ) {
+        let style = options.style.unwrap_or_default();
+        let locale = config.locale();
 
-        let fees: Vec<Option<Line>> = vec![
-            Some(Line::from(Span::raw("Priority"))),
-            get_fee_line("Low", fee_state.low),
-            get_fee_line("Normal", fee_state.medium),
-            get_fee_line("High", fee_state.high),
-        ];
-        
-        let filtered_fees: Vec<Line> = fees.into_iter().filter_map(|opt| opt).collect();
-
-        let fees_block = Paragraph::new(filtered_fees)
-            .block(
-                Block::bordered()
-                    .padding(Padding::left(1))
-                    .title("Fees")
-                    .title_alignment(Alignment::Center)
-                    .border_type(BorderType::Plain),
-            )
-            .style(style);
+        // Slowest target first, like low, normal and high with the default targets
+        let rates: Vec<(u64, f64)> = self
+            .fees
+            .result
+            .rates
+            .iter()
+            .rev()
+            .map(|(target, rate)| (*target, *rate))
+            .collect();
+
+        let mut filtered_fees: Vec<Line> = vec![Line::from(Span::raw("Priority"))];
+        filtered_fees.extend(
+            rates
+                .iter()
+                .map(|(target, rate)| self.get_fee_line(config, &locale, *target, *rate)),
+        );
+
+        // One line per size preset, the costs at each target from slowest to fastest
+        if config.fees.tx_vbytes.len() > 1 {
+            for vbytes in config.fees.tx_vbytes.iter() {
+                let costs: Vec<String> = rates
+                    .iter()
+                    .filter_map(|(_, rate)| self.cost(config, *rate, *vbytes))
+                    .collect();
+
+                if !costs.is_empty() {
+                    filtered_fees.push(Line::from(vec![
+                        Span::raw(format!("{} vB ≈ ", vbytes)),
+                        Span::styled(costs.join(" · "), Style::new().white().italic()),
+                    ]));
+                }
+            }
+        }
+
+        // Rates the node would turn away, e.g. a stale "low" while its mempool is full
+        if let Some(min_fee) = self.min_fee {
+            filtered_fees.push(Line::from(vec![
+                Span::raw("Node minimum: "),
+                Span::styled(
+                    format!("{} Sats/vbyte", locale.format_number(min_fee, 1)),
+                    Style::new().white().italic(),
+                ),
+            ]));
+        }
+
+        // A node that just restarted or sees few transactions estimates far off
+        if let Some(divergence) = &self.fees.divergence {
+            filtered_fees.push(Line::from(Span::styled(
+                format!(
+                    "Node says {}, {} says {}",
+                    locale.format_number(divergence.local, 0),
+                    divergence.external_provider,
+                    locale.format_number(divergence.external, 0)
+                ),
+                Style::new().yellow().italic(),
+            )));
+        }
+
+        // With a failover chain, which provider the rates are from
+        let title = match (config.fee_providers().len(), self.fees.provider.as_str()) {
+            (0..=1, _) | (_, "") => "Fees".to_string(),
+            (_, provider) => format!("Fees · {}", provider),
+        };
+
+        let block = options.block(title).padding(Padding::left(1));
+        let inner = block.inner(area);
+        let lines = filtered_fees.len() as u16;
+
+        let fees_block = Paragraph::new(filtered_fees).block(block).style(style);
 
         frame.render_widget(fees_block, area);
+
+        // Normal rate over the last polls in the rows left, to tell a mempool heating up
+        // from one cooling down
+        let (history, times): (Vec<f64>, Vec<i64>) = self
+            .fees
+            .history
+            .iter()
+            .zip(&self.fees.history_times)
+            .filter_map(|(result, time)| Some((result.medium()?, *time)))
+            .unzip();
+
+        if history.len() > 1 && inner.height > lines && !config.low_profile() {
+            let [_, chart] =
+                Layout::vertical([Constraint::Length(lines), Constraint::Fill(1)]).areas(inner);
+
+            let marks = sample_marks(self.annotations, &times);
+
+            frame.render_widget(
+                Sparkline::new(&history)
+                    .style(Style::new().dark_gray())
+                    .marks(&marks),
+                chart,
+            );
+        }
     }
 }
 
-fn get_fee_line<'a>(name: &'a str, value: Option<String>) -> Option<Line<'a>> {
-    if let Some(res) = value {
-        return Some(Line::from(vec![
+impl FeesPanel<'_> {
+    fn get_fee_line(
+        &self,
+        config: &AppConfig,
+        locale: &Locale,
+        target: u64,
+        rate: f64,
+    ) -> Line<'static> {
+        let formatted = match rate.fract() == 0.0 {
+            true => locale.format_number(rate, 0),
+            false => locale.format_number(rate, 1),
+        };
+
+        let name = match target {
+            1 => "Next block".to_string(),
+            _ => format!("{} blocks", target),
+        };
+
+        let rejected = self.min_fee.is_some_and(|min_fee| rate < min_fee);
+        let rate_style = match rejected {
+            true => Style::new().red().italic(),
+            false => Style::new().white().italic(),
+        };
+
+        let mut spans = vec![
             Span::raw(name),
             Span::raw(": "),
-            Span::styled(res, Style::new().white().italic()),
-            Span::styled(" Sats/vbyte ", Style::new().white().italic()),
-        ]));
+            Span::styled(formatted, rate_style),
+            Span::styled(" Sats/vbyte ", rate_style),
+        ];
+
+        if rejected {
+            spans.push(Span::styled("below node minimum ", Style::new().red()));
+        }
+
+        // Approximate cost in the price currency, for anyone not thinking in sats per vbyte
+        let cost = match config.fees.tx_vbytes.as_slice() {
+            [vbytes] => self.cost(config, rate, *vbytes),
+            _ => None,
+        };
+        if let Some(cost) = cost {
+            spans.push(Span::styled(
+                format!("≈ {}", cost),
+                Style::new().dark_gray().italic(),
+            ));
+        }
+
+        Line::from(spans)
     }
 
-    None
+    /// Fee of a transaction of `vbytes` at `rate`, in the price currency.
+    fn cost(&self, config: &AppConfig, rate: f64, vbytes: u64) -> Option<String> {
+        let price = self.price?;
+        let btc_price = price.last_price_in_currency?;
+        let cost = rate * vbytes as f64 / 100_000_000.0 * btc_price;
+
+        Some(price.format_price(config, cost, 2))
+    }
 }
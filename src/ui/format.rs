@@ -0,0 +1,22 @@
+use ratatui::{
+    style::{Color, Style},
+    text::Span,
+};
+
+use crate::format::Locale;
+
+/// Relative change as a signed percentage, `0.05` is +5.00%, green when up and red
+/// when down.
+pub fn format_change(locale: &Locale, change: f64) -> Span<'static> {
+    let text = format!(
+        "{}{}%",
+        if change > 0.0 { "+" } else { "" },
+        locale.format_number(change * 100.0, 2)
+    );
+
+    match change {
+        change if change > 0.0 => Span::styled(text, Style::new().fg(Color::Green)),
+        change if change < 0.0 => Span::styled(text, Style::new().fg(Color::Red)),
+        _ => Span::raw(text),
+    }
+}
@@ -0,0 +1,111 @@
+use chrono::{Datelike, Timelike};
+use ratatui::{
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use tui_popup::{Popup, SizedWrapper};
+
+use crate::annotations::Annotation;
+use crate::config::AppConfig;
+use crate::node::NodeState;
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const SHADES: [&str; 4] = ["░░", "▒▒", "▓▓", "██"];
+
+/// Block arrivals of the last week by weekday and hour, like a contribution graph.
+/// Hours with a note in them are marked.
+pub struct BlockHeatmap<'a> {
+    pub node: &'a NodeState,
+    pub annotations: &'a [Annotation],
+}
+
+impl BlockHeatmap<'_> {
+    fn counts(&self, config: &AppConfig) -> [[u32; 24]; 7] {
+        let locale = config.locale();
+        let mut counts = [[0; 24]; 7];
+
+        for time in self.node.block_times.values() {
+            if let Some(datetime) = locale.to_datetime(*time) {
+                counts[datetime.weekday().num_days_from_monday() as usize]
+                    [datetime.hour() as usize] += 1;
+            }
+        }
+
+        counts
+    }
+
+    fn annotated(&self, config: &AppConfig) -> [[bool; 24]; 7] {
+        let locale = config.locale();
+        let mut annotated = [[false; 24]; 7];
+        let since = self
+            .node
+            .block_times
+            .values()
+            .next()
+            .copied()
+            .unwrap_or(i64::MAX);
+
+        for annotation in self.annotations.iter().filter(|a| a.time >= since) {
+            if let Some(datetime) = locale.to_datetime(annotation.time) {
+                annotated[datetime.weekday().num_days_from_monday() as usize]
+                    [datetime.hour() as usize] = true;
+            }
+        }
+
+        annotated
+    }
+
+    pub fn draw(&self, config: &AppConfig, frame: &mut Frame) {
+        let counts = self.counts(config);
+        let annotated = self.annotated(config);
+        let max = counts.iter().flatten().copied().max().unwrap_or(0);
+        let shade_style = Style::new().fg(Color::Green);
+
+        let mut hours = String::from("    ");
+        for hour in (0..24).step_by(3) {
+            hours.push_str(&format!("{:<6}", hour));
+        }
+
+        let mut lines = vec![Line::from(hours)];
+
+        for (weekday, row) in counts.iter().enumerate() {
+            let mut spans = vec![Span::raw(format!("{} ", WEEKDAYS[weekday]))];
+
+            spans.extend(row.iter().zip(annotated[weekday]).map(|(count, marked)| {
+                let span = match count {
+                    0 => Span::styled("··", Style::new().fg(Color::DarkGray)),
+                    _ => Span::styled(
+                        SHADES[((count - 1) * SHADES.len() as u32 / max) as usize],
+                        shade_style,
+                    ),
+                };
+
+                match marked {
+                    true => span.bg(Color::Yellow),
+                    false => span,
+                }
+            }));
+
+            lines.push(Line::from(spans));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!(
+            "{} blocks, at most {} in one hour",
+            self.node.block_times.len(),
+            max
+        )));
+
+        let sized_paragraph = SizedWrapper {
+            inner: Paragraph::new(lines),
+            width: 52,
+            height: 10,
+        };
+
+        let popup = Popup::new(" Block arrivals (7d) ", sized_paragraph)
+            .style(Style::new().fg(Color::White).bg(Color::Black));
+        frame.render_widget(&popup, frame.size());
+    }
+}
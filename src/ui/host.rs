@@ -0,0 +1,88 @@
+use ratatui::{
+    layout::Rect,
+    prelude::Stylize,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Padding, Paragraph, Wrap},
+};
+
+use crate::config::AppConfig;
+use crate::format::format_bytes;
+use crate::host::{HostState, Usage};
+
+use super::{Draw, PanelOptions};
+
+/// Columns taken next to the node panel.
+pub const WIDTH: u16 = 28;
+
+impl Draw for HostState {
+    fn draw(
+        &self,
+        _config: &AppConfig,
+        frame: &mut ratatui::Frame,
+        area: Rect,
+        options: PanelOptions,
+    ) {
+        let style = options.style.unwrap_or_default();
+
+        let cpu = match (self.cpu, self.load) {
+            (Some(cpu), Some(load)) => {
+                Some((format!("{:.0}%, load {:.2}", cpu * 100.0, load), cpu))
+            }
+            (Some(cpu), None) => Some((format!("{:.0}%", cpu * 100.0), cpu)),
+            (None, Some(load)) => Some((format!("load {:.2}", load), 0.0)),
+            (None, None) => None,
+        };
+
+        let mut lines = vec![
+            get_host_line("CPU", cpu),
+            get_host_line("RAM", self.memory.map(usage)),
+            get_host_line("Disk", self.disk.map(usage)),
+        ];
+
+        if let Some(error) = self.error.as_ref().filter(|_| self.health.failing) {
+            lines.push(Line::from(Span::styled(
+                error.clone(),
+                Style::new().fg(Color::Red).italic(),
+            )));
+        }
+
+        let title = match self.name.as_str() {
+            "" | "local" => "Host".to_string(),
+            name => name.to_string(),
+        };
+
+        let host_block = Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(options.block(title).padding(Padding::left(1)))
+            .style(style);
+
+        frame.render_widget(host_block, area);
+    }
+}
+
+fn usage(usage: Usage) -> (String, f64) {
+    (
+        format!(
+            "{:.0}% of {}",
+            usage.ratio() * 100.0,
+            format_bytes(usage.total)
+        ),
+        usage.ratio(),
+    )
+}
+
+/// Value turns yellow past 75% and red past 90%.
+fn get_host_line(name: &str, value: Option<(String, f64)>) -> Line<'_> {
+    let (value, color) = match value {
+        Some((value, ratio)) if ratio >= 0.9 => (value, Color::Red),
+        Some((value, ratio)) if ratio >= 0.75 => (value, Color::Yellow),
+        Some((value, _)) => (value, Color::White),
+        None => ("...".to_string(), Color::White),
+    };
+
+    Line::from(vec![
+        Span::raw(format!("{:<6}", format!("{}:", name))),
+        Span::styled(value, Style::new().fg(color).italic()),
+    ])
+}
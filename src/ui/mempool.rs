@@ -0,0 +1,74 @@
+use ratatui::{
+    layout::Rect,
+    prelude::Stylize,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Padding, Paragraph},
+};
+
+use crate::config::AppConfig;
+use crate::fees::MempoolStats;
+use crate::format::format_elapsed;
+
+use super::{Draw, PanelOptions};
+
+/// Backlog of the mempool and how long it takes to clear at full blocks.
+pub struct MempoolPanel<'a> {
+    pub mempool: Option<&'a MempoolStats>,
+}
+
+impl Draw for MempoolPanel<'_> {
+    fn draw(
+        &self,
+        config: &AppConfig,
+        frame: &mut ratatui::Frame,
+        area: Rect,
+        options: PanelOptions,
+    ) {
+        let style = options.style.unwrap_or_default();
+        let locale = config.locale();
+
+        let lines = match self.mempool {
+            Some(mempool) => {
+                let blocks = mempool.blocks_to_clear();
+
+                vec![
+                    get_mempool_line(
+                        "Pending",
+                        format!(
+                            "{} vMB",
+                            locale.format_number(mempool.vsize as f64 / 1_000_000.0, 1)
+                        ),
+                    ),
+                    get_mempool_line(
+                        "Transactions",
+                        locale.format_number(mempool.count as f64, 0),
+                    ),
+                    get_mempool_line(
+                        "Clears in",
+                        format!(
+                            "≈ {} blocks, {}",
+                            locale.format_number(blocks as f64, 0),
+                            format_elapsed(blocks as i64 * 600)
+                        ),
+                    ),
+                ]
+            }
+            None => vec![get_mempool_line("Pending", "...".to_string())],
+        };
+
+        let mempool_block = Paragraph::new(lines)
+            .block(options.block("Mempool").padding(Padding::left(1)))
+            .style(style);
+
+        frame.render_widget(mempool_block, area);
+    }
+}
+
+fn get_mempool_line<'a>(name: &'a str, value: String) -> Line<'a> {
+    Line::from(vec![
+        Span::raw(name),
+        Span::raw(": "),
+        Span::styled(value, Style::new().white().italic()),
+    ])
+}
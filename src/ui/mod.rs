@@ -1,29 +1,269 @@
-use crate::{app::AppState, config::AppConfig, node::NodeStatus};
+use crate::{
+    app::{AppState, DetailView, Panel, PendingAction},
+    config::AppConfig,
+    health::DataHealth,
+    node::{providers, NodeState, NodeStatus},
+};
+use annotations::{draw_note_input, AnnotationList};
+use chrono::Utc;
+use diagnostics::DiagnosticsOverlay;
+use epochs::DifficultyEpochs;
+use fees::FeesPanel;
+use heatmap::BlockHeatmap;
+use mempool::MempoolPanel;
+use next_block::NextBlockPanel;
+use node::NodePanel;
+use peers::PeersPanel;
+use preflight::PreflightResults;
+use price::PricePanel;
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{
+        block::{Position, Title},
+        Block, BorderType, Paragraph,
+    },
     Frame,
 };
+use splash::Splash;
+use tui_popup::{Popup, SizedWrapper};
 
+pub mod annotations;
+pub mod banner;
+pub mod daily;
+pub mod diagnostics;
+pub mod epochs;
 pub mod fees;
+pub mod format;
+pub mod heatmap;
+pub mod host;
+pub mod mempool;
+pub mod next_block;
 pub mod node;
+pub mod peers;
+pub mod preflight;
 pub mod price;
+pub mod script;
+pub mod session;
+pub mod since;
+pub mod splash;
+pub mod widgets;
+
+/// How the layout wants a panel to be decorated.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PanelOptions {
+    pub style: Option<Style>,
+    pub focused: bool,
+    pub paused: bool,
+    /// Border color set apart from the style, e.g. `node.accent`.
+    pub accent: Option<Color>,
+    /// Data older than `ui.stale_polls` polls, still shown since nothing newer came.
+    pub stale: bool,
+}
+
+impl PanelOptions {
+    pub fn border_type(&self) -> BorderType {
+        if self.focused {
+            BorderType::Thick
+        } else {
+            BorderType::Plain
+        }
+    }
+
+    /// Bordered block with the title and the badges every panel shares.
+    pub fn block<'a, T: Into<Title<'a>>>(&self, title: T) -> Block<'a> {
+        let mut block = Block::bordered()
+            .title(title)
+            .title_alignment(Alignment::Center)
+            .border_type(self.border_type());
+
+        if let Some(accent) = self.accent {
+            block = block.border_style(Style::new().fg(accent));
+        }
+
+        if self.paused {
+            block = block.title(
+                Title::from(Span::styled(" paused ", Style::new().reversed()))
+                    .alignment(Alignment::Right),
+            );
+        } else if self.stale {
+            block = block.title(
+                Title::from(Span::styled(" STALE ", Style::new().fg(Color::DarkGray)))
+                    .position(Position::Bottom)
+                    .alignment(Alignment::Center),
+            );
+        }
+
+        block
+    }
+
+    /// Dimmed and watermarked when `stale`, paused panels are old on purpose.
+    pub fn stale(mut self, stale: bool) -> Self {
+        if stale && !self.paused {
+            self.stale = true;
+            self.style = self.style.map(|style| style.add_modifier(Modifier::DIM));
+        }
+        self
+    }
+}
 
 pub trait Draw {
-    fn draw(&self, frame: &mut Frame, area: Rect, style: Option<Style>);
+    fn draw(&self, config: &AppConfig, frame: &mut Frame, area: Rect, options: PanelOptions);
 }
 
 pub trait DrawStatus {
-    fn draw_status(&self, frame: &mut Frame, area: Rect);
+    fn draw_status(&self, config: &AppConfig, frame: &mut Frame, area: Rect);
 }
 
 pub fn render(config: &AppConfig, state: &AppState, frame: &mut Frame) {
     let node_state = state.node.clone().unwrap_or_default();
-    let node = node_state.lock().unwrap();
-    let status_style = get_status_style(&node.status);
+    let live_node = node_state.lock().unwrap();
+    let status_style = get_status_style(&live_node.status);
+
+    let panel_options = |panel: Panel| PanelOptions {
+        style: Some(status_style),
+        focused: state.focus == panel,
+        paused: state.paused.is_paused(panel),
+        accent: None,
+        stale: false,
+    };
+
+    // Explorer data stands in for an offline node, clearly marked
+    let external_node = match (&state.explorer, live_node.status) {
+        (Some(tip), NodeStatus::Offline | NodeStatus::Starting) => Some(NodeState {
+            height: tip.height,
+            headers: tip.height,
+            last_hash: tip.hash.clone(),
+            last_hash_time: tip.time,
+            last_hash_instant: None,
+            ..live_node.clone()
+        }),
+        _ => None,
+    };
+    // Values of the last session stand in, greyed out, until each source answers
+    let restored = state.restored.as_ref();
+    let restored_node = restored
+        .and_then(|restored| restored.node.as_ref())
+        .filter(|_| live_node.last_hash.is_empty() && external_node.is_none());
+    let restored_price = restored
+        .and_then(|restored| restored.price.as_ref())
+        .filter(|_| state.price.last_price_in_currency.is_none())
+        .filter(|_| state.shown_currency == state.price.currency);
+    let restored_fees = restored
+        .and_then(|restored| restored.fees.as_ref())
+        .filter(|_| state.fees.result.rates.is_empty());
+
+    let node = NodePanel {
+        node: state
+            .paused
+            .node
+            .as_ref()
+            .or(external_node.as_ref())
+            .or(restored_node)
+            .unwrap_or(&live_node),
+        disk: &state.disk,
+        external: state.paused.node.is_none() && external_node.is_some(),
+        restored: restored
+            .filter(|_| state.paused.node.is_none() && restored_node.is_some())
+            .map(|restored| restored.age()),
+    };
+    let peers = PeersPanel {
+        node: state.paused.peers.as_ref().unwrap_or(&live_node),
+        view: &state.peers,
+    };
+    let fees = state
+        .paused
+        .fees
+        .as_ref()
+        .or(restored_fees)
+        .unwrap_or(&state.fees);
+    let main_price = state
+        .paused
+        .price
+        .as_ref()
+        .or(restored_price)
+        .unwrap_or(&state.price);
+    // Badges stay on the main currency whichever one the panel shows
+    let price = match state.shown_currency == state.price.currency {
+        true => main_price,
+        false => state.shown_price(),
+    };
+    let price_panel = PricePanel {
+        price,
+        in_sats: state.price_in_sats,
+        // Alerts watch the main currency
+        alerts: match price.currency == state.price.currency {
+            true => &state.price_alerts,
+            false => &[],
+        },
+        annotations: &state.annotations,
+    };
+    let fees_panel = FeesPanel {
+        fees,
+        price: config.price.enabled.then_some(price),
+        min_fee: live_node
+            .mempool_min_fee
+            .filter(|_| config.fees.check_min_fee),
+        annotations: &state.annotations,
+    };
+    let mempool = MempoolPanel {
+        mempool: state
+            .paused
+            .mempool
+            .as_ref()
+            .or(restored_fees)
+            .unwrap_or(&state.fees)
+            .mempool
+            .as_ref(),
+    };
+    let next_block = NextBlockPanel {
+        next_block: state
+            .paused
+            .next_block
+            .as_ref()
+            .or(restored_fees)
+            .unwrap_or(&state.fees)
+            .next_block
+            .as_ref(),
+    };
+    let session = state.paused.session.as_ref().unwrap_or(&state.session);
+    let script = state.paused.custom.as_ref().unwrap_or(&state.script);
+
+    let mut bottom_widgets: Vec<(Panel, &dyn Draw, u16)> = vec![];
+    let shown = |panel: Panel| {
+        state
+            .rotation
+            .current
+            .is_none_or(|current| current == panel)
+    };
+
+    if config.fees.enabled && shown(Panel::Fees) {
+        bottom_widgets.push((Panel::Fees, &fees_panel, 2));
+    }
+
+    if config.fees.enabled && config.mempool.enabled && shown(Panel::Mempool) {
+        bottom_widgets.push((Panel::Mempool, &mempool, 2));
+    }
+
+    if config.fees.enabled && config.mempool.next_block && shown(Panel::NextBlock) {
+        bottom_widgets.push((Panel::NextBlock, &next_block, 2));
+    }
+
+    if config.price.enabled && shown(Panel::Price) {
+        bottom_widgets.push((Panel::Price, &price_panel, 3));
+    }
+
+    if config.session.enabled && shown(Panel::Session) {
+        bottom_widgets.push((Panel::Session, session, 2));
+    }
+
+    if !config.script.path.is_empty() && shown(Panel::Custom) {
+        bottom_widgets.push((Panel::Custom, script, 2));
+    }
 
     let (layout_constraints, status_panel_i): (Vec<Constraint>, usize) =
-        if config.price.enabled | config.fees.enabled {
+        if !bottom_widgets.is_empty() {
             (
                 vec![
                     Constraint::Length(frame.size().height / 2),
@@ -54,33 +294,296 @@ pub fn render(config: &AppConfig, state: &AppState, frame: &mut Frame) {
     let bottom_panel = &main_layout[1];
     let bottom_panel_layout = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints(vec![Constraint::Percentage(40), Constraint::Percentage(60)])
+        .constraints(
+            bottom_widgets
+                .iter()
+                .map(|(_, _, weight)| Constraint::Fill(*weight)),
+        )
         .split(*bottom_panel);
 
-    match (config.price.enabled, config.fees.enabled) {
-        (true, true) => {
-            let bottom_panel_left = &bottom_panel_layout[0];
-            let bottom_panel_right = &bottom_panel_layout[1];
+    for (i, (panel, widget, _)) in bottom_widgets.iter().enumerate() {
+        let source = match panel {
+            Panel::Fees | Panel::Mempool | Panel::NextBlock => {
+                Some((&fees.health, config.stale_after(config.fees_interval())))
+            }
+            Panel::Price => Some((
+                &price.health,
+                config.stale_after(crate::price::CHECK_INTERVAL),
+            )),
+            _ => None,
+        };
+        let health = source.map(|(health, stale_after)| health.health(stale_after));
+
+        let restored = match panel {
+            Panel::Fees | Panel::Mempool | Panel::NextBlock => restored_fees.is_some(),
+            Panel::Price => restored_price.is_some(),
+            _ => false,
+        };
+
+        let options = PanelOptions {
+            style: match restored {
+                true => Some(RESTORED_STYLE),
+                false => health
+                    .map(|health| get_health_style(&health))
+                    .or(Some(status_style)),
+            },
+            ..panel_options(*panel)
+        }
+        .stale(!restored && source.is_some_and(|(health, after)| health.is_stale(after)));
+
+        widget.draw(config, frame, bottom_panel_layout[i], options);
+    }
+
+    let node_stale = live_node
+        .health
+        .is_stale(config.stale_after(providers::poll_interval(config)));
+
+    let node_area = if config.peers.enabled {
+        let top_panel_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(6), Constraint::Fill(1)])
+            .split(*top_panel);
+
+        peers.draw(
+            config,
+            frame,
+            top_panel_layout[1],
+            panel_options(Panel::Peers).stale(node_stale),
+        );
+        top_panel_layout[0]
+    } else {
+        *top_panel
+    };
 
+    let node_options = PanelOptions {
+        style: node.restored.map(|_| RESTORED_STYLE).or(Some(status_style)),
+        accent: config.node_accent(),
+        ..panel_options(Panel::Node)
+    }
+    .stale(node.restored.is_none() && !node.external && node_stale);
+
+    if config.host.enabled {
+        let [node_area, host_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(host::WIDTH)])
+                .areas(node_area);
+        let stale_after = config.stale_after(crate::host::CHECK_INTERVAL);
+        let options = PanelOptions {
+            style: Some(get_health_style(&state.host.health.health(stale_after))),
+            ..PanelOptions::default()
+        }
+        .stale(state.host.health.is_stale(stale_after));
+
+        node.draw(config, frame, node_area, node_options);
+        state.host.draw(config, frame, host_area, options);
+    } else {
+        node.draw(config, frame, node_area, node_options);
+    }
+
+    // Config defined labels shown while their condition holds
+    let test_notification = state
+        .test_notification
+        .filter(|(_, at)| at.elapsed().as_secs() < 5)
+        .map(|(sent, _)| match sent {
+            true => ("TEST PUSH SENT", Color::Green),
+            false => ("TEST PUSH FAILED", Color::Red),
+        })
+        .map(|(label, color)| (label.to_string(), color));
+    // Why the last key did nothing
+    let notice = state
+        .notice
+        .as_ref()
+        .filter(|(_, at)| at.elapsed().as_secs() < 5)
+        .map(|(notice, _)| (notice.clone(), Color::Yellow));
+    // Projected days until the disk limit, once under the configured warning window
+    let disk_warning = state
+        .disk
+        .days_left(config.disk.limit_gb * crate::disk::GB)
+        .filter(|days| *days < config.disk.warn_days as f64)
+        .map(|days| {
+            let color = if days < 7.0 {
+                Color::Red
+            } else {
+                Color::Yellow
+            };
+            (format!("DISK {:.0}d", days), color)
+        });
+    // Pushes held back by a scheduled quiet window
+    let quiet = config
+        .locale()
+        .to_datetime(Utc::now().timestamp())
+        .filter(|now| state.quiet.iter().any(|window| window.is_open(now)))
+        .map(|_| ("QUIET".to_string(), Color::DarkGray));
+    let active_badges: Vec<Span> = test_notification
+        .into_iter()
+        .chain(notice)
+        .chain(quiet)
+        .chain(disk_warning)
+        .chain(
             state
-                .price
-                .draw(frame, *bottom_panel_right, Some(status_style));
+                .badges
+                .iter()
+                .filter(|badge| badge.is_active(&live_node, fees, main_price))
+                .map(|badge| (badge.label.clone(), Color::Yellow)),
+        )
+        .flat_map(|(label, color)| {
+            [
+                Span::styled(
+                    format!(" {} ", label),
+                    Style::new().fg(Color::Black).bg(color),
+                ),
+                Span::raw(" "),
+            ]
+        })
+        .collect();
+    let badges_width = active_badges.iter().map(|span| span.width() as u16).sum();
+
+    let [status_panel, badges_panel] =
+        Layout::horizontal([Constraint::Fill(1), Constraint::Length(badges_width)])
+            .areas(*status_panel);
+
+    live_node.draw_status(config, frame, status_panel);
+    frame.render_widget(
+        Paragraph::new(Line::from(active_badges)).style(Style::new().bg(Color::Black)),
+        badges_panel,
+    );
+
+    state.banner.draw(frame);
+
+    match state.detail {
+        Some(DetailView::BlockHeatmap) => BlockHeatmap {
+            node: &live_node,
+            annotations: &state.annotations,
+        }
+        .draw(config, frame),
+        Some(DetailView::DifficultyEpochs) => {
+            DifficultyEpochs { node: &live_node }.draw(config, frame)
+        }
+        Some(DetailView::SinceLastSeen) => {
+            if let Some(since_last_seen) = &state.since_last_seen {
+                since_last_seen.draw(config, frame);
+            }
+        }
+        Some(DetailView::DaySummary) => {
+            if let Some(day) = &state.day_summary {
+                day.draw(config, frame);
+            }
+        }
+        Some(DetailView::Annotations) => AnnotationList {
+            annotations: &state.annotations,
+        }
+        .draw(config, frame),
+        Some(DetailView::Preflight) => PreflightResults {
+            checks: state.preflight.as_deref(),
+        }
+        .draw(config, frame),
+        None => {}
+    }
 
+    if let Some((alert, price, crossed_at)) = state.price_alert {
+        if crossed_at.elapsed().as_secs() < 15 {
             state
-                .fees
-                .draw(frame, *bottom_panel_left, Some(status_style));
+                .price
+                .draw_price_alert_popup(config, frame, alert, price);
         }
-        (true, false) => {
-            state.price.draw(frame, *bottom_panel, Some(status_style));
+    }
+
+    if let Some(input) = &state.note_input {
+        draw_note_input(frame, input, live_node.height);
+    }
+
+    if let Some(input) = &state.mine_input {
+        draw_mine_input(frame, input);
+    }
+
+    if let Some(input) = &state.peer_input {
+        draw_peer_input(frame, input);
+    }
+
+    if let Some(action) = &state.pending_action {
+        draw_confirmation_popup(frame, action);
+    }
+
+    if state.splash.is_some() {
+        Splash {
+            warnings: &state.warnings,
         }
-        (false, true) => {
-            state.fees.draw(frame, *bottom_panel, Some(status_style));
+        .draw(config, frame);
+    }
+
+    if state.diagnostics.visible {
+        DiagnosticsOverlay {
+            state,
+            node: &live_node,
         }
-        _ => {}
+        .draw(config, frame);
     }
+}
+
+fn draw_mine_input(frame: &mut Frame, input: &str) {
+    let sized_paragraph = SizedWrapper {
+        inner: Paragraph::new(vec![
+            Line::from(""),
+            Line::from(format!("Blocks: {}_", input)),
+            Line::from(""),
+            Line::from("[Enter] mine  [Esc] cancel"),
+        ])
+        .centered(),
+        width: 28,
+        height: 4,
+    };
 
-    node.draw(frame, *top_panel, Some(status_style));
-    node.draw_status(frame, *status_panel);
+    let popup =
+        Popup::new(" Mine ", sized_paragraph).style(Style::new().fg(Color::White).bg(Color::Black));
+    frame.render_widget(&popup, frame.size());
+}
+
+fn draw_peer_input(frame: &mut Frame, input: &str) {
+    let sized_paragraph = SizedWrapper {
+        inner: Paragraph::new(vec![
+            Line::from(""),
+            Line::from(format!("Address: {}_", input)),
+            Line::from(""),
+            Line::from("[Enter] connect  [Esc] cancel"),
+        ])
+        .centered(),
+        width: input.len().max(20) + 12,
+        height: 4,
+    };
+
+    let popup = Popup::new(" Add peer ", sized_paragraph)
+        .style(Style::new().fg(Color::White).bg(Color::Black));
+    frame.render_widget(&popup, frame.size());
+}
+
+fn draw_confirmation_popup(frame: &mut Frame, action: &PendingAction) {
+    let width = action.description.len().max(20) as u16 + 2;
+    let sized_paragraph = SizedWrapper {
+        inner: Paragraph::new(vec![
+            Line::from(""),
+            Line::from(action.description.as_str()),
+            Line::from(""),
+            Line::from("[y] confirm  [n] cancel"),
+        ])
+        .centered(),
+        width: width as usize,
+        height: 4,
+    };
+
+    let popup = Popup::new(" Confirm ", sized_paragraph)
+        .style(Style::new().fg(Color::White).bg(Color::Black));
+    frame.render_widget(&popup, frame.size());
+}
+
+/// Values of the last session, until the source answers.
+const RESTORED_STYLE: Style = Style::new().fg(Color::DarkGray).bg(Color::Black);
+
+/// Same palette as the node status, for panels fed by a polled source.
+pub fn get_health_style(health: &DataHealth) -> Style {
+    match health {
+        DataHealth::Fresh => Style::default().fg(Color::Green).bg(Color::Black),
+        DataHealth::Stale => Style::default().fg(Color::Yellow).bg(Color::Black),
+        DataHealth::Failing => Style::default().fg(Color::Red).bg(Color::Black),
+    }
 }
 
 pub fn get_status_style(status: &NodeStatus) -> Style {
@@ -88,5 +591,6 @@ pub fn get_status_style(status: &NodeStatus) -> Style {
         NodeStatus::Online => Style::default().fg(Color::Green).bg(Color::Black),
         NodeStatus::Offline => Style::default().fg(Color::Red).bg(Color::Black),
         NodeStatus::Synchronizing => Style::default().fg(Color::Blue).bg(Color::Black),
+        NodeStatus::Starting => Style::default().fg(Color::Yellow).bg(Color::Black),
     }
 }
@@ -0,0 +1,109 @@
+use ratatui::{
+    layout::Rect,
+    prelude::Stylize,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Padding, Paragraph},
+};
+
+use crate::config::AppConfig;
+use crate::fees::ProjectedBlock;
+use crate::format::Locale;
+
+use super::{Draw, PanelOptions};
+
+/// Names of the points in a mempool.space fee range.
+const PERCENTILES: [&str; 7] = ["min", "10%", "25%", "50%", "75%", "90%", "max"];
+
+/// Eighths of a cell, for bars that don't end on a cell boundary.
+const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// What the projected next block pays, one bar per point of its fee range.
+pub struct NextBlockPanel<'a> {
+    pub next_block: Option<&'a ProjectedBlock>,
+}
+
+impl Draw for NextBlockPanel<'_> {
+    fn draw(
+        &self,
+        config: &AppConfig,
+        frame: &mut ratatui::Frame,
+        area: Rect,
+        options: PanelOptions,
+    ) {
+        let style = options.style.unwrap_or_default();
+        let locale = config.locale();
+
+        let lines = match self.next_block {
+            Some(block) if !block.fee_range.is_empty() => {
+                // Label, value and the padding and borders around them
+                let width = area.width.saturating_sub(18) as usize;
+                let max = block.fee_range.iter().cloned().fold(0.0, f64::max);
+
+                let mut lines = vec![Line::from(vec![
+                    Span::raw("Median: "),
+                    Span::styled(
+                        format!("{} Sats/vbyte", format_rate(&locale, block.median_fee)),
+                        Style::new().white().italic(),
+                    ),
+                    Span::styled(
+                        format!(" {} txs", locale.format_number(block.count as f64, 0)),
+                        Style::new().dark_gray().italic(),
+                    ),
+                ])];
+
+                lines.extend(block.fee_range.iter().enumerate().map(|(i, rate)| {
+                    let label = match block.fee_range.len() {
+                        7 => PERCENTILES[i],
+                        _ => "",
+                    };
+
+                    Line::from(vec![
+                        Span::raw(format!("{:>4} ", label)),
+                        Span::styled(bar(*rate, max, width), Style::new().white()),
+                        Span::styled(
+                            format!(" {}", format_rate(&locale, *rate)),
+                            Style::new().white().italic(),
+                        ),
+                    ])
+                }));
+
+                lines
+            }
+            _ => vec![Line::from(vec![
+                Span::raw("Median: "),
+                Span::styled("...", Style::new().white().italic()),
+            ])],
+        };
+
+        let next_block = Paragraph::new(lines)
+            .block(options.block("Next block").padding(Padding::left(1)))
+            .style(style);
+
+        frame.render_widget(next_block, area);
+    }
+}
+
+/// Bar for `rate` out of `width` cells. Fee ranges are skewed, a few transactions pay
+/// many times the rest, so the scale is logarithmic to keep the low end readable.
+fn bar(rate: f64, max: f64, width: usize) -> String {
+    if max <= 0.0 {
+        return String::new();
+    }
+
+    let eighths = ((rate.max(0.0).ln_1p() / max.ln_1p()) * (width * 8) as f64).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+
+    if let partial @ 1.. = eighths % 8 {
+        bar.push(PARTIAL_BLOCKS[partial]);
+    }
+
+    bar
+}
+
+fn format_rate(locale: &Locale, rate: f64) -> String {
+    match rate < 10.0 {
+        true => locale.format_number(rate, 1),
+        false => locale.format_number(rate, 0),
+    }
+}
@@ -1,17 +1,21 @@
-use ratatui::layout::{Constraint, Direction, Layout};
+use chrono::Utc;
+use ratatui::layout::{Alignment, Constraint, Direction, Layout};
 use ratatui::prelude::Stylize;
 use ratatui::Frame;
 use ratatui::{
-    layout::{Alignment, Rect},
+    layout::Rect,
     style::{Color, Style},
     text::{Line, Span},
-    widgets::{Block, BorderType, Padding, Paragraph},
+    widgets::{block::Title, Block, Padding, Paragraph},
 };
 use tui_popup::{Popup, SizedWrapper};
 
+use crate::config::AppConfig;
+use crate::disk::{DiskGrowth, GB};
+use crate::format::{format_bytes, format_elapsed};
 use crate::node::{NodeState, NodeStatus};
 
-use super::{get_status_style, Draw, DrawStatus};
+use super::{get_status_style, Draw, DrawStatus, PanelOptions};
 
 impl NodeState {
     fn draw_new_block_popup(&self, frame: &mut Frame, block_height: u64) {
@@ -33,19 +37,49 @@ impl NodeState {
     }
 }
 
+/// Node panel, with the disk usage history kept by the app next to the node state.
+pub struct NodePanel<'a> {
+    pub node: &'a NodeState,
+    pub disk: &'a DiskGrowth,
+    /// Height and last block come from the explorer, the node is offline.
+    pub external: bool,
+    /// Seconds since the snapshot shown was taken, the node has not answered yet.
+    pub restored: Option<i64>,
+}
+
 impl DrawStatus for NodeState {
-    fn draw_status(&self, frame: &mut Frame, area: Rect) {
-        let zmq_status_width = 12;
+    fn draw_status(&self, config: &AppConfig, frame: &mut Frame, area: Rect) {
+        // Only present when built with the zmq feature, one entry per watched topic
+        let mut zmq_topics: Vec<String> = self
+            .services
+            .iter()
+            .filter_map(|(service, status)| {
+                service
+                    .strip_prefix("ZMQ-")
+                    .map(|topic| format!("{} {:?}", topic, status))
+            })
+            .collect();
+        zmq_topics.sort();
+
+        let zmq_status = match zmq_topics.is_empty() {
+            true => "".to_string(),
+            false => format!("ZMQ {} ", zmq_topics.join(" · ")),
+        };
+
+        let zmq_status_width = (zmq_status.chars().count() as u16).min(area.width / 2);
         let status_bar_layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints(vec![
                 Constraint::Length(1),
-                Constraint::Length(frame.size().width - zmq_status_width - 1),
+                Constraint::Length(area.width.saturating_sub(zmq_status_width + 1)),
                 Constraint::Length(zmq_status_width),
             ])
             .split(area);
 
-        if self.status == NodeStatus::Synchronizing {
+        if matches!(
+            self.status,
+            NodeStatus::Synchronizing | NodeStatus::Starting
+        ) {
             let throbber = throbber_widgets_tui::Throbber::default()
                 .throbber_set(throbber_widgets_tui::QUADRANT_BLOCK_CRACK);
             frame.render_widget(throbber, status_bar_layout[0]);
@@ -57,78 +91,194 @@ impl DrawStatus for NodeState {
         }
 
         frame.render_widget(
-            Paragraph::new(format!("Node {}", self.status))
-                .block(Block::new().padding(Padding::left(1)))
-                .style(Style::default().fg(Color::White).bg(Color::Black)),
+            Paragraph::new(Line::from(vec![
+                Span::styled(
+                    "Node",
+                    config
+                        .node_accent()
+                        .map_or(Style::new(), |accent| Style::new().fg(accent).bold()),
+                ),
+                Span::raw(format!(" {}", self.status)),
+            ]))
+            .block(Block::new().padding(Padding::left(1)))
+            .style(Style::default().fg(Color::White).bg(Color::Black)),
             status_bar_layout[1],
         );
 
         frame.render_widget(
-            Paragraph::new(format!(
-                "ZMQ {:?} ",
-                self.services.get("ZMQ").unwrap_or(&NodeStatus::Offline)
-            ))
-            .style(Style::default().fg(Color::White).bg(Color::Black))
-            .right_aligned(),
+            Paragraph::new(zmq_status)
+                .style(Style::default().fg(Color::White).bg(Color::Black))
+                .right_aligned(),
             status_bar_layout[2],
         );
     }
 }
 
-impl Draw for NodeState {
-    fn draw(&self, frame: &mut ratatui::Frame, area: Rect, style: Option<Style>) {
-        let style = style.unwrap_or(get_status_style(&self.status));
+impl NodePanel<'_> {
+    /// Size, daily growth and days left until `disk.limit_gb` when it is set.
+    fn disk_line(&self, config: &AppConfig) -> Option<Line<'static>> {
+        let size = self.node.size_on_disk?;
+        let mut spans = vec![
+            Span::raw("Disk: "),
+            Span::styled(format_bytes(size), Style::new().fg(Color::White).italic()),
+        ];
+
+        if let Some(rate) = self.disk.bytes_per_day() {
+            let sign = if rate < 0.0 { "-" } else { "+" };
+            spans.push(Span::styled(
+                format!(" ({}{}/day)", sign, format_bytes(rate.abs() as u64)),
+                Style::new().fg(Color::Blue).italic(),
+            ));
+        }
 
-        let block_height = match self.status {
+        if let Some(days) = self.disk.days_left(config.disk.limit_gb * GB) {
+            let color = match days < config.disk.warn_days as f64 {
+                true => Color::Yellow,
+                false => Color::White,
+            };
+            spans.push(Span::styled(
+                format!(", {:.0} days to {} GB", days, config.disk.limit_gb),
+                Style::new().fg(color).italic(),
+            ));
+        }
+
+        Some(Line::from(spans))
+    }
+}
+
+impl Draw for NodePanel<'_> {
+    fn draw(
+        &self,
+        config: &AppConfig,
+        frame: &mut ratatui::Frame,
+        area: Rect,
+        options: PanelOptions,
+    ) {
+        let style = options.style.unwrap_or(get_status_style(&self.node.status));
+        let locale = config.locale();
+
+        let block_height = match self.node.status {
             NodeStatus::Synchronizing => Line::from(vec![
                 Span::raw("Block Height: "),
                 Span::styled(
-                    self.height.to_string(),
+                    self.node.height.to_string(),
                     Style::new().fg(Color::White).italic(),
                 ),
                 Span::raw("/"),
                 Span::styled(
-                    self.headers.to_string(),
+                    self.node.headers.to_string(),
                     Style::new().fg(Color::Blue).italic(),
                 ),
             ]),
-            _ => Line::from(vec![
-                Span::raw("Block Height: "),
-                Span::styled(
-                    self.height.to_string(),
-                    Style::new().fg(Color::White).italic(),
-                ),
-            ]),
+            _ => {
+                let mut spans = vec![
+                    Span::raw("Block Height: "),
+                    Span::styled(
+                        self.node.height.to_string(),
+                        Style::new().fg(Color::White).italic(),
+                    ),
+                ];
+
+                // Lag within the sync tolerance, not enough to change the status
+                if self.node.headers > self.node.height {
+                    spans.push(Span::styled(
+                        format!(" ({} behind)", self.node.headers - self.node.height),
+                        Style::new().fg(Color::Blue).italic(),
+                    ));
+                }
+
+                Line::from(spans)
+            }
         };
 
-        let text: Vec<Line> = vec![
-            block_height,
+        let block_time = match self.node.last_hash_time {
+            Some(time) => [
+                locale.format_timestamp(time),
+                format!("({} ago)", format_elapsed(Utc::now().timestamp() - time)),
+            ]
+            .join(" "),
+            None => "-".to_string(),
+        };
+
+        let mut text: Vec<Line> = vec![block_height];
+
+        if let (NodeStatus::Starting, Some(starting)) = (self.node.status, &self.node.starting) {
+            text.push(Line::from(vec![
+                Span::raw("Starting: "),
+                Span::styled(starting.clone(), Style::new().fg(Color::Yellow).italic()),
+            ]));
+        }
+
+        if let Some(label) = &self.node.label {
+            text.push(Line::from(vec![
+                Span::raw("Label: "),
+                Span::styled(label.clone(), Style::new().fg(Color::White).italic()),
+            ]));
+        }
+
+        if let Some(policy) = &self.node.policy {
+            text.push(Line::from(vec![
+                Span::raw("Policy: "),
+                Span::styled(policy.clone(), Style::new().fg(Color::White).italic()),
+            ]));
+        }
+
+        text.extend(vec![
             Line::from(vec![
                 Span::raw("Last Block: "),
                 Span::styled(
-                    self.last_hash.clone(),
+                    self.node.last_hash.clone(),
                     Style::new().fg(Color::White).italic(),
                 ),
             ]),
-            "------".into(),
-        ];
+            Line::from(vec![
+                Span::raw("Block Time: "),
+                Span::styled(block_time, Style::new().fg(Color::White).italic()),
+            ]),
+        ]);
 
-        frame.render_widget(
-            Paragraph::new(text)
-                .block(
-                    Block::bordered()
-                        .padding(Padding::left(1))
-                        .title("Bitcoin Core")
-                        .title_alignment(Alignment::Center)
-                        .border_type(BorderType::Plain),
-                )
-                .style(style),
-            area,
-        );
+        text.extend(self.disk_line(config));
+        text.push("------".into());
+
+        let mut block = options
+            .block(self.node.name.as_str())
+            .padding(Padding::left(1));
+
+        if self.external {
+            block = block.title(
+                Title::from(Span::styled(
+                    " external data ",
+                    Style::new().fg(Color::Black).bg(Color::Yellow),
+                ))
+                .alignment(Alignment::Right),
+            );
+        }
+
+        if let Some(age) = self.restored {
+            block = block.title(
+                Title::from(Span::styled(
+                    format!(" last session, {} ", format_elapsed(age)),
+                    Style::new().fg(Color::Black).bg(Color::DarkGray),
+                ))
+                .alignment(Alignment::Right),
+            );
+        }
+
+        if let Some(implementation) = &self.node.implementation {
+            block = block.title(
+                Title::from(Span::styled(
+                    format!(" {} ", implementation),
+                    Style::new().reversed(),
+                ))
+                .alignment(Alignment::Left),
+            );
+        }
+
+        frame.render_widget(Paragraph::new(text).block(block).style(style), area);
 
-        if let Some(time) = self.last_hash_instant {
-            if time.elapsed().as_secs() < 15 && self.status == NodeStatus::Online {
-                self.draw_new_block_popup(frame, self.height);
+        if let Some(time) = self.node.last_hash_instant {
+            if time.elapsed().as_secs() < 15 && self.node.status == NodeStatus::Online {
+                self.node.draw_new_block_popup(frame, self.node.height);
             }
         }
     }
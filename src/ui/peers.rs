@@ -0,0 +1,86 @@
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    widgets::Row,
+    Frame,
+};
+
+use crate::app::PeersView;
+use crate::config::AppConfig;
+use crate::node::widgets::VirtualList;
+use crate::node::NodeState;
+
+use super::{Draw, PanelOptions};
+
+/// Connected peers or the ban list of a node, whichever the view selects.
+pub struct PeersPanel<'a> {
+    pub node: &'a NodeState,
+    pub view: &'a PeersView,
+}
+
+impl Draw for PeersPanel<'_> {
+    fn draw(&self, config: &AppConfig, frame: &mut Frame, area: Rect, options: PanelOptions) {
+        let style = options.style.unwrap_or_default();
+        let locale = config.locale();
+        let mut list_state = self.view.list.get();
+
+        let title = format!(
+            "Peers ({}) · Banned ({})",
+            self.node.peers.len(),
+            self.node.banned.len()
+        );
+
+        let highlight_style = Style::new().add_modifier(Modifier::REVERSED);
+
+        if self.view.banned {
+            let list = VirtualList::new(
+                self.node.banned.len(),
+                [Constraint::Fill(1), Constraint::Length(20)],
+                |i| {
+                    let ban = &self.node.banned[i];
+                    Row::new(vec![
+                        ban.address.clone(),
+                        locale.format_timestamp(ban.banned_until),
+                    ])
+                },
+            )
+            .header(Row::new(vec!["Address", "Banned until"]))
+            .block(options.block(title))
+            .style(style)
+            .highlight_style(highlight_style);
+
+            frame.render_stateful_widget(list, area, &mut list_state);
+        } else {
+            let list = VirtualList::new(
+                self.node.peers.len(),
+                [
+                    Constraint::Length(5),
+                    Constraint::Fill(2),
+                    Constraint::Fill(2),
+                    Constraint::Length(4),
+                    Constraint::Length(8),
+                ],
+                |i| {
+                    let peer = &self.node.peers[i];
+                    Row::new(vec![
+                        peer.id.to_string(),
+                        peer.address.clone(),
+                        peer.client.clone(),
+                        if peer.inbound { "in" } else { "out" }.to_string(),
+                        peer.ping.map_or("-".to_string(), |ping| {
+                            format!("{}ms", locale.format_number(ping * 1000.0, 0))
+                        }),
+                    ])
+                },
+            )
+            .header(Row::new(vec!["Id", "Address", "Client", "Dir", "Ping"]))
+            .block(options.block(title))
+            .style(style)
+            .highlight_style(highlight_style);
+
+            frame.render_stateful_widget(list, area, &mut list_state);
+        }
+
+        self.view.list.set(list_state);
+    }
+}
@@ -0,0 +1,58 @@
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use tui_popup::{Popup, SizedWrapper};
+
+use crate::config::AppConfig;
+use crate::node::providers::target;
+use crate::preflight::Check;
+
+/// Connection test of the configured node, step by step.
+pub struct PreflightResults<'a> {
+    /// None while the checks are still running.
+    pub checks: Option<&'a [Check]>,
+}
+
+impl PreflightResults<'_> {
+    pub fn draw(&self, config: &AppConfig, frame: &mut Frame) {
+        let mut lines = vec![
+            Line::from(Span::styled(
+                format!("{} at {}", config.node.provider, target(config)),
+                Style::new().fg(Color::DarkGray),
+            )),
+            Line::from(""),
+        ];
+
+        match self.checks {
+            Some(checks) => lines.extend(checks.iter().map(|check| {
+                let color = match check.result {
+                    Ok(_) => Color::Green,
+                    Err(_) => Color::Red,
+                };
+                Line::from(Span::styled(check.to_string(), Style::new().fg(color)))
+            })),
+            None => lines.push(Line::from("Running checks...")),
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from("[T] run again  [Esc] close").centered());
+
+        let width = lines.iter().map(|line| line.width()).max().unwrap_or(0);
+        let height = lines.len();
+
+        let sized_paragraph = SizedWrapper {
+            inner: Paragraph::new(lines),
+            width: width
+                .max(40)
+                .min(frame.size().width.saturating_sub(4) as usize),
+            height,
+        };
+
+        let popup = Popup::new(" Connection test ", sized_paragraph)
+            .style(Style::new().fg(Color::White).bg(Color::Black));
+        frame.render_widget(&popup, frame.size());
+    }
+}
@@ -1,53 +1,374 @@
 use ratatui::{
-    layout::{Alignment, Rect},
+    layout::{Alignment, Constraint, Layout, Margin, Rect},
     style::{Color, Style},
-    widgets::{Block, BorderType, Padding, Paragraph},
+    text::{Line, Span},
+    widgets::{block::Title, Padding, Paragraph},
+    Frame,
 };
 use tui_big_text::{BigText, PixelSize};
+use tui_popup::{Popup, SizedWrapper};
 
-use crate::price::PriceState;
+use crate::annotations::{sample_marks, Annotation};
+use crate::config::AppConfig;
+use crate::price::{PriceAlert, PriceState, CHECK_INTERVAL};
 
-use super::Draw;
+use super::widgets::Sparkline;
+use super::{get_health_style, Draw, PanelOptions};
 
-impl Draw for PriceState {
-    fn draw(&self, frame: &mut ratatui::Frame, area: Rect, style: Option<Style>) {
-        let style = style.unwrap_or(Style::new());
+/// Big text sizes by `price.pixel_size` name, from the biggest glyphs to the smallest.
+/// When the configured one doesn't fit, the next ones are tried before plain text.
+pub const PIXEL_SIZES: [(&str, PixelSize); 6] = [
+    ("full", PixelSize::Full),
+    ("half_height", PixelSize::HalfHeight),
+    ("half_width", PixelSize::HalfWidth),
+    ("third_height", PixelSize::ThirdHeight),
+    ("quadrant", PixelSize::Quadrant),
+    ("sextant", PixelSize::Sextant),
+];
 
-        let lines = vec![match self.last_price_in_currency {
-            Some(v) => vec![v.trunc().to_string(), self.currency.to_string()]
-                .join(" ")
-                .into(),
-            None => "...".into(),
-        }];
+/// Cells one 8x8 glyph takes at `size`, wide and high.
+fn glyph_cells(size: PixelSize) -> (u16, u16) {
+    let (per_column, per_row) = match size {
+        PixelSize::Full => (1, 1),
+        PixelSize::HalfHeight => (1, 2),
+        PixelSize::HalfWidth => (2, 1),
+        PixelSize::Quadrant => (2, 2),
+        PixelSize::ThirdHeight => (1, 3),
+        PixelSize::Sextant => (2, 3),
+    };
 
-        let price_block = Block::bordered()
-            .padding(Padding::top(1))
-            .title("Price")
-            .title_alignment(Alignment::Center)
-            .border_type(BorderType::Plain)
-            .style(style);
+    (8_u16.div_ceil(per_column), 8_u16.div_ceil(per_row))
+}
+
+/// Configured size, or the first smaller one `text` fits `area` at.
+fn fitting_pixel_size(config: &AppConfig, text: &str, area: Rect) -> Option<PixelSize> {
+    let start = PIXEL_SIZES
+        .iter()
+        .position(|(name, _)| *name == config.price.pixel_size)
+        .unwrap_or(PIXEL_SIZES.len() - 1);
+    let chars = Line::from(text).width() as u16;
+
+    PIXEL_SIZES[start..]
+        .iter()
+        .map(|(_, size)| *size)
+        .find(|size| {
+            let (width, height) = glyph_cells(*size);
+            chars * width <= area.width && height <= area.height
+        })
+}
+
+/// Price panel, in the currency or in how many sats one unit of it buys.
+pub struct PricePanel<'a> {
+    pub price: &'a PriceState,
+    pub in_sats: bool,
+    /// Levels still armed, listed under the price.
+    pub alerts: &'a [PriceAlert],
+    /// Notes marked on the sparkline.
+    pub annotations: &'a [Annotation],
+}
+
+impl PriceState {
+    /// Symbol placed as the locale does, or the ISO code trailing when symbols are off.
+    pub fn format_price(&self, config: &AppConfig, value: f64, decimals: usize) -> String {
+        let locale = config.locale();
+
+        match config.price.symbol {
+            true => locale.format_currency(value, decimals, self.currency.symbol()),
+            false => [
+                locale.format_number(value, decimals),
+                self.currency.to_string(),
+            ]
+            .join(" "),
+        }
+    }
+
+    /// Sats one unit of the currency buys, e.g. "1,063 sats/$". Decimals only when a unit
+    /// buys few of them, like the yen.
+    pub fn format_sats(&self, config: &AppConfig, price: f64) -> String {
+        let sats = 100_000_000.0 / price;
+        let decimals = match sats >= 100.0 {
+            true => 0,
+            false => 2,
+        };
+        let unit = match config.price.symbol {
+            true => self.currency.symbol().to_string(),
+            false => self.currency.to_string(),
+        };
+
+        format!(
+            "{} sats/{}",
+            config.locale().format_number(sats, decimals),
+            unit
+        )
+    }
+
+    fn draw_new_ath_popup(&self, config: &AppConfig, frame: &mut Frame, price: f64) {
+        let sized_paragraph = SizedWrapper {
+            inner: Paragraph::new(vec![
+                Line::from(""),
+                Line::from(vec![Span::raw(self.format_price(config, price, 2))]),
+                Line::from(""),
+            ])
+            .centered(),
+            width: 25,
+            height: 3,
+        };
+
+        let popup = Popup::new(" New all time high! ", sized_paragraph)
+            .style(Style::new().fg(Color::White).bg(Color::Black));
+        frame.render_widget(&popup, frame.size());
+    }
+
+    pub fn draw_price_alert_popup(
+        &self,
+        config: &AppConfig,
+        frame: &mut Frame,
+        alert: PriceAlert,
+        price: f64,
+    ) {
+        let direction = match alert {
+            PriceAlert::Above(_) => "Above",
+            PriceAlert::Below(_) => "Below",
+        };
+
+        let sized_paragraph = SizedWrapper {
+            inner: Paragraph::new(vec![
+                Line::from(""),
+                Line::from(format!(
+                    "{} {}",
+                    direction,
+                    self.format_price(config, alert.level(), 2)
+                )),
+                Line::from(Span::styled(
+                    format!("now {}", self.format_price(config, price, 2)),
+                    Style::new().fg(Color::DarkGray),
+                )),
+                Line::from(""),
+            ])
+            .centered(),
+            width: 25,
+            height: 4,
+        };
+
+        let popup = Popup::new(" Price alert ", sized_paragraph)
+            .style(Style::new().fg(Color::White).bg(Color::Black));
+        frame.render_widget(&popup, frame.size());
+    }
+}
+
+impl Draw for PricePanel<'_> {
+    fn draw(
+        &self,
+        config: &AppConfig,
+        frame: &mut ratatui::Frame,
+        area: Rect,
+        options: PanelOptions,
+    ) {
+        let price = self.price;
+        let style = options.style.unwrap_or_default();
+        let locale = config.locale();
+
+        let mut price_block = options.block("Price").padding(Padding::top(1)).style(style);
+
+        // Where the price comes from and how fresh it is, in the corner
+        if !price.provider.is_empty() {
+            let health = price.health.health(config.stale_after(CHECK_INTERVAL));
+            price_block = price_block.title(
+                Title::from(Line::from(vec![
+                    Span::raw(format!(" {} ", price.provider)),
+                    Span::styled("● ", get_health_style(&health)),
+                ]))
+                .alignment(Alignment::Right),
+            );
+        }
 
         let price_block_area = price_block.inner(area);
         frame.render_widget(price_block, area);
 
-        if frame.size().width > 70 {
+        // The history may be kept longer for the moving averages than the sparkline spans
+        let sparkline_len = config.price_history_len();
+        let recent: Vec<f64> = price
+            .history
+            .iter()
+            .skip(price.history.len().saturating_sub(sparkline_len))
+            .copied()
+            .collect();
+        let recent_times: Vec<i64> = price
+            .history_times
+            .iter()
+            .skip(price.history_times.len().saturating_sub(sparkline_len))
+            .copied()
+            .collect();
+
+        // Direction of the last prices under the number, while there is room for both.
+        // Not drawn at all in the low profile.
+        let chart_height = match (recent.len(), price_block_area.height) {
+            _ if config.low_profile() => 0,
+            (0..=1, _) | (_, 0..=4) => 0,
+            (_, 5..=7) => 1,
+            _ => 2,
+        };
+        let volume_height = price.volume_24h.map_or(0, |_| 1);
+        let alerts_height = match self.alerts.is_empty() {
+            true => 0,
+            false => 1,
+        };
+        // Only once the history covers the whole window
+        let averages: Vec<(String, f64)> = config
+            .price_ma_windows()
+            .into_iter()
+            .filter_map(|(window, samples)| Some((window, price.moving_average(samples)?)))
+            .collect();
+        let averages_height = match averages.is_empty() {
+            true => 0,
+            false => 1,
+        };
+        let [price_block_area, chart_area, averages_area, volume_area, alerts_area, ath_area] =
+            Layout::vertical([
+                Constraint::Fill(1),
+                Constraint::Length(chart_height),
+                Constraint::Length(averages_height),
+                Constraint::Length(volume_height),
+                Constraint::Length(alerts_height),
+                Constraint::Length(1),
+            ])
+            .areas(price_block_area);
+
+        if chart_height > 0 {
+            // Sats per unit go down as the price goes up
+            let history: Vec<f64> = match self.in_sats {
+                true => recent.iter().map(|v| 100_000_000.0 / v).collect(),
+                false => recent,
+            };
+
+            let marks = sample_marks(self.annotations, &recent_times);
+
             frame.render_widget(
+                Sparkline::new(&history)
+                    .style(Style::new().fg(Color::DarkGray))
+                    .marks(&marks),
+                chart_area.inner(Margin::new(2, 0)),
+            );
+        }
+
+        if let (false, Some(current)) = (averages.is_empty(), price.last_price_in_currency) {
+            let mut spans = vec![Span::raw("MA")];
+
+            for (window, average) in averages {
+                // Arrows follow the number shown, sats per unit go down as the price goes up
+                let above = match self.in_sats {
+                    true => current < average,
+                    false => current > average,
+                };
+                let (arrow, color) = match above {
+                    true => ("▲", Color::Green),
+                    false => ("▼", Color::Red),
+                };
+                let value = match self.in_sats {
+                    true => price.format_sats(config, average),
+                    false => price.format_price(config, average, 0),
+                };
+
+                spans.push(Span::raw(format!("  {} {} ", window, value)));
+                spans.push(Span::styled(arrow, Style::new().fg(color)));
+            }
+
+            frame.render_widget(
+                Paragraph::new(Line::from(spans))
+                    .style(Style::default().fg(Color::DarkGray))
+                    .alignment(Alignment::Center),
+                averages_area,
+            );
+        }
+
+        if let Some(volume) = price.volume_24h {
+            frame.render_widget(
+                Paragraph::new(format!(
+                    "24h volume {} BTC",
+                    locale.format_number(volume, 0)
+                ))
+                .style(Style::default().fg(Color::DarkGray))
+                .alignment(Alignment::Center),
+                volume_area,
+            );
+        }
+
+        if !self.alerts.is_empty() {
+            let levels: Vec<String> = self
+                .alerts
+                .iter()
+                .map(|alert| {
+                    let arrow = match alert {
+                        PriceAlert::Above(_) => "▲",
+                        PriceAlert::Below(_) => "▼",
+                    };
+                    format!("{} {}", arrow, price.format_price(config, alert.level(), 0))
+                })
+                .collect();
+
+            frame.render_widget(
+                Paragraph::new(format!("Alert {}", levels.join("  ")))
+                    .style(Style::default().fg(Color::DarkGray))
+                    .alignment(Alignment::Center),
+                alerts_area,
+            );
+        }
+
+        if let Some(distance) = price.ath_distance() {
+            let ath_text = match distance {
+                distance if distance >= 0.0 => "At all time high".to_string(),
+                distance => format!("{}% from ATH", locale.format_number(distance * 100.0, 1)),
+            };
+
+            frame.render_widget(
+                Paragraph::new(ath_text)
+                    .style(Style::default().fg(Color::White))
+                    .alignment(Alignment::Center),
+                ath_area,
+            );
+        }
+
+        // Decimals are dropped in big text, there is no room for them
+        let big_text = match config.low_profile() {
+            true => None,
+            false => {
+                let text = match price.last_price_in_currency {
+                    Some(v) if self.in_sats => price.format_sats(config, v),
+                    Some(v) => price.format_price(config, v.trunc(), 0),
+                    None => "...".to_string(),
+                };
+
+                fitting_pixel_size(config, &text, price_block_area).map(|size| (text, size))
+            }
+        };
+
+        match big_text {
+            Some((text, size)) => frame.render_widget(
                 BigText::builder()
                     .alignment(Alignment::Center)
-                    .pixel_size(PixelSize::Sextant)
+                    .pixel_size(size)
                     .style(style)
-                    .lines(lines)
+                    .lines(vec![text.into()])
                     .build()
                     .unwrap(),
                 price_block_area,
-            );
-        } else {
-            frame.render_widget(
-                Paragraph::new(lines)
-                    .style(Style::default().fg(Color::White))
-                    .alignment(Alignment::Center),
+            ),
+            None => frame.render_widget(
+                Paragraph::new(match price.last_price_in_currency {
+                    Some(v) if self.in_sats => price.format_sats(config, v),
+                    Some(v) => price.format_price(config, v, 2),
+                    None => "...".to_string(),
+                })
+                .style(Style::default().fg(Color::White))
+                .alignment(Alignment::Center),
                 price_block_area,
-            );
+            ),
+        }
+
+        if let (Some(time), Some(ath)) = (price.ath_reached_at, price.ath) {
+            if time.elapsed().as_secs() < 15 {
+                price.draw_new_ath_popup(config, frame, ath);
+            }
         }
     }
 }
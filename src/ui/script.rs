@@ -0,0 +1,47 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Padding, Paragraph, Wrap},
+};
+
+use crate::config::AppConfig;
+use crate::script::ScriptState;
+
+use super::{Draw, PanelOptions};
+
+impl Draw for ScriptState {
+    fn draw(
+        &self,
+        config: &AppConfig,
+        frame: &mut ratatui::Frame,
+        area: Rect,
+        options: PanelOptions,
+    ) {
+        let style = options.style.unwrap_or_default();
+
+        let mut lines: Vec<Line> = vec![];
+
+        // The previous lines stay below the error, a failing run does not blank the panel
+        if let Some(error) = &self.error {
+            lines.push(Line::styled(error.as_str(), Style::new().fg(Color::Red)));
+        }
+
+        lines.extend(
+            self.lines
+                .iter()
+                .map(|line| Line::styled(line.as_str(), Style::new().fg(Color::White))),
+        );
+
+        let script_block = Paragraph::new(lines)
+            .block(
+                options
+                    .block(config.script.title.as_str())
+                    .padding(Padding::left(1)),
+            )
+            .wrap(Wrap { trim: true })
+            .style(style);
+
+        frame.render_widget(script_block, area);
+    }
+}
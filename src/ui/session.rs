@@ -0,0 +1,59 @@
+use ratatui::{
+    layout::Rect,
+    prelude::Stylize,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Padding, Paragraph},
+};
+
+use crate::config::AppConfig;
+use crate::format::format_elapsed;
+use crate::session::SessionState;
+
+use super::{Draw, PanelOptions};
+
+impl Draw for SessionState {
+    fn draw(
+        &self,
+        config: &AppConfig,
+        frame: &mut ratatui::Frame,
+        area: Rect,
+        options: PanelOptions,
+    ) {
+        let style = options.style.unwrap_or_default();
+        let locale = config.locale();
+
+        let price_range = match (self.price_low, self.price_high) {
+            (Some(low), Some(high)) => [
+                locale.format_number(low.trunc(), 0),
+                locale.format_number(high.trunc(), 0),
+            ]
+            .join(" - "),
+            _ => "...".to_string(),
+        };
+
+        let lines = vec![
+            get_session_line(
+                "Uptime",
+                format_elapsed(self.started.elapsed().as_secs() as i64),
+            ),
+            get_session_line("Blocks", self.blocks_seen.to_string()),
+            get_session_line("Price", price_range),
+            get_session_line("Reconnects", self.reconnects.to_string()),
+        ];
+
+        let session_block = Paragraph::new(lines)
+            .block(options.block("Session").padding(Padding::left(1)))
+            .style(style);
+
+        frame.render_widget(session_block, area);
+    }
+}
+
+fn get_session_line<'a>(name: &'a str, value: String) -> Line<'a> {
+    Line::from(vec![
+        Span::raw(name),
+        Span::raw(": "),
+        Span::styled(value, Style::new().white().italic()),
+    ])
+}
@@ -0,0 +1,48 @@
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use tui_popup::{Popup, SizedWrapper};
+
+use crate::config::AppConfig;
+use crate::format::format_elapsed;
+use crate::session::SinceLastSeen;
+
+use super::format::format_change;
+
+impl SinceLastSeen {
+    pub fn draw(&self, config: &AppConfig, frame: &mut Frame) {
+        let locale = config.locale();
+
+        let blocks = match self.blocks {
+            Some(blocks) => blocks.to_string(),
+            None => "-".to_string(),
+        };
+
+        let price = match self.price_change {
+            Some(change) => format_change(&locale, change),
+            None => Span::raw("-"),
+        };
+
+        let lines = vec![
+            Line::from(format!("{} ago", format_elapsed(self.elapsed))),
+            Line::from(""),
+            Line::from(format!("Blocks mined: {}", blocks)),
+            Line::from(vec![Span::raw("Price: "), price]),
+            Line::from(""),
+            Line::from("[Esc] close"),
+        ];
+
+        let sized_paragraph = SizedWrapper {
+            inner: Paragraph::new(lines).centered(),
+            width: 30,
+            height: 6,
+        };
+
+        let popup = Popup::new(" Since you last looked ", sized_paragraph)
+            .style(Style::new().fg(Color::White).bg(Color::Black));
+        frame.render_widget(&popup, frame.size());
+    }
+}
@@ -0,0 +1,122 @@
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use tui_popup::{Popup, SizedWrapper};
+
+use crate::config::AppConfig;
+use crate::node::providers::target;
+
+/// What btcmon is about to watch and with which settings, shown at startup so a
+/// misconfiguration is obvious before it turns into an empty panel.
+pub struct Splash<'a> {
+    pub warnings: &'a [String],
+}
+
+impl Splash<'_> {
+    fn summary(config: &AppConfig) -> Vec<(&'static str, String)> {
+        let config_files = match config.config_files.is_empty() {
+            true => "none found, using defaults".to_string(),
+            false => config
+                .config_files
+                .iter()
+                .map(|file| file.display().to_string())
+                .collect::<Vec<String>>()
+                .join(", "),
+        };
+
+        let provider = match config.node.provider.as_str() {
+            "electrum" => "Electrum",
+            "custom" => config.custom_node.name.as_str(),
+            _ => "Bitcoin Core",
+        };
+
+        let mut node = format!("{} at {}", provider, target(config));
+        if config.node.provider == "bitcoin_core" && config.bitcoin_core.lightweight {
+            node.push_str(", lightweight");
+        }
+        if config.node.share {
+            node.push_str(", shared");
+        }
+
+        let panels: Vec<&str> = [
+            ("node", true),
+            ("peers", config.peers.enabled),
+            ("host", config.host.enabled),
+            ("fees", config.fees.enabled),
+            ("mempool", config.fees.enabled && config.mempool.enabled),
+            (
+                "next block",
+                config.fees.enabled && config.mempool.next_block,
+            ),
+            ("price", config.price.enabled),
+            ("session", config.session.enabled),
+        ]
+        .into_iter()
+        .filter_map(|(panel, enabled)| enabled.then_some(panel))
+        .collect();
+
+        let notify = match config.notify.url.is_empty() {
+            true => "off".to_string(),
+            false => format!("{} at {}", config.notify.service, config.notify.url),
+        };
+
+        vec![
+            ("Config", config_files),
+            ("Node", node),
+            ("Panels", panels.join(", ")),
+            ("Price", config.price_currency_codes().join(", ")),
+            ("Badges", config.badges.len().to_string()),
+            ("Pushes", notify),
+        ]
+    }
+
+    pub fn draw(&self, config: &AppConfig, frame: &mut Frame) {
+        let mut lines: Vec<Line> = Splash::summary(config)
+            .into_iter()
+            .map(|(name, value)| {
+                Line::from(vec![
+                    Span::styled(format!("{:<8}", name), Style::new().fg(Color::DarkGray)),
+                    Span::raw(value),
+                ])
+            })
+            .collect();
+
+        lines.push(Line::from(""));
+
+        if self.warnings.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "Configuration looks good",
+                Style::new().fg(Color::Green),
+            )));
+        }
+
+        for warning in self.warnings.iter() {
+            lines.push(Line::from(Span::styled(
+                format!("! {}", warning),
+                Style::new().fg(Color::Yellow),
+            )));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from("[any key] continue").centered());
+
+        let width = lines.iter().map(|line| line.width()).max().unwrap_or(0);
+        let height = lines.len();
+
+        let sized_paragraph = SizedWrapper {
+            inner: Paragraph::new(lines),
+            width: width
+                .max(40)
+                .min(frame.size().width.saturating_sub(4) as usize),
+            height,
+        };
+
+        let title = format!(" btcmon {} ", env!("CARGO_PKG_VERSION"));
+        let popup = Popup::new(title, sized_paragraph)
+            .style(Style::new().fg(Color::White).bg(Color::Black));
+        frame.render_widget(&popup, frame.size());
+    }
+}
@@ -0,0 +1,3 @@
+mod sparkline;
+
+pub use sparkline::{Sparkline, SparklineSymbols};
@@ -0,0 +1,161 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::Widget,
+};
+
+const BRAILLE_BLANK: u32 = 0x2800;
+
+/// Braille dots of a cell column, from the bottom row to the top one.
+const BRAILLE_LEFT_DOTS: [u32; 4] = [0x40, 0x04, 0x02, 0x01];
+const BRAILLE_RIGHT_DOTS: [u32; 4] = [0x80, 0x20, 0x10, 0x08];
+
+const BLOCKS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SparklineSymbols {
+    /// Two samples per cell and four levels per row.
+    #[default]
+    Braille,
+    /// One sample per cell and eight levels per row.
+    Block,
+}
+
+/// Filled sparkline of the most recent samples that fit in the area.
+///
+/// Values are scaled between the visible minimum and maximum unless
+/// explicit bounds are given. Marked samples are drawn in yellow.
+#[derive(Debug, Clone, Default)]
+pub struct Sparkline<'a> {
+    data: &'a [f64],
+    style: Style,
+    symbols: SparklineSymbols,
+    min: Option<f64>,
+    max: Option<f64>,
+    marks: &'a [usize],
+}
+
+impl<'a> Sparkline<'a> {
+    pub fn new(data: &'a [f64]) -> Self {
+        Self {
+            data,
+            ..Self::default()
+        }
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn symbols(mut self, symbols: SparklineSymbols) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    pub fn min(mut self, min: f64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    pub fn max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Indexes in the data of the samples to mark.
+    pub fn marks(mut self, marks: &'a [usize]) -> Self {
+        self.marks = marks;
+        self
+    }
+
+    fn samples_per_cell(&self) -> usize {
+        match self.symbols {
+            SparklineSymbols::Braille => 2,
+            SparklineSymbols::Block => 1,
+        }
+    }
+
+    fn levels_per_row(&self) -> u64 {
+        match self.symbols {
+            SparklineSymbols::Braille => 4,
+            SparklineSymbols::Block => 8,
+        }
+    }
+
+    /// Scales the visible samples to a level between 1 and `total_levels`.
+    fn levels(&self, data: &[f64], total_levels: u64) -> Vec<u64> {
+        let min = self
+            .min
+            .unwrap_or_else(|| data.iter().cloned().fold(f64::INFINITY, f64::min));
+        let max = self
+            .max
+            .unwrap_or_else(|| data.iter().cloned().fold(f64::NEG_INFINITY, f64::max));
+        let range = max - min;
+
+        data.iter()
+            .map(|value| {
+                if range <= 0.0 || !range.is_finite() {
+                    return total_levels / 2;
+                }
+
+                let ratio = ((value - min) / range).clamp(0.0, 1.0);
+                1 + (ratio * (total_levels - 1) as f64).round() as u64
+            })
+            .collect()
+    }
+}
+
+impl Widget for Sparkline<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() || self.data.is_empty() {
+            return;
+        }
+
+        let samples_per_cell = self.samples_per_cell();
+        let levels_per_row = self.levels_per_row();
+        let capacity = area.width as usize * samples_per_cell;
+        let hidden = self.data.len().saturating_sub(capacity);
+        let data = &self.data[hidden..];
+        let levels = self.levels(data, area.height as u64 * levels_per_row);
+
+        // Right align the samples so the latest one is always on the edge
+        let first_column = area.width as usize - levels.len().div_ceil(samples_per_cell);
+
+        for (i, chunk) in levels.chunks(samples_per_cell).enumerate() {
+            let x = area.left() + (first_column + i) as u16;
+            let marked = self
+                .marks
+                .iter()
+                .any(|mark| mark.checked_sub(hidden).map(|m| m / samples_per_cell) == Some(i));
+            let style = match marked {
+                true => self.style.fg(Color::Yellow),
+                false => self.style,
+            };
+
+            for row in 0..area.height {
+                let y = area.bottom() - 1 - row;
+                let row_floor = row as u64 * levels_per_row;
+                let filled = |level: u64| level.saturating_sub(row_floor).min(levels_per_row);
+
+                let symbol = match self.symbols {
+                    SparklineSymbols::Braille => {
+                        let dots = chunk.iter().enumerate().fold(0, |dots, (side, level)| {
+                            let column = if side == 0 {
+                                BRAILLE_LEFT_DOTS
+                            } else {
+                                BRAILLE_RIGHT_DOTS
+                            };
+                            dots | column[..filled(*level) as usize].iter().sum::<u32>()
+                        });
+                        char::from_u32(BRAILLE_BLANK + dots).unwrap_or(' ')
+                    }
+                    SparklineSymbols::Block => BLOCKS[filled(chunk[0]) as usize],
+                };
+
+                buf.get_mut(x, y).set_char(symbol).set_style(style);
+            }
+        }
+    }
+}